@@ -0,0 +1 @@
+ALTER TABLE match_events ADD COLUMN IF NOT EXISTS is_bisque BOOLEAN NOT NULL DEFAULT false;
@@ -0,0 +1,2 @@
+ALTER TABLE matches ADD COLUMN IF NOT EXISTS time_called_at TIMESTAMPTZ;
+ALTER TABLE matches ADD COLUMN IF NOT EXISTS points_before_time_called INTEGER;
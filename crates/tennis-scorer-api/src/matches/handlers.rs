@@ -1,6 +1,10 @@
+use std::sync::Arc;
+use std::time::SystemTime;
+
 use axum::Json;
 use axum::extract::{Path, Query, State};
 use axum::http::StatusCode;
+use tennis_scorer::{MatchState, MatchWithHistory, Player, ScoreLine, ScoringRules, StartingScore};
 use uuid::Uuid;
 
 use super::models::*;
@@ -56,11 +60,26 @@ async fn create_match_inner(
         return Ok((StatusCode::OK, Json(serde_json::json!({"id": existing}))));
     }
 
+    if let Some(called) = req.time_called
+        && !(0..=req.events.len() as i32).contains(&called.points_before)
+    {
+        return Err(AppError::Unprocessable(format!(
+            "Time called after {} points, but the match has {}",
+            called.points_before,
+            req.events.len()
+        )));
+    }
+
+    let score = match &req.score {
+        Some(text) => Some(normalize_score(text, req.player1_sets, req.player2_sets)?),
+        None => score_from_events(&req),
+    };
+
     let mut tx = state.pool.begin().await?;
 
     let match_id = sqlx::query_scalar::<_, Uuid>(
-        "INSERT INTO matches (user_id, client_id, match_type, config, winner, player1_sets, player2_sets, started_at, ended_at)
-         VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+        "INSERT INTO matches (user_id, client_id, match_type, config, winner, player1_sets, player2_sets, score, started_at, ended_at, time_called_at, points_before_time_called)
+         VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
          RETURNING id"
     )
     .bind(user_id)
@@ -70,20 +89,24 @@ async fn create_match_inner(
     .bind(req.winner)
     .bind(req.player1_sets)
     .bind(req.player2_sets)
+    .bind(score)
     .bind(req.started_at)
     .bind(req.ended_at)
+    .bind(req.time_called.map(|called| called.at))
+    .bind(req.time_called.map(|called| called.points_before))
     .fetch_one(&mut *tx)
     .await?;
 
     for event in &req.events {
         sqlx::query(
-            "INSERT INTO match_events (match_id, point_number, player, timestamp)
-             VALUES ($1, $2, $3, $4)",
+            "INSERT INTO match_events (match_id, point_number, player, timestamp, is_bisque)
+             VALUES ($1, $2, $3, $4, $5)",
         )
         .bind(match_id)
         .bind(event.point_number)
         .bind(event.player)
         .bind(event.timestamp)
+        .bind(event.is_bisque)
         .execute(&mut *tx)
         .await?;
     }
@@ -96,6 +119,95 @@ async fn create_match_inner(
     ))
 }
 
+/// Parse a client-supplied score and check it agrees with the set counts.
+fn normalize_score(text: &str, player1_sets: i16, player2_sets: i16) -> Result<String, AppError> {
+    let line: ScoreLine = text
+        .parse()
+        .map_err(|e| AppError::Unprocessable(format!("Invalid score: {e}")))?;
+    let (p1, p2) = line.sets_won();
+    if i16::from(p1) != player1_sets || i16::from(p2) != player2_sets {
+        return Err(AppError::Unprocessable(format!(
+            "Score {line} does not match sets {player1_sets}-{player2_sets}"
+        )));
+    }
+    Ok(line.to_string())
+}
+
+/// Replay the events to derive the final score. Returns `None` when the
+/// config is not a core `MatchConfig` or the events do not finish the match.
+fn score_from_events(req: &CreateMatchRequest) -> Option<String> {
+    let stored: StoredConfig = serde_json::from_value(req.config.clone()).ok()?;
+    let rules = stored.rules();
+    let events: Vec<(Player, SystemTime)> = req
+        .events
+        .iter()
+        .map(|event| {
+            (
+                player_from_db(event.player),
+                SystemTime::from(event.timestamp),
+            )
+        })
+        .collect();
+    let bisques: Vec<bool> = req.events.iter().map(|event| event.is_bisque).collect();
+    let start = MatchState::new_with(stored.config, rules.as_ref());
+    let recording = rebuild(start, &events, &bisques, req.time_called, rules).ok()?;
+    recording
+        .current()
+        .winner()
+        .map(|_| ScoreLine::from_recording(&recording).to_string())
+}
+
+fn player_from_db(player: i16) -> Player {
+    if player == 1 {
+        Player::Player1
+    } else {
+        Player::Player2
+    }
+}
+
+/// Replay stored points from `start` under `rules`, claiming bisques and
+/// calling time where they were.
+fn rebuild(
+    start: MatchState,
+    events: &[(Player, SystemTime)],
+    bisques: &[bool],
+    time_called: Option<TimeCalled>,
+    rules: Arc<dyn ScoringRules>,
+) -> Result<MatchWithHistory, AppError> {
+    let points_before = time_called.map(|called| {
+        usize::try_from(called.points_before)
+            .unwrap_or(0)
+            .min(events.len())
+    });
+    let mut recording = MatchWithHistory::with_rules(start, rules);
+    for (i, &(scorer, timestamp)) in events.iter().enumerate() {
+        if let Some(called) = time_called
+            && points_before == Some(i)
+        {
+            recording = recording
+                .end_on_time_at(called.at.into())
+                .map_err(unprocessable)?;
+        }
+        recording = if bisques.get(i).copied().unwrap_or(false) {
+            recording
+                .claim_bisque_with_clock(scorer, &|| timestamp)
+                .map_err(unprocessable)?
+        } else {
+            recording
+                .score_point_at(scorer, timestamp)
+                .map_err(unprocessable)?
+        };
+    }
+    if let Some(called) = time_called
+        && points_before == Some(events.len())
+    {
+        recording = recording
+            .end_on_time_at(called.at.into())
+            .map_err(unprocessable)?;
+    }
+    Ok(recording)
+}
+
 pub async fn list_matches(
     auth: AuthUser,
     State(state): State<AppState>,
@@ -109,8 +221,8 @@ pub async fn list_matches(
         .fetch_one(&state.pool)
         .await?;
 
-    let rows = sqlx::query_as::<_, (Uuid, Option<Uuid>, String, serde_json::Value, i16, i16, i16, chrono::DateTime<chrono::Utc>, chrono::DateTime<chrono::Utc>, chrono::DateTime<chrono::Utc>)>(
-        "SELECT id, client_id, match_type, config, winner, player1_sets, player2_sets, started_at, ended_at, created_at
+    let rows = sqlx::query_as::<_, (Uuid, Option<Uuid>, String, serde_json::Value, i16, i16, i16, Option<String>, chrono::DateTime<chrono::Utc>, chrono::DateTime<chrono::Utc>, chrono::DateTime<chrono::Utc>, Option<chrono::DateTime<chrono::Utc>>, Option<i32>)>(
+        "SELECT id, client_id, match_type, config, winner, player1_sets, player2_sets, score, started_at, ended_at, created_at, time_called_at, points_before_time_called
          FROM matches WHERE user_id = $1
          ORDER BY started_at DESC
          LIMIT $2 OFFSET $3"
@@ -131,9 +243,11 @@ pub async fn list_matches(
             winner: r.4,
             player1_sets: r.5,
             player2_sets: r.6,
-            started_at: r.7,
-            ended_at: r.8,
-            created_at: r.9,
+            score: r.7,
+            started_at: r.8,
+            ended_at: r.9,
+            created_at: r.10,
+            time_called: time_called(r.11, r.12),
             events: None,
         })
         .collect();
@@ -146,8 +260,8 @@ pub async fn get_match(
     State(state): State<AppState>,
     Path(match_id): Path<Uuid>,
 ) -> Result<Json<MatchResponse>, AppError> {
-    let row = sqlx::query_as::<_, (Uuid, Option<Uuid>, String, serde_json::Value, i16, i16, i16, chrono::DateTime<chrono::Utc>, chrono::DateTime<chrono::Utc>, chrono::DateTime<chrono::Utc>)>(
-        "SELECT id, client_id, match_type, config, winner, player1_sets, player2_sets, started_at, ended_at, created_at
+    let row = sqlx::query_as::<_, (Uuid, Option<Uuid>, String, serde_json::Value, i16, i16, i16, Option<String>, chrono::DateTime<chrono::Utc>, chrono::DateTime<chrono::Utc>, chrono::DateTime<chrono::Utc>, Option<chrono::DateTime<chrono::Utc>>, Option<i32>)>(
+        "SELECT id, client_id, match_type, config, winner, player1_sets, player2_sets, score, started_at, ended_at, created_at, time_called_at, points_before_time_called
          FROM matches WHERE id = $1 AND user_id = $2"
     )
     .bind(match_id)
@@ -156,8 +270,8 @@ pub async fn get_match(
     .await?
     .ok_or_else(|| AppError::NotFound("Match not found".to_string()))?;
 
-    let events = sqlx::query_as::<_, (i32, i16, chrono::DateTime<chrono::Utc>)>(
-        "SELECT point_number, player, timestamp FROM match_events
+    let events = sqlx::query_as::<_, (i32, i16, chrono::DateTime<chrono::Utc>, bool)>(
+        "SELECT point_number, player, timestamp, is_bisque FROM match_events
          WHERE match_id = $1 ORDER BY point_number",
     )
     .bind(match_id)
@@ -170,6 +284,7 @@ pub async fn get_match(
             point_number: e.0,
             player: e.1,
             timestamp: e.2,
+            is_bisque: e.3,
         })
         .collect();
 
@@ -181,13 +296,25 @@ pub async fn get_match(
         winner: row.4,
         player1_sets: row.5,
         player2_sets: row.6,
-        started_at: row.7,
-        ended_at: row.8,
-        created_at: row.9,
+        score: row.7,
+        started_at: row.8,
+        ended_at: row.9,
+        created_at: row.10,
+        time_called: time_called(row.11, row.12),
         events: Some(event_responses),
     }))
 }
 
+fn time_called(
+    at: Option<chrono::DateTime<chrono::Utc>>,
+    points_before: Option<i32>,
+) -> Option<TimeCalled> {
+    Some(TimeCalled {
+        at: at?,
+        points_before: points_before?,
+    })
+}
+
 pub async fn delete_match(
     auth: AuthUser,
     State(state): State<AppState>,
@@ -205,3 +332,208 @@ pub async fn delete_match(
 
     Ok(StatusCode::NO_CONTENT)
 }
+
+fn unprocessable(e: impl std::fmt::Display) -> AppError {
+    AppError::Unprocessable(e.to_string())
+}
+
+/// A stored match loaded for replay.
+struct Recording {
+    config: StoredConfig,
+    winner: i16,
+    player1_sets: i16,
+    player2_sets: i16,
+    ended_at: chrono::DateTime<chrono::Utc>,
+    events: Vec<(Player, SystemTime)>,
+    /// Whether each of `events` was claimed as a bisque.
+    bisques: Vec<bool>,
+    time_called: Option<TimeCalled>,
+    last_point_number: i32,
+}
+
+async fn load_recording(
+    conn: &mut sqlx::PgConnection,
+    user_id: Uuid,
+    match_id: Uuid,
+) -> Result<Recording, AppError> {
+    let row = sqlx::query_as::<
+        _,
+        (
+            serde_json::Value,
+            i16,
+            i16,
+            i16,
+            chrono::DateTime<chrono::Utc>,
+            Option<chrono::DateTime<chrono::Utc>>,
+            Option<i32>,
+        ),
+    >(
+        "SELECT config, winner, player1_sets, player2_sets, ended_at, time_called_at, points_before_time_called
+         FROM matches WHERE id = $1 AND user_id = $2",
+    )
+    .bind(match_id)
+    .bind(user_id)
+    .fetch_optional(&mut *conn)
+    .await?
+    .ok_or_else(|| AppError::NotFound("Match not found".to_string()))?;
+
+    let config: StoredConfig = serde_json::from_value(row.0)
+        .map_err(|e| AppError::Unprocessable(format!("Invalid config: {e}")))?;
+
+    let events = sqlx::query_as::<_, (i32, i16, chrono::DateTime<chrono::Utc>, bool)>(
+        "SELECT point_number, player, timestamp, is_bisque FROM match_events
+         WHERE match_id = $1 ORDER BY point_number",
+    )
+    .bind(match_id)
+    .fetch_all(&mut *conn)
+    .await?;
+
+    let last_point_number = events.last().map_or(0, |e| e.0);
+    let bisques = events.iter().map(|e| e.3).collect();
+    let events = events
+        .into_iter()
+        .map(|(_, player, ts, _)| (player_from_db(player), SystemTime::from(ts)))
+        .collect();
+
+    Ok(Recording {
+        config,
+        winner: row.1,
+        player1_sets: row.2,
+        player2_sets: row.3,
+        ended_at: row.4,
+        events,
+        bisques,
+        time_called: time_called(row.5, row.6),
+        last_point_number,
+    })
+}
+
+/// Merge a continuation recorded on another device into this match.
+///
+/// The continuation's points are replayed from the score this match's own
+/// points reach, and the replay must finish with the result stored on the
+/// continuation. On success the continuation's points are appended to this
+/// match and the continuation is deleted.
+pub async fn merge_matches(
+    auth: AuthUser,
+    State(state): State<AppState>,
+    Path(match_id): Path<Uuid>,
+    Json(req): Json<MergeMatchRequest>,
+) -> Result<Json<serde_json::Value>, AppError> {
+    if req.continuation_id == match_id {
+        return Err(AppError::BadRequest(
+            "Cannot merge a match into itself".to_string(),
+        ));
+    }
+
+    let mut tx = state.pool.begin().await?;
+
+    // Lock both matches, in a fixed order, so neither can be scored or
+    // merged elsewhere between reading its points and writing the result
+    let locked = sqlx::query_scalar::<_, Uuid>(
+        "SELECT id FROM matches WHERE id = ANY($1) AND user_id = $2
+         ORDER BY id FOR UPDATE",
+    )
+    .bind([match_id, req.continuation_id])
+    .bind(auth.user_id)
+    .fetch_all(&mut *tx)
+    .await?;
+    if locked.len() != 2 {
+        return Err(AppError::NotFound("Match not found".to_string()));
+    }
+
+    let first = load_recording(&mut tx, auth.user_id, match_id).await?;
+    let second = load_recording(&mut tx, auth.user_id, req.continuation_id).await?;
+
+    let rules = first.config.rules();
+    let recorded = rebuild(
+        MatchState::new_with(first.config.config, rules.as_ref()),
+        &first.events,
+        &first.bisques,
+        first.time_called,
+        rules,
+    )?;
+    let start = StartingScore::from_state(recorded.current())
+        .ok_or_else(|| AppError::Unprocessable("Match is already complete".to_string()))?;
+    let rules = second.config.rules();
+    let start = MatchState::from_score_with(second.config.config, &start, rules.as_ref())
+        .map_err(unprocessable)?;
+    let continuation = rebuild(
+        start,
+        &second.events,
+        &second.bisques,
+        second.time_called,
+        rules,
+    )?;
+    let merged = recorded.stitch(&continuation).map_err(unprocessable)?;
+
+    let MatchState::Completed {
+        winner,
+        player1_sets,
+        player2_sets,
+        ..
+    } = merged.current()
+    else {
+        return Err(AppError::Unprocessable(
+            "Merged points do not finish the match".to_string(),
+        ));
+    };
+    let winner: i16 = if *winner == Player::Player1 { 1 } else { 2 };
+    let (player1_sets, player2_sets) = (i16::from(*player1_sets), i16::from(*player2_sets));
+    if (winner, player1_sets, player2_sets)
+        != (second.winner, second.player1_sets, second.player2_sets)
+    {
+        return Err(AppError::Unprocessable(format!(
+            "Merged points end {player1_sets}-{player2_sets} but the continuation records {}-{}",
+            second.player1_sets, second.player2_sets
+        )));
+    }
+    let score = ScoreLine::from_recording(&merged).to_string();
+    let merged_time_called = merged.time_called();
+
+    for (i, ((player, timestamp), is_bisque)) in
+        second.events.iter().zip(&second.bisques).enumerate()
+    {
+        sqlx::query(
+            "INSERT INTO match_events (match_id, point_number, player, timestamp, is_bisque)
+             VALUES ($1, $2, $3, $4, $5)",
+        )
+        .bind(match_id)
+        .bind(first.last_point_number + i as i32 + 1)
+        .bind(if *player == Player::Player1 {
+            1i16
+        } else {
+            2i16
+        })
+        .bind(chrono::DateTime::<chrono::Utc>::from(*timestamp))
+        .bind(is_bisque)
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    sqlx::query(
+        "UPDATE matches
+         SET winner = $1, player1_sets = $2, player2_sets = $3, score = $4, ended_at = $5,
+             time_called_at = $6, points_before_time_called = $7
+         WHERE id = $8",
+    )
+    .bind(winner)
+    .bind(player1_sets)
+    .bind(player2_sets)
+    .bind(&score)
+    .bind(second.ended_at)
+    .bind(merged_time_called.map(|called| chrono::DateTime::<chrono::Utc>::from(called.at)))
+    .bind(merged_time_called.map(|called| called.points_before as i32))
+    .bind(match_id)
+    .execute(&mut *tx)
+    .await?;
+
+    sqlx::query("DELETE FROM matches WHERE id = $1")
+        .bind(req.continuation_id)
+        .execute(&mut *tx)
+        .await?;
+
+    tx.commit().await?;
+
+    Ok(Json(serde_json::json!({"id": match_id, "score": score})))
+}
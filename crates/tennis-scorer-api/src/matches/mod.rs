@@ -17,6 +17,7 @@ pub fn routes() -> Router<AppState> {
             "/matches/{id}",
             get(handlers::get_match).delete(handlers::delete_match),
         )
+        .route("/matches/{id}/merge", post(handlers::merge_matches))
         // Debug: no-auth endpoint for local testing
         .merge(debug_routes())
 }
@@ -1,7 +1,36 @@
+use std::sync::Arc;
+
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use tennis_scorer::{
+    CumulativeGamesRules, MatchConfig, ScoringRules, StandardRules, TiebreakOnlyRules,
+};
 use uuid::Uuid;
 
+/// A match's stored config: the core `MatchConfig` plus the format it was
+/// scored under.
+#[derive(Deserialize)]
+pub struct StoredConfig {
+    #[serde(flatten)]
+    pub config: MatchConfig,
+    /// Decided by total games rather than sets (team tennis).
+    #[serde(default)]
+    pub cumulative_games: Option<CumulativeGamesRules>,
+    /// Every set is a single tiebreak.
+    #[serde(default)]
+    pub tiebreak_only: bool,
+}
+
+impl StoredConfig {
+    pub fn rules(&self) -> Arc<dyn ScoringRules> {
+        match self.cumulative_games {
+            Some(cumulative) => Arc::new(cumulative),
+            None if self.tiebreak_only => Arc::new(TiebreakOnlyRules),
+            None => Arc::new(StandardRules),
+        }
+    }
+}
+
 #[derive(Deserialize)]
 pub struct CreateMatchRequest {
     pub client_id: Option<Uuid>,
@@ -12,14 +41,37 @@ pub struct CreateMatchRequest {
     pub player2_sets: i16,
     pub started_at: DateTime<Utc>,
     pub ended_at: DateTime<Utc>,
+    /// Final score such as "6-4 3-6 7-6(5)". Derived from the events when omitted.
+    #[serde(default)]
+    pub score: Option<String>,
+    /// When time was called on a timed match, if it was.
+    #[serde(default)]
+    pub time_called: Option<TimeCalled>,
     pub events: Vec<CreateMatchEvent>,
 }
 
+/// Time called on a timed match. Points after the first `points_before`
+/// were played in the sudden-death point or tiebreak.
+#[derive(Serialize, Deserialize, Clone, Copy)]
+pub struct TimeCalled {
+    pub at: DateTime<Utc>,
+    pub points_before: i32,
+}
+
 #[derive(Deserialize)]
 pub struct CreateMatchEvent {
     pub point_number: i32,
     pub player: i16,
     pub timestamp: DateTime<Utc>,
+    /// Claimed from a handicap bisque rather than played.
+    #[serde(default)]
+    pub is_bisque: bool,
+}
+
+#[derive(Deserialize)]
+pub struct MergeMatchRequest {
+    /// Match recorded from the score the target match stopped at.
+    pub continuation_id: Uuid,
 }
 
 #[derive(Serialize)]
@@ -31,9 +83,11 @@ pub struct MatchResponse {
     pub winner: i16,
     pub player1_sets: i16,
     pub player2_sets: i16,
+    pub score: Option<String>,
     pub started_at: DateTime<Utc>,
     pub ended_at: DateTime<Utc>,
     pub created_at: DateTime<Utc>,
+    pub time_called: Option<TimeCalled>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub events: Option<Vec<MatchEventResponse>>,
 }
@@ -43,6 +97,7 @@ pub struct MatchEventResponse {
     pub point_number: i32,
     pub player: i16,
     pub timestamp: DateTime<Utc>,
+    pub is_bisque: bool,
 }
 
 #[derive(Serialize)]
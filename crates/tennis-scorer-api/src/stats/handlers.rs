@@ -1,16 +1,20 @@
 use std::time::SystemTime;
 
 use axum::Json;
-use axum::extract::{Path, State};
-use serde::Serialize;
+use axum::extract::{Path, Query, State};
+use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-use tennis_scorer::analysis::{self, MatchAnalysis, MomentumData, PaceData};
-use tennis_scorer::{MatchConfig, Player};
+use tennis_scorer::analysis::{
+    self, GameLogEntry, MatchAnalysis, MatchReport, MomentumData, MomentumKind, PaceData,
+    PointContext,
+};
+use tennis_scorer::{MatchConfig, MatchState, Player};
 
 use crate::AppState;
 use crate::auth::middleware::AuthUser;
 use crate::error::AppError;
+use crate::matches::models::StoredConfig;
 
 #[derive(Serialize)]
 pub struct StatsSummary {
@@ -89,14 +93,16 @@ pub async fn summary(
 
 // --- Per-match analysis helpers ---
 
-async fn load_match_analysis_data(
+/// Load a match's config and replay its point events, flagging the points
+/// claimed as bisques. Scoring stops where time was called.
+async fn load_match_contexts(
     pool: &sqlx::PgPool,
     user_id: Uuid,
     match_id: Uuid,
-) -> Result<(MatchConfig, Vec<(Player, SystemTime)>), AppError> {
+) -> Result<(MatchConfig, Vec<PointContext>), AppError> {
     // Load match config (JSON) and verify ownership
-    let row = sqlx::query_as::<_, (serde_json::Value,)>(
-        "SELECT config FROM matches WHERE id = $1 AND user_id = $2",
+    let row = sqlx::query_as::<_, (serde_json::Value, Option<i32>)>(
+        "SELECT config, points_before_time_called FROM matches WHERE id = $1 AND user_id = $2",
     )
     .bind(match_id)
     .bind(user_id)
@@ -104,12 +110,14 @@ async fn load_match_analysis_data(
     .await?
     .ok_or_else(|| AppError::NotFound("Match not found".to_string()))?;
 
-    let config: MatchConfig = serde_json::from_value(row.0)
+    let stored: StoredConfig = serde_json::from_value(row.0)
         .map_err(|e| AppError::Internal(format!("Invalid config: {e}")))?;
+    let rules = stored.rules();
+    let config = stored.config;
 
     // Load point events
-    let events = sqlx::query_as::<_, (i16, chrono::DateTime<chrono::Utc>)>(
-        "SELECT player, timestamp FROM match_events
+    let events = sqlx::query_as::<_, (i16, chrono::DateTime<chrono::Utc>, bool)>(
+        "SELECT player, timestamp, is_bisque FROM match_events
          WHERE match_id = $1 ORDER BY point_number",
     )
     .bind(match_id)
@@ -117,8 +125,8 @@ async fn load_match_analysis_data(
     .await?;
 
     let point_events: Vec<(Player, SystemTime)> = events
-        .into_iter()
-        .map(|(player, ts)| {
+        .iter()
+        .map(|&(player, ts, _)| {
             let p = if player == 1 {
                 Player::Player1
             } else {
@@ -129,7 +137,16 @@ async fn load_match_analysis_data(
         })
         .collect();
 
-    Ok((config, point_events))
+    let start = MatchState::new_with(config.clone(), rules.as_ref());
+    let time_called = row
+        .1
+        .map(|points_before| usize::try_from(points_before).unwrap_or(0));
+    let mut contexts = analysis::replay_with(&start, &point_events, rules.as_ref(), time_called);
+    for (context, &(_, _, is_bisque)) in contexts.iter_mut().zip(&events) {
+        context.is_bisque = is_bisque;
+    }
+
+    Ok((config, contexts))
 }
 
 pub async fn match_analysis(
@@ -137,20 +154,46 @@ pub async fn match_analysis(
     State(state): State<AppState>,
     Path(match_id): Path<Uuid>,
 ) -> Result<Json<MatchAnalysis>, AppError> {
-    let (config, events) = load_match_analysis_data(&state.pool, auth.user_id, match_id).await?;
-    let contexts = analysis::replay_with_context(&config, &events);
+    let (_, contexts) = load_match_contexts(&state.pool, auth.user_id, match_id).await?;
     let result = analysis::compute_analysis(&contexts);
     Ok(Json(result))
 }
 
+#[derive(Deserialize)]
+pub struct MomentumParams {
+    pub model: Option<String>,
+    pub half_life: Option<f64>,
+    pub window: Option<usize>,
+}
+
+impl MomentumParams {
+    fn kind(&self) -> Result<MomentumKind, AppError> {
+        match self.model.as_deref().unwrap_or("cumulative") {
+            "cumulative" => Ok(MomentumKind::Cumulative),
+            "weighted" => Ok(MomentumKind::Weighted),
+            "ewma" => Ok(MomentumKind::Ewma {
+                half_life: self.half_life.unwrap_or(4.0),
+            }),
+            "rolling" => Ok(MomentumKind::RollingWindow {
+                points: self.window.unwrap_or(10),
+            }),
+            "games" => Ok(MomentumKind::Games),
+            other => Err(AppError::BadRequest(format!(
+                "Unknown momentum model: {other}"
+            ))),
+        }
+    }
+}
+
 pub async fn match_momentum(
     auth: AuthUser,
     State(state): State<AppState>,
     Path(match_id): Path<Uuid>,
+    Query(params): Query<MomentumParams>,
 ) -> Result<Json<MomentumData>, AppError> {
-    let (config, events) = load_match_analysis_data(&state.pool, auth.user_id, match_id).await?;
-    let contexts = analysis::replay_with_context(&config, &events);
-    let result = analysis::compute_momentum(&contexts);
+    let model = params.kind()?;
+    let (_, contexts) = load_match_contexts(&state.pool, auth.user_id, match_id).await?;
+    let result = analysis::compute_momentum_with(&contexts, &model);
     Ok(Json(result))
 }
 
@@ -159,8 +202,30 @@ pub async fn match_pace(
     State(state): State<AppState>,
     Path(match_id): Path<Uuid>,
 ) -> Result<Json<PaceData>, AppError> {
-    let (config, events) = load_match_analysis_data(&state.pool, auth.user_id, match_id).await?;
-    let contexts = analysis::replay_with_context(&config, &events);
-    let result = analysis::compute_pace(&contexts);
+    let (config, contexts) = load_match_contexts(&state.pool, auth.user_id, match_id).await?;
+    let result = match config.time_limit {
+        Some(limit) => analysis::compute_pace_with_limit(&contexts, limit.duration()),
+        None => analysis::compute_pace(&contexts),
+    };
+    Ok(Json(result))
+}
+
+pub async fn match_games(
+    auth: AuthUser,
+    State(state): State<AppState>,
+    Path(match_id): Path<Uuid>,
+) -> Result<Json<Vec<GameLogEntry>>, AppError> {
+    let (_, contexts) = load_match_contexts(&state.pool, auth.user_id, match_id).await?;
+    let result = analysis::compute_game_log(&contexts);
+    Ok(Json(result))
+}
+
+pub async fn match_report(
+    auth: AuthUser,
+    State(state): State<AppState>,
+    Path(match_id): Path<Uuid>,
+) -> Result<Json<MatchReport>, AppError> {
+    let (config, contexts) = load_match_contexts(&state.pool, auth.user_id, match_id).await?;
+    let result = analysis::compute_report(&config, &contexts);
     Ok(Json(result))
 }
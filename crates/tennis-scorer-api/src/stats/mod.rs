@@ -9,4 +9,6 @@ pub fn routes() -> Router<AppState> {
         .route("/stats/match/{id}/analysis", get(handlers::match_analysis))
         .route("/stats/match/{id}/momentum", get(handlers::match_momentum))
         .route("/stats/match/{id}/pace", get(handlers::match_pace))
+        .route("/stats/match/{id}/games", get(handlers::match_games))
+        .route("/stats/match/{id}/report", get(handlers::match_report))
 }
@@ -45,6 +45,9 @@ async fn setup() -> axum::Router {
         include_str!("../migrations/001_create_users.sql"),
         include_str!("../migrations/002_create_matches.sql"),
         include_str!("../migrations/003_create_match_events.sql"),
+        include_str!("../migrations/004_add_match_score.sql"),
+        include_str!("../migrations/005_add_match_event_bisque.sql"),
+        include_str!("../migrations/006_add_match_time_called.sql"),
     ] {
         sqlx::query(sql)
             .execute(&pool)
@@ -299,7 +302,7 @@ async fn test_match_create_list_get_delete() {
                 "ended_at": "2026-02-06T11:00:00Z",
                 "events": [
                     {"point_number": 1, "player": 1, "timestamp": "2026-02-06T10:01:00Z"},
-                    {"point_number": 2, "player": 1, "timestamp": "2026-02-06T10:02:00Z"}
+                    {"point_number": 2, "player": 1, "timestamp": "2026-02-06T10:02:00Z", "is_bisque": true}
                 ]
             }),
             &token,
@@ -335,6 +338,8 @@ async fn test_match_create_list_get_delete() {
     let body = body_json(resp).await;
     assert_eq!(body["id"].as_str().unwrap(), match_id);
     assert_eq!(body["events"].as_array().unwrap().len(), 2);
+    assert_eq!(body["events"][0]["is_bisque"], false);
+    assert_eq!(body["events"][1]["is_bisque"], true);
 
     // Delete match
     let resp = app
@@ -359,6 +364,160 @@ async fn test_match_create_list_get_delete() {
     assert_eq!(body["total"], 0);
 }
 
+#[tokio::test]
+#[ignore]
+async fn test_match_score_notation() {
+    let app = setup().await;
+    let email = format!("score_{}@example.com", uuid::Uuid::new_v4());
+    let token = register_and_login(&app, &email, "testpassword123").await;
+
+    let match_body = |score: &str, player2_sets: i64| {
+        json!({
+            "match_type": "singles",
+            "config": {"sets_to_win": 2},
+            "winner": 1,
+            "player1_sets": 2,
+            "player2_sets": player2_sets,
+            "score": score,
+            "started_at": "2026-02-06T10:00:00Z",
+            "ended_at": "2026-02-06T11:00:00Z",
+            "events": []
+        })
+    };
+
+    // Score is stored in normalised form
+    let resp = app
+        .clone()
+        .oneshot(auth_json_request(
+            "POST",
+            "/api/matches",
+            match_body("6-4  3-6 7-6(5)", 1),
+            &token,
+        ))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::CREATED);
+    let match_id = body_json(resp).await["id"].as_str().unwrap().to_string();
+
+    let resp = app
+        .clone()
+        .oneshot(auth_request(
+            "GET",
+            &format!("/api/matches/{match_id}"),
+            &token,
+        ))
+        .await
+        .unwrap();
+    let body = body_json(resp).await;
+    assert_eq!(body["score"], "6-4 3-6 7-6(5)");
+
+    // Score disagreeing with the set counts is rejected
+    let resp = app
+        .clone()
+        .oneshot(auth_json_request(
+            "POST",
+            "/api/matches",
+            match_body("6-4 6-3", 1),
+            &token,
+        ))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+    // Unparseable score is rejected
+    let resp = app
+        .clone()
+        .oneshot(auth_json_request(
+            "POST",
+            "/api/matches",
+            match_body("six-four", 0),
+            &token,
+        ))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::UNPROCESSABLE_ENTITY);
+}
+
+#[tokio::test]
+#[ignore]
+async fn test_match_time_called() {
+    let app = setup().await;
+    let email = format!("timed_{}@example.com", uuid::Uuid::new_v4());
+    let token = register_and_login(&app, &email, "testpassword123").await;
+
+    // Player2 wins the only game, then time is called
+    let events: Vec<Value> = (1..=4)
+        .map(|i| {
+            json!({
+                "point_number": i,
+                "player": 2,
+                "timestamp": format!("2026-02-06T10:0{i}:00Z")
+            })
+        })
+        .collect();
+    let resp = app
+        .clone()
+        .oneshot(auth_json_request(
+            "POST",
+            "/api/matches",
+            json!({
+                "match_type": "singles",
+                "config": {
+                    "sets_to_win": 2,
+                    "time_limit": {"minutes": 45, "leader": "Games", "decider": "SuddenDeathPoint"}
+                },
+                "winner": 2,
+                "player1_sets": 0,
+                "player2_sets": 0,
+                "started_at": "2026-02-06T10:00:00Z",
+                "ended_at": "2026-02-06T10:45:00Z",
+                "time_called": {"at": "2026-02-06T10:45:00Z", "points_before": 4},
+                "events": events
+            }),
+            &token,
+        ))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::CREATED);
+    let match_id = body_json(resp).await["id"].as_str().unwrap().to_string();
+
+    let resp = app
+        .clone()
+        .oneshot(auth_request(
+            "GET",
+            &format!("/api/matches/{match_id}"),
+            &token,
+        ))
+        .await
+        .unwrap();
+    let body = body_json(resp).await;
+    assert_eq!(body["time_called"]["points_before"], 4);
+    assert!(body["score"].is_string());
+
+    // Time cannot be called after more points than were played
+    let resp = app
+        .clone()
+        .oneshot(auth_json_request(
+            "POST",
+            "/api/matches",
+            json!({
+                "match_type": "singles",
+                "config": {"sets_to_win": 2},
+                "winner": 1,
+                "player1_sets": 0,
+                "player2_sets": 0,
+                "started_at": "2026-02-06T10:00:00Z",
+                "ended_at": "2026-02-06T10:45:00Z",
+                "time_called": {"at": "2026-02-06T10:45:00Z", "points_before": 1},
+                "events": []
+            }),
+            &token,
+        ))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::UNPROCESSABLE_ENTITY);
+}
+
 // ---------------------------------------------------------------------------
 // Match idempotency via client_id
 // ---------------------------------------------------------------------------
@@ -570,6 +729,131 @@ async fn test_register_validation_invalid_email() {
     assert_eq!(resp.status(), StatusCode::UNPROCESSABLE_ENTITY);
 }
 
+// ---------------------------------------------------------------------------
+// Merging partial recordings
+// ---------------------------------------------------------------------------
+
+/// Helper: create a one-set match where Player1 wins `points` straight points
+/// starting at `first_minute`, and return its id.
+async fn create_partial_match(
+    app: &axum::Router,
+    token: &str,
+    first_minute: u32,
+    points: u32,
+    winner: i64,
+) -> String {
+    let events: Vec<Value> = (0..points)
+        .map(|i| {
+            let minute = first_minute + i;
+            json!({
+                "point_number": i + 1,
+                "player": 1,
+                "timestamp": format!("2026-02-06T{:02}:{:02}:00Z", 10 + minute / 60, minute % 60)
+            })
+        })
+        .collect();
+    let resp = app
+        .clone()
+        .oneshot(auth_json_request(
+            "POST",
+            "/api/matches",
+            json!({
+                "match_type": "singles",
+                "config": {
+                    "sets_to_win": 1,
+                    "tiebreak_points": 7,
+                    "final_set_tiebreak": true,
+                    "no_ad_scoring": false
+                },
+                "winner": winner,
+                "player1_sets": if winner == 1 { 1 } else { 0 },
+                "player2_sets": if winner == 2 { 1 } else { 0 },
+                "started_at": "2026-02-06T10:00:00Z",
+                "ended_at": "2026-02-06T11:00:00Z",
+                "events": events
+            }),
+            token,
+        ))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::CREATED);
+    body_json(resp).await["id"].as_str().unwrap().to_string()
+}
+
+#[tokio::test]
+#[ignore]
+async fn test_merge_partial_matches() {
+    let app = setup().await;
+    let email = format!("merge_{}@example.com", uuid::Uuid::new_v4());
+    let token = register_and_login(&app, &email, "testpassword123").await;
+
+    // Watch recorded 4 games, phone recorded the last 2
+    let first_id = create_partial_match(&app, &token, 0, 16, 1).await;
+    let second_id = create_partial_match(&app, &token, 20, 8, 1).await;
+
+    let resp = app
+        .clone()
+        .oneshot(auth_json_request(
+            "POST",
+            &format!("/api/matches/{first_id}/merge"),
+            json!({"continuation_id": second_id}),
+            &token,
+        ))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(body_json(resp).await["score"], "6-0");
+
+    let resp = app
+        .clone()
+        .oneshot(auth_request(
+            "GET",
+            &format!("/api/matches/{first_id}"),
+            &token,
+        ))
+        .await
+        .unwrap();
+    let body = body_json(resp).await;
+    assert_eq!(body["events"].as_array().unwrap().len(), 24);
+    assert_eq!(body["score"], "6-0");
+
+    // The continuation has been folded in
+    let resp = app
+        .clone()
+        .oneshot(auth_request(
+            "GET",
+            &format!("/api/matches/{second_id}"),
+            &token,
+        ))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+#[ignore]
+async fn test_merge_rejects_inconsistent_result() {
+    let app = setup().await;
+    let email = format!("merge_bad_{}@example.com", uuid::Uuid::new_v4());
+    let token = register_and_login(&app, &email, "testpassword123").await;
+
+    // The continuation claims Player2 won, but its points finish 6-0 to Player1
+    let first_id = create_partial_match(&app, &token, 0, 16, 1).await;
+    let second_id = create_partial_match(&app, &token, 20, 8, 2).await;
+
+    let resp = app
+        .clone()
+        .oneshot(auth_json_request(
+            "POST",
+            &format!("/api/matches/{first_id}/merge"),
+            json!({"continuation_id": second_id}),
+            &token,
+        ))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::UNPROCESSABLE_ENTITY);
+}
+
 // ---------------------------------------------------------------------------
 // Match analysis endpoints
 // ---------------------------------------------------------------------------
@@ -655,6 +939,98 @@ async fn test_match_momentum_valid() {
     );
 }
 
+#[tokio::test]
+#[ignore]
+async fn test_match_momentum_model() {
+    let app = setup().await;
+    let email = format!("momentum_model_{}@example.com", uuid::Uuid::new_v4());
+    let token = register_and_login(&app, &email, "testpassword123").await;
+    let match_id = create_match_with_events(&app, &token).await;
+
+    let resp = app
+        .clone()
+        .oneshot(auth_request(
+            "GET",
+            &format!("/api/stats/match/{match_id}/momentum?model=ewma&half_life=3"),
+            &token,
+        ))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body = body_json(resp).await;
+    assert_eq!(
+        body["model"].as_array().unwrap().len(),
+        body["basic"].as_array().unwrap().len()
+    );
+
+    let resp = app
+        .clone()
+        .oneshot(auth_request(
+            "GET",
+            &format!("/api/stats/match/{match_id}/momentum?model=magic"),
+            &token,
+        ))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+#[ignore]
+async fn test_match_games_valid() {
+    let app = setup().await;
+    let email = format!("games_{}@example.com", uuid::Uuid::new_v4());
+    let token = register_and_login(&app, &email, "testpassword123").await;
+    let match_id = create_match_with_events(&app, &token).await;
+
+    let resp = app
+        .clone()
+        .oneshot(auth_request(
+            "GET",
+            &format!("/api/stats/match/{match_id}/games"),
+            &token,
+        ))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body = body_json(resp).await;
+    let games = body.as_array().expect("should be an array of games");
+    assert!(!games.is_empty());
+    assert_eq!(games[0]["points"], "15-0 30-0 30-15 40-15");
+    assert!(games[0]["server"].is_string(), "should have server");
+}
+
+#[tokio::test]
+#[ignore]
+async fn test_match_report_valid() {
+    let app = setup().await;
+    let email = format!("report_{}@example.com", uuid::Uuid::new_v4());
+    let token = register_and_login(&app, &email, "testpassword123").await;
+    let match_id = create_match_with_events(&app, &token).await;
+
+    let resp = app
+        .clone()
+        .oneshot(auth_request(
+            "GET",
+            &format!("/api/stats/match/{match_id}/report"),
+            &token,
+        ))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body = body_json(resp).await;
+    assert!(
+        body["analysis"]["player1"].is_object(),
+        "should have analysis"
+    );
+    let per_set = body["per_set"]
+        .as_array()
+        .expect("should have per-set analysis");
+    assert_eq!(per_set[0]["set_number"], 1);
+    assert!(body["momentum"]["basic"].is_array(), "should have momentum");
+    assert!(body["pace"].is_object(), "should have pace");
+}
+
 #[tokio::test]
 #[ignore]
 async fn test_match_pace_valid() {
@@ -716,7 +1092,7 @@ async fn test_match_analysis_not_owned() {
     let match_id = create_match_with_events(&app, &token_a).await;
 
     // User B cannot access user A's match analysis
-    for endpoint in &["analysis", "momentum", "pace"] {
+    for endpoint in &["analysis", "momentum", "pace", "games", "report"] {
         let resp = app
             .clone()
             .oneshot(auth_request(
@@ -1,13 +1,26 @@
-use std::sync::RwLock;
+use std::fmt;
+use std::sync::{Arc, RwLock};
 use std::time::{Duration, SystemTime};
 
 use tennis_scorer::{
-    GameState as CoreGameState, MatchConfig as CoreMatchConfig, MatchState, MatchType,
-    MatchWithHistory, Player as CorePlayer, Point, SetState, TiebreakState,
+    BisqueError, CompletionReason, CumulativeGamesRules, EndOnTimeError,
+    GameState as CoreGameState, Handicap, MatchConfig as CoreMatchConfig, MatchState, MatchType,
+    MatchWithHistory, ParseScoreError, Player as CorePlayer, PlayerModel,
+    RallyConfig as CoreRallyConfig, RallyState, RallyWithHistory, ScoreLine, ScoringRules,
+    ServeRule, SetLine, SetState, SimulationConfig, SimulationError as CoreSimulationError,
+    StandardRules, SystemClock, TiebreakOnlyRules, TiebreakRule, TiebreakState,
+    TimeLimit as CoreTimeLimit, TimedDecider as CoreTimedDecider, TimedLeader as CoreTimedLeader,
+    TimestampError,
     analysis::{
-        PointContext as CorePointContext, compute_analysis as core_compute_analysis,
-        compute_momentum as core_compute_momentum, compute_pace as core_compute_pace,
-        replay_with_context as core_replay_with_context,
+        MomentumData as CoreMomentumData, MomentumKind, PaceData as CorePaceData,
+        PointContext as CorePointContext, ServeWinProbabilities, TurningPointOptions,
+        annotate_importance_with, biggest_points, compute_analysis as core_compute_analysis,
+        compute_game_log as core_compute_game_log,
+        compute_momentum_with as core_compute_momentum_with, compute_pace as core_compute_pace,
+        compute_pace_with_limit as core_compute_pace_with_limit,
+        compute_report as core_compute_report,
+        compute_turning_points as core_compute_turning_points, replay_with as core_replay_with,
+        win_probability_series_with,
     },
 };
 
@@ -47,21 +60,12 @@ pub enum GameScore {
     Completed { winner: Player },
 }
 
-fn point_to_string(p: Point) -> String {
-    match p {
-        Point::Love => "0".to_string(),
-        Point::Fifteen => "15".to_string(),
-        Point::Thirty => "30".to_string(),
-        Point::Forty => "40".to_string(),
-    }
-}
-
 impl From<&CoreGameState> for GameScore {
     fn from(gs: &CoreGameState) -> Self {
         match gs {
             CoreGameState::Points { player1, player2 } => GameScore::Points {
-                player1: point_to_string(*player1),
-                player2: point_to_string(*player2),
+                player1: player1.to_string(),
+                player2: player2.to_string(),
             },
             CoreGameState::Deuce { .. } => GameScore::Deuce,
             CoreGameState::Advantage { player, .. } => GameScore::Advantage {
@@ -74,6 +78,148 @@ impl From<&CoreGameState> for GameScore {
     }
 }
 
+/// How the leader is decided when a timed match runs out of time.
+#[derive(uniffi::Enum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimedLeader {
+    Games,
+    SetsThenGames,
+}
+
+/// What is played when a timed match ends level.
+#[derive(uniffi::Enum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimedDecider {
+    SuddenDeathPoint,
+    Tiebreak { points: u8 },
+}
+
+// --- UniFFI errors ---
+
+#[derive(uniffi::Error, Debug, Clone, PartialEq)]
+pub enum ScoringError {
+    NonMonotonicTimestamp {
+        previous_epoch_secs: f64,
+        given_epoch_secs: f64,
+    },
+    NoBisquesLeft {
+        player: Player,
+    },
+    MatchOver,
+    NoTimeLimit,
+}
+
+impl fmt::Display for ScoringError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ScoringError::NonMonotonicTimestamp {
+                previous_epoch_secs,
+                given_epoch_secs,
+            } => write!(
+                f,
+                "timestamp {given_epoch_secs} is earlier than the previous point at {previous_epoch_secs}"
+            ),
+            ScoringError::NoBisquesLeft { player } => write!(f, "{player:?} has no bisques left"),
+            ScoringError::MatchOver => write!(f, "the match is over"),
+            ScoringError::NoTimeLimit => write!(f, "the match has no time limit"),
+        }
+    }
+}
+
+impl std::error::Error for ScoringError {}
+
+#[derive(uniffi::Error, Debug, Clone, PartialEq)]
+pub enum ScoreNotationError {
+    InvalidScore { message: String },
+}
+
+impl fmt::Display for ScoreNotationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ScoreNotationError::InvalidScore { message } => f.write_str(message),
+        }
+    }
+}
+
+impl std::error::Error for ScoreNotationError {}
+
+impl From<ParseScoreError> for ScoreNotationError {
+    fn from(e: ParseScoreError) -> Self {
+        ScoreNotationError::InvalidScore {
+            message: e.to_string(),
+        }
+    }
+}
+
+#[derive(uniffi::Error, Debug, Clone, PartialEq)]
+pub enum SimulationError {
+    /// A serve-win chance that is not between 0 and 1.
+    InvalidModel {
+        player: Player,
+    },
+    InvalidTiming,
+    /// The simulated match went on too long, as when neither player can
+    /// lose a service point.
+    PointLimit,
+}
+
+impl fmt::Display for SimulationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SimulationError::InvalidModel { player } => {
+                write!(f, "the model for {player:?} is not a valid probability")
+            }
+            SimulationError::InvalidTiming => write!(f, "the point timing is not valid"),
+            SimulationError::PointLimit => write!(f, "the match went on too long to simulate"),
+        }
+    }
+}
+
+impl std::error::Error for SimulationError {}
+
+impl From<CoreSimulationError> for SimulationError {
+    fn from(e: CoreSimulationError) -> Self {
+        match e {
+            CoreSimulationError::InvalidModel(player) => SimulationError::InvalidModel {
+                player: player.into(),
+            },
+            CoreSimulationError::InvalidTiming => SimulationError::InvalidTiming,
+            CoreSimulationError::PointLimit => SimulationError::PointLimit,
+        }
+    }
+}
+
+impl From<BisqueError> for ScoringError {
+    fn from(e: BisqueError) -> Self {
+        match e {
+            BisqueError::NoneRemaining(player) => ScoringError::NoBisquesLeft {
+                player: player.into(),
+            },
+            BisqueError::MatchOver => ScoringError::MatchOver,
+        }
+    }
+}
+
+impl From<EndOnTimeError> for ScoringError {
+    fn from(e: EndOnTimeError) -> Self {
+        match e {
+            EndOnTimeError::NoTimeLimit => ScoringError::NoTimeLimit,
+            EndOnTimeError::AlreadyEnded => ScoringError::MatchOver,
+        }
+    }
+}
+
+impl From<TimestampError> for ScoringError {
+    fn from(e: TimestampError) -> Self {
+        match e {
+            TimestampError::NotMonotonic { previous, given } => {
+                ScoringError::NonMonotonicTimestamp {
+                    previous_epoch_secs: system_time_to_epoch_secs(&previous),
+                    given_epoch_secs: system_time_to_epoch_secs(&given),
+                }
+            }
+        }
+    }
+}
+
 // --- UniFFI records ---
 
 #[derive(uniffi::Record, Debug, Clone)]
@@ -84,6 +230,92 @@ pub struct MatchConfig {
     pub no_ad_scoring: bool,
     pub is_doubles: bool,
     pub first_server_team: Option<Player>,
+    #[uniffi(default = None)]
+    pub match_tiebreak_points: Option<u8>,
+    /// The deuce whose point decides the game (3 for padel's star point).
+    #[uniffi(default = None)]
+    pub golden_point_deuce: Option<u8>,
+    /// Handicap points each game starts with: 1 is +15, -1 owes 15.
+    #[uniffi(default = 0)]
+    pub player1_handicap_points: i8,
+    #[uniffi(default = 0)]
+    pub player2_handicap_points: i8,
+    #[uniffi(default = 0)]
+    pub player1_bisques: u8,
+    #[uniffi(default = 0)]
+    pub player2_bisques: u8,
+    #[uniffi(default = None)]
+    pub time_limit: Option<TimeLimit>,
+    /// Decide the match by total games rather than sets (team tennis).
+    #[uniffi(default = None)]
+    pub cumulative_games: Option<CumulativeGames>,
+    /// Every set is a single tiebreak to `tiebreak_points`.
+    #[uniffi(default = false)]
+    pub tiebreak_only: bool,
+    /// Lead needed to win a set tiebreak; 1 is sudden death at the target.
+    #[uniffi(default = 2)]
+    pub tiebreak_win_by: u8,
+    /// First to this many points wins a set tiebreak whatever the margin.
+    #[uniffi(default = None)]
+    pub tiebreak_cap: Option<u8>,
+    #[uniffi(default = 2)]
+    pub match_tiebreak_win_by: u8,
+    #[uniffi(default = None)]
+    pub match_tiebreak_cap: Option<u8>,
+}
+
+/// Cumulative-games scoring: every set is played and total games decide.
+#[derive(uniffi::Record, Debug, Clone)]
+pub struct CumulativeGames {
+    pub sets: u8,
+    pub games_per_set: u8,
+    /// Play on after the last set if the side behind on games won it.
+    pub overtime: bool,
+    pub supertiebreak_points: u8,
+}
+
+impl From<&CumulativeGames> for CumulativeGamesRules {
+    fn from(c: &CumulativeGames) -> Self {
+        CumulativeGamesRules {
+            sets: c.sets,
+            games_per_set: c.games_per_set,
+            overtime: c.overtime,
+            supertiebreak_points: c.supertiebreak_points,
+        }
+    }
+}
+
+/// The rules a config's format scores under.
+fn scoring_rules(config: &MatchConfig) -> Arc<dyn ScoringRules> {
+    match &config.cumulative_games {
+        Some(cumulative) => Arc::new(CumulativeGamesRules::from(cumulative)),
+        None if config.tiebreak_only => Arc::new(TiebreakOnlyRules),
+        None => Arc::new(StandardRules),
+    }
+}
+
+/// A match played until its slot ends.
+#[derive(uniffi::Record, Debug, Clone)]
+pub struct TimeLimit {
+    pub minutes: u16,
+    pub leader: TimedLeader,
+    pub decider: TimedDecider,
+}
+
+impl From<&TimeLimit> for CoreTimeLimit {
+    fn from(t: &TimeLimit) -> Self {
+        CoreTimeLimit {
+            minutes: t.minutes,
+            leader: match t.leader {
+                TimedLeader::Games => CoreTimedLeader::Games,
+                TimedLeader::SetsThenGames => CoreTimedLeader::SetsThenGames,
+            },
+            decider: match t.decider {
+                TimedDecider::SuddenDeathPoint => CoreTimedDecider::SuddenDeathPoint,
+                TimedDecider::Tiebreak { points } => CoreTimedDecider::Tiebreak { points },
+            },
+        }
+    }
 }
 
 impl From<&MatchConfig> for CoreMatchConfig {
@@ -109,10 +341,81 @@ impl From<&MatchConfig> for CoreMatchConfig {
             no_ad_scoring: c.no_ad_scoring,
             match_type,
             serve_order,
+            match_tiebreak_points: c.match_tiebreak_points,
+            golden_point_deuce: c.golden_point_deuce,
+            handicap: Handicap {
+                player1_points: c.player1_handicap_points,
+                player2_points: c.player2_handicap_points,
+                player1_bisques: c.player1_bisques,
+                player2_bisques: c.player2_bisques,
+            },
+            time_limit: c.time_limit.as_ref().map(CoreTimeLimit::from),
+            tiebreak_rule: TiebreakRule {
+                win_by: c.tiebreak_win_by,
+                cap: c.tiebreak_cap,
+            },
+            match_tiebreak_rule: TiebreakRule {
+                win_by: c.match_tiebreak_win_by,
+                cap: c.match_tiebreak_cap,
+            },
+        }
+    }
+}
+
+/// A rally-point format (squash, badminton, table tennis).
+#[derive(uniffi::Record, Debug, Clone)]
+pub struct RallyConfig {
+    pub points_to_win: u8,
+    pub win_by: u8,
+    /// First to this many points wins regardless of margin.
+    pub cap: Option<u8>,
+    /// Serve changes every this many points; `None` means the rally winner
+    /// serves.
+    pub serve_every: Option<u8>,
+    pub games_to_win: u8,
+    pub first_server: Player,
+}
+
+impl From<&RallyConfig> for CoreRallyConfig {
+    fn from(c: &RallyConfig) -> Self {
+        CoreRallyConfig {
+            points_to_win: c.points_to_win,
+            win_by: c.win_by,
+            cap: c.cap,
+            serve_rule: match c.serve_every {
+                Some(every) => ServeRule::Alternate { every },
+                None => ServeRule::WinnerServes,
+            },
+            games_to_win: c.games_to_win,
+            first_server: c.first_server.into(),
         }
     }
 }
 
+#[derive(uniffi::Record, Debug, Clone, PartialEq)]
+pub struct RallyScore {
+    pub player1_games: u8,
+    pub player2_games: u8,
+    /// Points in each game so far, the last entry being the game in play.
+    pub player1_points: Vec<u8>,
+    pub player2_points: Vec<u8>,
+    pub serving: Option<Player>,
+    pub winner: Option<Player>,
+}
+
+fn extract_rally_score(state: &RallyState) -> RallyScore {
+    let (player1_games, player2_games) = state.games_won();
+    let (player1_points, player2_points) = state.games().iter().map(|g| g.points()).unzip();
+    RallyScore {
+        player1_games,
+        player2_games,
+        player1_points,
+        player2_points,
+        serving: state.serving().map(Player::from),
+        winner: state.winner().map(Player::from),
+    }
+}
+
 #[derive(uniffi::Record, Debug, Clone)]
 pub struct MatchScore {
     pub player1_sets: u8,
@@ -126,10 +429,67 @@ pub struct MatchScore {
     pub current_server: u8,
 }
 
+#[derive(uniffi::Record, Debug, Clone, PartialEq, Eq)]
+pub struct SetScoreLine {
+    pub player1: u8,
+    pub player2: u8,
+    /// Losing side's points in a set decided by a tiebreak, e.g. 5 in "7-6(5)".
+    pub tiebreak_loser_points: Option<u8>,
+    /// A match tiebreak written as "[10-8]"; player1/player2 hold its points.
+    pub is_match_tiebreak: bool,
+}
+
+impl From<&SetLine> for SetScoreLine {
+    fn from(set: &SetLine) -> Self {
+        match set {
+            SetLine::Games {
+                player1,
+                player2,
+                tiebreak_loser_points,
+            } => SetScoreLine {
+                player1: *player1,
+                player2: *player2,
+                tiebreak_loser_points: *tiebreak_loser_points,
+                is_match_tiebreak: false,
+            },
+            SetLine::MatchTiebreak { player1, player2 } => SetScoreLine {
+                player1: *player1,
+                player2: *player2,
+                tiebreak_loser_points: None,
+                is_match_tiebreak: true,
+            },
+        }
+    }
+}
+
+#[derive(uniffi::Record, Debug, Clone, PartialEq, Eq)]
+pub struct ParsedScore {
+    pub sets: Vec<SetScoreLine>,
+    pub retired: bool,
+    pub time_called: bool,
+    pub player1_sets: u8,
+    pub player2_sets: u8,
+}
+
 #[derive(uniffi::Record, Debug, Clone)]
 pub struct PointEvent {
     pub player: Player,
     pub timestamp_epoch_secs: f64,
+    /// A free point claimed from the handicap rather than played.
+    #[uniffi(default = false)]
+    pub is_bisque: bool,
+    /// Played after time was called, in the sudden-death point or tiebreak.
+    #[uniffi(default = false)]
+    pub is_overtime: bool,
+}
+
+/// How and when a finished match ended.
+#[derive(uniffi::Record, Debug, Clone, PartialEq)]
+pub struct MatchCompletion {
+    pub winner: Player,
+    /// Decided by calling time rather than played to the end.
+    pub ended_on_time: bool,
+    pub ended_at_epoch_secs: f64,
 }
 
 fn system_time_to_epoch_secs(time: &SystemTime) -> f64 {
@@ -219,6 +579,7 @@ fn extract_score(state: &MatchState) -> MatchScore {
             player1_sets,
             player2_sets,
             sets,
+            ..
         } => {
             let mut p1_games = Vec::new();
             let mut p2_games = Vec::new();
@@ -263,6 +624,7 @@ fn extract_score(state: &MatchState) -> MatchScore {
 pub struct TennisMatch {
     inner: RwLock<MatchWithHistory>,
     config: RwLock<CoreMatchConfig>,
+    rules: Arc<dyn ScoringRules>,
 }
 
 impl Default for TennisMatch {
@@ -280,16 +642,19 @@ impl TennisMatch {
         TennisMatch {
             inner: RwLock::new(MatchWithHistory::new(state)),
             config: RwLock::new(config),
+            rules: Arc::new(StandardRules),
         }
     }
 
     #[uniffi::constructor]
     pub fn new_with_config(config: MatchConfig) -> Self {
         let core_config = CoreMatchConfig::from(&config);
-        let state = MatchState::new(core_config.clone());
+        let rules = scoring_rules(&config);
+        let state = MatchState::new_with(core_config.clone(), rules.as_ref());
         TennisMatch {
-            inner: RwLock::new(MatchWithHistory::new(state)),
+            inner: RwLock::new(MatchWithHistory::with_rules(state, Arc::clone(&rules))),
             config: RwLock::new(core_config),
+            rules,
         }
     }
 
@@ -300,6 +665,25 @@ impl TennisMatch {
         extract_score(inner.current())
     }
 
+    /// Score a point at the time it actually happened (epoch seconds), so
+    /// points queued while the UI was busy keep their real timestamps.
+    /// `None` records the current time.
+    pub fn score_point_at(
+        &self,
+        player: Player,
+        timestamp_epoch_secs: Option<f64>,
+    ) -> Result<MatchScore, ScoringError> {
+        let mut inner = self.inner.write().unwrap();
+        let new_state = match timestamp_epoch_secs {
+            Some(secs) => {
+                inner.score_point_at(CorePlayer::from(player), epoch_secs_to_system_time(secs))?
+            }
+            None => inner.score_point(CorePlayer::from(player)),
+        };
+        *inner = new_state;
+        Ok(extract_score(inner.current()))
+    }
+
     pub fn undo(&self) -> MatchScore {
         let mut inner = self.inner.write().unwrap();
         let new_state = inner.undo();
@@ -317,26 +701,363 @@ impl TennisMatch {
         extract_score(inner.current())
     }
 
+    /// Score in standard notation, e.g. "6-4 2-1 30-15*".
+    pub fn get_score_text(&self) -> String {
+        let inner = self.inner.read().unwrap();
+        match inner.current() {
+            MatchState::Completed { .. } => ScoreLine::from_recording(&inner).to_string(),
+            current => current.display_with(inner.rules()).to_string(),
+        }
+    }
+
+    /// Claim a free point for `player` from their handicap bisques.
+    pub fn claim_bisque(&self, player: Player) -> Result<MatchScore, ScoringError> {
+        let mut inner = self.inner.write().unwrap();
+        let new_state = inner.claim_bisque(CorePlayer::from(player))?;
+        *inner = new_state;
+        Ok(extract_score(inner.current()))
+    }
+
+    pub fn bisques_left(&self, player: Player) -> u8 {
+        let inner = self.inner.read().unwrap();
+        inner.bisques_left(CorePlayer::from(player))
+    }
+
+    /// Call time on a timed match. A level score goes to the configured
+    /// sudden-death point or tiebreak, scored with `score_point`.
+    pub fn end_on_time(&self) -> Result<MatchScore, ScoringError> {
+        let mut inner = self.inner.write().unwrap();
+        let new_state = inner.end_on_time()?;
+        *inner = new_state;
+        Ok(extract_score(inner.current()))
+    }
+
+    /// Points won in the sudden-death point or tiebreak after time was
+    /// called, as [player1, player2].
+    pub fn get_overtime_score(&self) -> Option<Vec<u8>> {
+        let inner = self.inner.read().unwrap();
+        inner.overtime_score().map(|(p1, p2)| vec![p1, p2])
+    }
+
+    /// Seconds left in a timed match's slot, counted from the first point.
+    pub fn time_left_secs(&self) -> Option<f64> {
+        let inner = self.inner.read().unwrap();
+        inner
+            .time_left_with_clock(&SystemClock)
+            .map(|left| left.as_secs_f64())
+    }
+
+    pub fn get_completion(&self) -> Option<MatchCompletion> {
+        let inner = self.inner.read().unwrap();
+        inner.completion().map(|c| MatchCompletion {
+            winner: c.winner.into(),
+            ended_on_time: c.reason == CompletionReason::TimeLimit,
+            ended_at_epoch_secs: system_time_to_epoch_secs(&c.ended_at),
+        })
+    }
+
     pub fn get_point_events(&self) -> Vec<PointEvent> {
         let inner = self.inner.read().unwrap();
+        let bisques = inner.bisque_points();
+        let points_before = inner.time_called().map(|called| called.points_before);
         inner
             .point_events()
             .iter()
-            .map(|(player, timestamp)| PointEvent {
+            .enumerate()
+            .map(|(i, (player, timestamp))| PointEvent {
                 player: (*player).into(),
                 timestamp_epoch_secs: system_time_to_epoch_secs(timestamp),
+                is_bisque: bisques.contains(&i),
+                is_overtime: points_before.is_some_and(|before| i >= before),
             })
             .collect()
     }
 
+    /// Odds from the current score over `runs` seeded simulations.
+    pub fn simulate_odds(
+        &self,
+        player1_serve_win: f64,
+        player2_serve_win: f64,
+        runs: u32,
+        seed: u64,
+    ) -> Result<SimulationSummaryFFI, SimulationError> {
+        // Simulate from a copy so scoring is not held up meanwhile
+        let start = self.inner.read().unwrap().current().clone();
+        let config = simulation_config(player1_serve_win, player2_serve_win, seed, 0.0);
+        let summary =
+            tennis_scorer::simulate_many_with(&start, &config, runs, self.rules.as_ref())?;
+        Ok(SimulationSummaryFFI {
+            runs: summary.runs,
+            player1_wins: summary.player1_wins,
+            player1_win_probability: summary.player1_win_probability,
+            average_points: summary.average_points,
+            score_lines: summary
+                .score_lines
+                .into_iter()
+                .map(|s| ScoreLineCountFFI {
+                    score: s.score,
+                    count: s.count,
+                })
+                .collect(),
+        })
+    }
+
     pub fn new_match(&self) {
         let config = self.config.read().unwrap().clone();
-        let state = MatchState::new(config);
+        let state = MatchState::new_with(config, self.rules.as_ref());
+        let mut inner = self.inner.write().unwrap();
+        *inner = MatchWithHistory::with_rules(state, Arc::clone(&self.rules));
+    }
+}
+
+#[derive(uniffi::Record, Debug, Clone)]
+pub struct ScoreLineCountFFI {
+    pub score: String,
+    pub count: u32,
+}
+
+#[derive(uniffi::Record, Debug, Clone)]
+pub struct SimulationSummaryFFI {
+    pub runs: u32,
+    pub player1_wins: u32,
+    pub player1_win_probability: f64,
+    pub average_points: f64,
+    pub score_lines: Vec<ScoreLineCountFFI>,
+}
+
+fn simulation_config(
+    player1_serve_win: f64,
+    player2_serve_win: f64,
+    seed: u64,
+    start_epoch_secs: f64,
+) -> SimulationConfig {
+    SimulationConfig {
+        player1: PlayerModel {
+            serve_win: player1_serve_win,
+            pressure: 0.0,
+        },
+        player2: PlayerModel {
+            serve_win: player2_serve_win,
+            pressure: 0.0,
+        },
+        seed,
+        start_time: epoch_secs_to_system_time(start_epoch_secs),
+        ..SimulationConfig::default()
+    }
+}
+
+/// A synthetic complete match, e.g. for demo data.
+#[uniffi::export]
+pub fn simulate_match_events(
+    config: MatchConfig,
+    player1_serve_win: f64,
+    player2_serve_win: f64,
+    seed: u64,
+    start_epoch_secs: f64,
+) -> Result<Vec<PointEvent>, SimulationError> {
+    let rules = scoring_rules(&config);
+    let state = MatchState::new_with(CoreMatchConfig::from(&config), rules.as_ref());
+    let config = simulation_config(player1_serve_win, player2_serve_win, seed, start_epoch_secs);
+    Ok(
+        tennis_scorer::simulate_match_with(&state, &config, rules.as_ref())?
+            .events
+            .iter()
+            .map(|(player, timestamp)| PointEvent {
+                player: (*player).into(),
+                timestamp_epoch_secs: system_time_to_epoch_secs(timestamp),
+                is_bisque: false,
+                is_overtime: false,
+            })
+            .collect(),
+    )
+}
+
+// --- UniFFI Object wrapping RallyWithHistory ---
+
+#[derive(uniffi::Object)]
+pub struct RallyMatch {
+    inner: RwLock<RallyWithHistory>,
+    config: CoreRallyConfig,
+}
+
+#[uniffi::export]
+impl RallyMatch {
+    #[uniffi::constructor]
+    pub fn new(config: RallyConfig) -> Self {
+        let config = CoreRallyConfig::from(&config);
+        RallyMatch {
+            inner: RwLock::new(RallyWithHistory::new(RallyState::new(config.clone()))),
+            config,
+        }
+    }
+
+    pub fn score_point(&self, player: Player) -> RallyScore {
+        let mut inner = self.inner.write().unwrap();
+        let new_state = inner.score_point(CorePlayer::from(player));
+        *inner = new_state;
+        extract_rally_score(inner.current())
+    }
+
+    /// Score a rally at the time it actually happened (epoch seconds).
+    /// `None` records the current time.
+    pub fn score_point_at(
+        &self,
+        player: Player,
+        timestamp_epoch_secs: Option<f64>,
+    ) -> Result<RallyScore, ScoringError> {
+        let mut inner = self.inner.write().unwrap();
+        let new_state = match timestamp_epoch_secs {
+            Some(secs) => {
+                inner.score_point_at(CorePlayer::from(player), epoch_secs_to_system_time(secs))?
+            }
+            None => inner.score_point(CorePlayer::from(player)),
+        };
+        *inner = new_state;
+        Ok(extract_rally_score(inner.current()))
+    }
+
+    pub fn undo(&self) -> RallyScore {
         let mut inner = self.inner.write().unwrap();
-        *inner = MatchWithHistory::new(state);
+        let new_state = inner.undo();
+        *inner = new_state;
+        extract_rally_score(inner.current())
+    }
+
+    pub fn can_undo(&self) -> bool {
+        let inner = self.inner.read().unwrap();
+        inner.can_undo()
+    }
+
+    pub fn get_score(&self) -> RallyScore {
+        let inner = self.inner.read().unwrap();
+        extract_rally_score(inner.current())
+    }
+
+    /// Game scores, e.g. "11-7 *4-3" with "*" marking the server.
+    pub fn get_score_text(&self) -> String {
+        let inner = self.inner.read().unwrap();
+        inner.current().to_string()
+    }
+
+    pub fn get_point_events(&self) -> Vec<PointEvent> {
+        let inner = self.inner.read().unwrap();
+        inner
+            .point_events()
+            .iter()
+            .map(|(player, timestamp)| PointEvent {
+                player: (*player).into(),
+                timestamp_epoch_secs: system_time_to_epoch_secs(timestamp),
+                is_bisque: false,
+                is_overtime: false,
+            })
+            .collect()
+    }
+
+    pub fn new_match(&self) {
+        let mut inner = self.inner.write().unwrap();
+        *inner = RallyWithHistory::new(RallyState::new(self.config.clone()));
+    }
+}
+
+/// Padel: doubles, best of three, star point at the third deuce.
+#[uniffi::export]
+pub fn padel_match_config(first_server_team: Player) -> MatchConfig {
+    MatchConfig {
+        sets_to_win: 2,
+        tiebreak_points: 7,
+        final_set_tiebreak: true,
+        no_ad_scoring: false,
+        is_doubles: true,
+        first_server_team: Some(first_server_team),
+        match_tiebreak_points: None,
+        golden_point_deuce: Some(3),
+        player1_handicap_points: 0,
+        player2_handicap_points: 0,
+        player1_bisques: 0,
+        player2_bisques: 0,
+        time_limit: None,
+        cumulative_games: None,
+        tiebreak_only: false,
+        tiebreak_win_by: 2,
+        tiebreak_cap: None,
+        match_tiebreak_win_by: 2,
+        match_tiebreak_cap: None,
+    }
+}
+
+/// Team tennis: five sets to five games, no-ad, decided by total games with
+/// extended play and a supertiebreak.
+#[uniffi::export]
+pub fn team_tennis_match_config() -> MatchConfig {
+    MatchConfig {
+        sets_to_win: 2,
+        tiebreak_points: 5,
+        final_set_tiebreak: true,
+        no_ad_scoring: true,
+        is_doubles: false,
+        first_server_team: None,
+        match_tiebreak_points: None,
+        golden_point_deuce: None,
+        player1_handicap_points: 0,
+        player2_handicap_points: 0,
+        player1_bisques: 0,
+        player2_bisques: 0,
+        time_limit: None,
+        cumulative_games: Some(CumulativeGames {
+            sets: 5,
+            games_per_set: 5,
+            overtime: true,
+            supertiebreak_points: 7,
+        }),
+        tiebreak_only: false,
+        tiebreak_win_by: 2,
+        tiebreak_cap: None,
+        match_tiebreak_win_by: 2,
+        match_tiebreak_cap: None,
+    }
+}
+
+/// A practice session of stand-alone tiebreaks to `points`; the first to
+/// win `tiebreaks_to_win` of them wins.
+#[uniffi::export]
+pub fn tiebreak_session_config(points: u8, tiebreaks_to_win: u8) -> MatchConfig {
+    MatchConfig {
+        sets_to_win: tiebreaks_to_win,
+        tiebreak_points: points,
+        final_set_tiebreak: true,
+        no_ad_scoring: false,
+        is_doubles: false,
+        first_server_team: None,
+        match_tiebreak_points: None,
+        golden_point_deuce: None,
+        player1_handicap_points: 0,
+        player2_handicap_points: 0,
+        player1_bisques: 0,
+        player2_bisques: 0,
+        time_limit: None,
+        cumulative_games: None,
+        tiebreak_only: true,
+        tiebreak_win_by: 2,
+        tiebreak_cap: None,
+        match_tiebreak_win_by: 2,
+        match_tiebreak_cap: None,
     }
 }
 
+/// Parse a final score such as "6-4 3-6 7-6(5)" into set scores.
+#[uniffi::export]
+pub fn parse_score_text(text: String) -> Result<ParsedScore, ScoreNotationError> {
+    let line: ScoreLine = text.parse()?;
+    let (player1_sets, player2_sets) = line.sets_won();
+    Ok(ParsedScore {
+        sets: line.sets.iter().map(SetScoreLine::from).collect(),
+        retired: line.retired,
+        time_called: line.time_called,
+        player1_sets,
+        player2_sets,
+    })
+}
+
 // --- Analysis UniFFI types ---
 
 #[derive(uniffi::Record, Debug, Clone)]
@@ -352,6 +1073,12 @@ pub struct AnalysisPointContext {
     pub game_number_in_set: u32,
     pub set_number: u32,
     pub is_tiebreak: bool,
+    pub is_golden_point: bool,
+    pub importance: Option<f64>,
+    pub server_position: u8,
+    pub returner_position: u8,
+    pub is_bisque: bool,
+    pub is_overtime: bool,
 }
 
 impl From<&CorePointContext> for AnalysisPointContext {
@@ -368,6 +1095,12 @@ impl From<&CorePointContext> for AnalysisPointContext {
             game_number_in_set: p.game_number_in_set,
             set_number: p.set_number,
             is_tiebreak: p.is_tiebreak,
+            is_golden_point: p.is_golden_point,
+            importance: p.importance,
+            server_position: p.server_position,
+            returner_position: p.returner_position,
+            is_bisque: p.is_bisque,
+            is_overtime: p.is_overtime,
         }
     }
 }
@@ -403,6 +1136,9 @@ pub struct DeuceStatsFFI {
     pub deuce_game_win_rate: f64,
     pub total_deuce_count: u32,
     pub average_deuces_per_deuce_game: f64,
+    pub golden_point_games: u32,
+    pub golden_points_won: u32,
+    pub golden_point_win_rate: f64,
 }
 
 #[derive(uniffi::Record, Debug, Clone)]
@@ -451,29 +1187,161 @@ pub struct TotalPointsStatsFFI {
 }
 
 #[derive(uniffi::Record, Debug, Clone)]
-pub struct PlayerStatsFFI {
-    pub break_points: BreakPointStatsFFI,
-    pub service: ServiceStatsFFI,
-    pub deuce: DeuceStatsFFI,
-    pub conversion: ConversionRateStatsFFI,
-    pub streaks: StreakStatsFFI,
-    pub clutch: ClutchStatsFFI,
-    pub tiebreak: TiebreakStatsFFI,
-    pub total_points: TotalPointsStatsFFI,
+pub struct LeadForGoodFFI {
+    pub point_number: u32,
+    pub player1_sets: u8,
+    pub player2_sets: u8,
+    pub player1_games: u8,
+    pub player2_games: u8,
+    pub player1_points: String,
+    pub player2_points: String,
 }
 
 #[derive(uniffi::Record, Debug, Clone)]
-pub struct MatchAnalysisFFI {
-    pub player1: PlayerStatsFFI,
-    pub player2: PlayerStatsFFI,
+pub struct ComebackStatsFFI {
+    pub largest_set_deficit_overcome: u8,
+    pub largest_game_deficit_overcome: u8,
+    pub largest_point_deficit_overcome: u8,
+    pub points_leading: u32,
+    pub time_leading_seconds: f64,
+    pub lead_for_good: Option<LeadForGoodFFI>,
+    pub break_leads_lost: u32,
+    pub sets_lost_after_break_up: u32,
 }
 
 #[derive(uniffi::Record, Debug, Clone)]
-pub struct MomentumDataFFI {
-    pub basic: Vec<f64>,
-    pub weighted: Vec<f64>,
-    pub per_set_basic: Vec<Vec<f64>>,
-    pub per_set_weighted: Vec<Vec<f64>>,
+pub struct CourtSplitFFI {
+    pub service_points_won: u32,
+    pub service_points_total: u32,
+    pub return_points_won: u32,
+    pub return_points_total: u32,
+    pub break_points_created: u32,
+    pub break_points_converted: u32,
+    pub break_point_conversion_rate: f64,
+}
+
+#[derive(uniffi::Record, Debug, Clone)]
+pub struct ServerCourtSplitFFI {
+    pub position: u8,
+    pub deuce_points_won: u32,
+    pub deuce_points_total: u32,
+    pub ad_points_won: u32,
+    pub ad_points_total: u32,
+}
+
+#[derive(uniffi::Record, Debug, Clone)]
+pub struct ReturnerCourtSplitFFI {
+    pub position: u8,
+    pub return_points_won: u32,
+    pub return_points_total: u32,
+    pub break_points_created: u32,
+    pub break_points_converted: u32,
+    pub break_point_conversion_rate: f64,
+}
+
+#[derive(uniffi::Record, Debug, Clone)]
+pub struct CourtStatsFFI {
+    pub deuce: CourtSplitFFI,
+    pub ad: CourtSplitFFI,
+    pub by_server_position: Vec<ServerCourtSplitFFI>,
+    pub by_returner_position: Vec<ReturnerCourtSplitFFI>,
+}
+
+#[derive(uniffi::Record, Debug, Clone)]
+pub struct PlayerStatsFFI {
+    pub break_points: BreakPointStatsFFI,
+    pub service: ServiceStatsFFI,
+    pub deuce: DeuceStatsFFI,
+    pub conversion: ConversionRateStatsFFI,
+    pub streaks: StreakStatsFFI,
+    pub clutch: ClutchStatsFFI,
+    pub tiebreak: TiebreakStatsFFI,
+    pub total_points: TotalPointsStatsFFI,
+    pub comeback: ComebackStatsFFI,
+    pub court: CourtStatsFFI,
+}
+
+#[derive(uniffi::Record, Debug, Clone)]
+pub struct MatchAnalysisFFI {
+    pub player1: PlayerStatsFFI,
+    pub player2: PlayerStatsFFI,
+}
+
+#[derive(uniffi::Enum, Debug, Clone, Copy, PartialEq)]
+pub enum MomentumModel {
+    Cumulative,
+    Weighted,
+    Ewma { half_life: f64 },
+    RollingWindow { points: u32 },
+    Games,
+}
+
+impl From<MomentumModel> for MomentumKind {
+    fn from(m: MomentumModel) -> Self {
+        match m {
+            MomentumModel::Cumulative => MomentumKind::Cumulative,
+            MomentumModel::Weighted => MomentumKind::Weighted,
+            MomentumModel::Ewma { half_life } => MomentumKind::Ewma { half_life },
+            MomentumModel::RollingWindow { points } => MomentumKind::RollingWindow {
+                points: points as usize,
+            },
+            MomentumModel::Games => MomentumKind::Games,
+        }
+    }
+}
+
+#[derive(uniffi::Record, Debug, Clone)]
+pub struct MomentumDataFFI {
+    pub basic: Vec<f64>,
+    pub weighted: Vec<f64>,
+    pub per_set_basic: Vec<Vec<f64>>,
+    pub per_set_weighted: Vec<Vec<f64>>,
+    pub model: Vec<f64>,
+    pub per_set_model: Vec<Vec<f64>>,
+}
+
+#[derive(uniffi::Record, Debug, Clone)]
+pub struct GameLogEntryFFI {
+    pub set_number: u32,
+    pub game_number: u32,
+    pub server: Player,
+    pub winner: Option<Player>,
+    pub held: Option<bool>,
+    pub is_tiebreak: bool,
+    pub points: String,
+    pub deuce_count: u8,
+    pub duration_seconds: f64,
+    pub player1_sets_before: u8,
+    pub player2_sets_before: u8,
+    pub player1_games_before: u8,
+    pub player2_games_before: u8,
+}
+
+#[derive(uniffi::Record, Debug, Clone)]
+pub struct MomentumPhaseFFI {
+    pub start_point: u32,
+    pub end_point: u32,
+    pub leader: Option<Player>,
+    pub swing: f64,
+}
+
+#[derive(uniffi::Record, Debug, Clone)]
+pub struct TurningPointFFI {
+    pub point_number: u32,
+    pub player: Player,
+    pub reason: String,
+    pub player1_sets: u8,
+    pub player2_sets: u8,
+    pub player1_games: u8,
+    pub player2_games: u8,
+    pub player1_points: String,
+    pub player2_points: String,
+}
+
+#[derive(uniffi::Record, Debug, Clone)]
+pub struct TurningPointDataFFI {
+    pub phases: Vec<MomentumPhaseFFI>,
+    pub turning_points: Vec<TurningPointFFI>,
 }
 
 #[derive(uniffi::Record, Debug, Clone)]
@@ -481,6 +1349,34 @@ pub struct PaceDataFFI {
     pub average_point_interval_seconds: f64,
     pub per_set_durations_seconds: Vec<f64>,
     pub total_duration_seconds: f64,
+    pub time_limit_seconds: Option<f64>,
+    pub points_after_time_limit: u32,
+}
+
+#[derive(uniffi::Record, Debug, Clone)]
+pub struct SetAnalysisFFI {
+    pub set_number: u32,
+    pub analysis: MatchAnalysisFFI,
+}
+
+#[derive(uniffi::Record, Debug, Clone)]
+pub struct MatchReportFFI {
+    pub analysis: MatchAnalysisFFI,
+    pub per_set: Vec<SetAnalysisFFI>,
+    pub momentum: MomentumDataFFI,
+    pub pace: PaceDataFFI,
+}
+
+#[derive(uniffi::Record, Debug, Clone)]
+pub struct WinProbabilityPointFFI {
+    pub point_number: u32,
+    pub scorer: Player,
+    pub player1_game_before: f64,
+    pub player1_set_before: f64,
+    pub player1_match_before: f64,
+    pub player1_game_after: f64,
+    pub player1_set_after: f64,
+    pub player1_match_after: f64,
 }
 
 fn core_analysis_to_ffi(a: &tennis_scorer::analysis::MatchAnalysis) -> MatchAnalysisFFI {
@@ -518,6 +1414,9 @@ fn player_stats_to_ffi(s: &tennis_scorer::analysis::PlayerStats) -> PlayerStatsF
             deuce_game_win_rate: s.deuce.deuce_game_win_rate,
             total_deuce_count: s.deuce.total_deuce_count,
             average_deuces_per_deuce_game: s.deuce.average_deuces_per_deuce_game,
+            golden_point_games: s.deuce.golden_point_games,
+            golden_points_won: s.deuce.golden_points_won,
+            golden_point_win_rate: s.deuce.golden_point_win_rate,
         },
         conversion: ConversionRateStatsFFI {
             game_points_total: s.conversion.game_points_total,
@@ -554,6 +1453,93 @@ fn player_stats_to_ffi(s: &tennis_scorer::analysis::PlayerStats) -> PlayerStatsF
             total_points: s.total_points.total_points,
             points_won_percentage: s.total_points.points_won_percentage,
         },
+        comeback: ComebackStatsFFI {
+            largest_set_deficit_overcome: s.comeback.largest_set_deficit_overcome,
+            largest_game_deficit_overcome: s.comeback.largest_game_deficit_overcome,
+            largest_point_deficit_overcome: s.comeback.largest_point_deficit_overcome,
+            points_leading: s.comeback.points_leading,
+            time_leading_seconds: s.comeback.time_leading_seconds,
+            lead_for_good: s.comeback.lead_for_good.as_ref().map(|lead| {
+                let set = lead.score.sets.last();
+                LeadForGoodFFI {
+                    point_number: lead.point_number,
+                    player1_sets: lead.score.player1_sets,
+                    player2_sets: lead.score.player2_sets,
+                    player1_games: set.map_or(0, |s| s.player1_games),
+                    player2_games: set.map_or(0, |s| s.player2_games),
+                    player1_points: lead.score.current_game.player1_points.clone(),
+                    player2_points: lead.score.current_game.player2_points.clone(),
+                }
+            }),
+            break_leads_lost: s.comeback.break_leads_lost,
+            sets_lost_after_break_up: s.comeback.sets_lost_after_break_up,
+        },
+        court: CourtStatsFFI {
+            deuce: court_split_to_ffi(&s.court.deuce),
+            ad: court_split_to_ffi(&s.court.ad),
+            by_server_position: s
+                .court
+                .by_server_position
+                .iter()
+                .map(|p| ServerCourtSplitFFI {
+                    position: p.position,
+                    deuce_points_won: p.deuce_points_won,
+                    deuce_points_total: p.deuce_points_total,
+                    ad_points_won: p.ad_points_won,
+                    ad_points_total: p.ad_points_total,
+                })
+                .collect(),
+            by_returner_position: s
+                .court
+                .by_returner_position
+                .iter()
+                .map(|r| ReturnerCourtSplitFFI {
+                    position: r.position,
+                    return_points_won: r.return_points_won,
+                    return_points_total: r.return_points_total,
+                    break_points_created: r.break_points_created,
+                    break_points_converted: r.break_points_converted,
+                    break_point_conversion_rate: r.break_point_conversion_rate,
+                })
+                .collect(),
+        },
+    }
+}
+
+fn court_split_to_ffi(s: &tennis_scorer::analysis::CourtSplit) -> CourtSplitFFI {
+    CourtSplitFFI {
+        service_points_won: s.service_points_won,
+        service_points_total: s.service_points_total,
+        return_points_won: s.return_points_won,
+        return_points_total: s.return_points_total,
+        break_points_created: s.break_points_created,
+        break_points_converted: s.break_points_converted,
+        break_point_conversion_rate: s.break_point_conversion_rate,
+    }
+}
+
+fn momentum_to_ffi(momentum: CoreMomentumData) -> MomentumDataFFI {
+    MomentumDataFFI {
+        basic: momentum.basic,
+        weighted: momentum.weighted,
+        per_set_basic: momentum.per_set_basic,
+        per_set_weighted: momentum.per_set_weighted,
+        model: momentum.model,
+        per_set_model: momentum.per_set_model,
+    }
+}
+
+fn pace_to_ffi(pace: &CorePaceData) -> PaceDataFFI {
+    PaceDataFFI {
+        average_point_interval_seconds: pace.average_point_interval_seconds,
+        per_set_durations_seconds: pace
+            .per_set_durations
+            .iter()
+            .map(|s| s.duration_seconds)
+            .collect(),
+        total_duration_seconds: pace.total_duration_seconds,
+        time_limit_seconds: pace.time_limit_seconds,
+        points_after_time_limit: pace.points_after_time_limit,
     }
 }
 
@@ -564,11 +1550,11 @@ fn epoch_secs_to_system_time(secs: f64) -> SystemTime {
     SystemTime::UNIX_EPOCH + Duration::from_secs_f64(secs)
 }
 
-// --- Analysis exported functions ---
-
-#[uniffi::export]
-pub fn analyze_match(config: MatchConfig, events: Vec<PointEvent>) -> MatchAnalysisFFI {
-    let core_config = CoreMatchConfig::from(&config);
+/// Replay `events` under `config`, flagging the points claimed as bisques.
+/// Scoring stops at the first point played after time was called.
+fn replay_events(config: &MatchConfig, events: &[PointEvent]) -> Vec<CorePointContext> {
+    let rules = scoring_rules(config);
+    let start = MatchState::new_with(CoreMatchConfig::from(config), rules.as_ref());
     let core_events: Vec<(CorePlayer, SystemTime)> = events
         .iter()
         .map(|e| {
@@ -578,74 +1564,199 @@ pub fn analyze_match(config: MatchConfig, events: Vec<PointEvent>) -> MatchAnaly
             )
         })
         .collect();
-    let contexts = core_replay_with_context(&core_config, &core_events);
+    let time_called = events.iter().position(|e| e.is_overtime);
+    let mut contexts = core_replay_with(&start, &core_events, rules.as_ref(), time_called);
+    for (context, event) in contexts.iter_mut().zip(events) {
+        context.is_bisque = event.is_bisque;
+    }
+    contexts
+}
+
+// --- Analysis exported functions ---
+
+#[uniffi::export]
+pub fn analyze_match(config: MatchConfig, events: Vec<PointEvent>) -> MatchAnalysisFFI {
+    let contexts = replay_events(&config, &events);
     let analysis = core_compute_analysis(&contexts);
     core_analysis_to_ffi(&analysis)
 }
 
+#[uniffi::export(default(model = None))]
+pub fn compute_match_momentum(
+    config: MatchConfig,
+    events: Vec<PointEvent>,
+    model: Option<MomentumModel>,
+) -> MomentumDataFFI {
+    let contexts = replay_events(&config, &events);
+    let model = MomentumKind::from(model.unwrap_or(MomentumModel::Cumulative));
+    momentum_to_ffi(core_compute_momentum_with(&contexts, &model))
+}
+
+#[uniffi::export(default(model = None))]
+pub fn compute_momentum_turning_points(
+    config: MatchConfig,
+    events: Vec<PointEvent>,
+    model: Option<MomentumModel>,
+) -> TurningPointDataFFI {
+    let contexts = replay_events(&config, &events);
+    let model = MomentumKind::from(model.unwrap_or(MomentumModel::Cumulative));
+    let momentum = core_compute_momentum_with(&contexts, &model);
+    let data = core_compute_turning_points(&contexts, &momentum, &TurningPointOptions::default());
+    TurningPointDataFFI {
+        phases: data
+            .phases
+            .iter()
+            .map(|p| MomentumPhaseFFI {
+                start_point: p.start_point,
+                end_point: p.end_point,
+                leader: p.leader.map(Player::from),
+                swing: p.swing,
+            })
+            .collect(),
+        turning_points: data
+            .turning_points
+            .into_iter()
+            .map(|t| {
+                let set = t.score.sets.last();
+                TurningPointFFI {
+                    point_number: t.point_number,
+                    player: t.player.into(),
+                    reason: t.reason,
+                    player1_sets: t.score.player1_sets,
+                    player2_sets: t.score.player2_sets,
+                    player1_games: set.map_or(0, |s| s.player1_games),
+                    player2_games: set.map_or(0, |s| s.player2_games),
+                    player1_points: t.score.current_game.player1_points,
+                    player2_points: t.score.current_game.player2_points,
+                }
+            })
+            .collect(),
+    }
+}
+
 #[uniffi::export]
-pub fn compute_match_momentum(config: MatchConfig, events: Vec<PointEvent>) -> MomentumDataFFI {
-    let core_config = CoreMatchConfig::from(&config);
-    let core_events: Vec<(CorePlayer, SystemTime)> = events
-        .iter()
-        .map(|e| {
-            (
-                CorePlayer::from(e.player),
-                epoch_secs_to_system_time(e.timestamp_epoch_secs),
-            )
+pub fn compute_game_log(config: MatchConfig, events: Vec<PointEvent>) -> Vec<GameLogEntryFFI> {
+    let contexts = replay_events(&config, &events);
+    core_compute_game_log(&contexts)
+        .into_iter()
+        .map(|g| {
+            let set = g.score_before.sets.last();
+            GameLogEntryFFI {
+                set_number: g.set_number,
+                game_number: g.game_number,
+                server: g.server.into(),
+                winner: g.winner.map(Player::from),
+                held: g.held,
+                is_tiebreak: g.is_tiebreak,
+                points: g.points,
+                deuce_count: g.deuce_count,
+                duration_seconds: g.duration_seconds,
+                player1_sets_before: g.score_before.player1_sets,
+                player2_sets_before: g.score_before.player2_sets,
+                player1_games_before: set.map_or(0, |s| s.player1_games),
+                player2_games_before: set.map_or(0, |s| s.player2_games),
+            }
         })
-        .collect();
-    let contexts = core_replay_with_context(&core_config, &core_events);
-    let momentum = core_compute_momentum(&contexts);
-    MomentumDataFFI {
-        basic: momentum.basic,
-        weighted: momentum.weighted,
-        per_set_basic: momentum.per_set_basic,
-        per_set_weighted: momentum.per_set_weighted,
-    }
+        .collect()
 }
 
 #[uniffi::export]
 pub fn compute_match_pace(config: MatchConfig, events: Vec<PointEvent>) -> PaceDataFFI {
     let core_config = CoreMatchConfig::from(&config);
-    let core_events: Vec<(CorePlayer, SystemTime)> = events
-        .iter()
-        .map(|e| {
-            (
-                CorePlayer::from(e.player),
-                epoch_secs_to_system_time(e.timestamp_epoch_secs),
-            )
-        })
-        .collect();
-    let contexts = core_replay_with_context(&core_config, &core_events);
-    let pace = core_compute_pace(&contexts);
-    PaceDataFFI {
-        average_point_interval_seconds: pace.average_point_interval_seconds,
-        per_set_durations_seconds: pace
-            .per_set_durations
+    let contexts = replay_events(&config, &events);
+    let pace = match core_config.time_limit {
+        Some(limit) => core_compute_pace_with_limit(&contexts, limit.duration()),
+        None => core_compute_pace(&contexts),
+    };
+    pace_to_ffi(&pace)
+}
+
+/// Whole-match and per-set stats, momentum and pace from one replay.
+#[uniffi::export]
+pub fn compute_match_report(config: MatchConfig, events: Vec<PointEvent>) -> MatchReportFFI {
+    let core_config = CoreMatchConfig::from(&config);
+    let contexts = replay_events(&config, &events);
+    let report = core_compute_report(&core_config, &contexts);
+    MatchReportFFI {
+        analysis: core_analysis_to_ffi(&report.analysis),
+        per_set: report
+            .per_set
             .iter()
-            .map(|s| s.duration_seconds)
+            .map(|s| SetAnalysisFFI {
+                set_number: s.set_number,
+                analysis: core_analysis_to_ffi(&s.analysis),
+            })
             .collect(),
-        total_duration_seconds: pace.total_duration_seconds,
+        momentum: momentum_to_ffi(report.momentum),
+        pace: pace_to_ffi(&report.pace),
     }
 }
 
 #[uniffi::export]
-pub fn replay_match_with_context(
+pub fn compute_win_probability_series(
     config: MatchConfig,
     events: Vec<PointEvent>,
+    player1_serve_win: f64,
+    player2_serve_win: f64,
+) -> Vec<WinProbabilityPointFFI> {
+    let core_config = CoreMatchConfig::from(&config);
+    let contexts = replay_events(&config, &events);
+    let serve = ServeWinProbabilities {
+        player1: player1_serve_win,
+        player2: player2_serve_win,
+    };
+    win_probability_series_with(
+        &core_config,
+        &contexts,
+        &serve,
+        scoring_rules(&config).as_ref(),
+    )
+    .into_iter()
+    .map(|p| WinProbabilityPointFFI {
+        point_number: p.point_number,
+        scorer: p.scorer.into(),
+        player1_game_before: p.before.player1_game,
+        player1_set_before: p.before.player1_set,
+        player1_match_before: p.before.player1_match,
+        player1_game_after: p.after.player1_game,
+        player1_set_after: p.after.player1_set,
+        player1_match_after: p.after.player1_match,
+    })
+    .collect()
+}
+
+#[uniffi::export]
+pub fn compute_biggest_points(
+    config: MatchConfig,
+    events: Vec<PointEvent>,
+    player1_serve_win: f64,
+    player2_serve_win: f64,
+    limit: u32,
 ) -> Vec<AnalysisPointContext> {
     let core_config = CoreMatchConfig::from(&config);
-    let core_events: Vec<(CorePlayer, SystemTime)> = events
-        .iter()
-        .map(|e| {
-            (
-                CorePlayer::from(e.player),
-                epoch_secs_to_system_time(e.timestamp_epoch_secs),
-            )
-        })
-        .collect();
-    let contexts = core_replay_with_context(&core_config, &core_events);
+    let mut contexts = replay_events(&config, &events);
+    let serve = ServeWinProbabilities {
+        player1: player1_serve_win,
+        player2: player2_serve_win,
+    };
+    annotate_importance_with(
+        &core_config,
+        &mut contexts,
+        &serve,
+        scoring_rules(&config).as_ref(),
+    );
+    biggest_points(&contexts, limit as usize)
+        .into_iter()
+        .map(AnalysisPointContext::from)
+        .collect()
+}
+
+#[uniffi::export]
+pub fn replay_match_with_context(
+    config: MatchConfig,
+    events: Vec<PointEvent>,
+) -> Vec<AnalysisPointContext> {
+    let contexts = replay_events(&config, &events);
     contexts.iter().map(AnalysisPointContext::from).collect()
 }
 
@@ -653,6 +1764,348 @@ pub fn replay_match_with_context(
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_end_on_time_sudden_death() {
+        let mut config = padel_match_config(Player::Player1);
+        config.time_limit = Some(TimeLimit {
+            minutes: 60,
+            leader: TimedLeader::Games,
+            decider: TimedDecider::SuddenDeathPoint,
+        });
+        let m = TennisMatch::new_with_config(config.clone());
+        assert_eq!(m.get_completion(), None);
+        m.score_point(Player::Player1);
+
+        let score = m.end_on_time().unwrap();
+        assert_eq!(score.winner, None);
+        assert_eq!(m.get_overtime_score(), Some(vec![0, 0]));
+
+        let score = m.score_point(Player::Player2);
+        assert_eq!(score.winner, Some(Player::Player2));
+        let completion = m.get_completion().unwrap();
+        assert!(completion.ended_on_time);
+        assert_eq!(completion.winner, Player::Player2);
+
+        let events = m.get_point_events();
+        assert!(!events[0].is_overtime);
+        assert!(events[1].is_overtime);
+        let analysis = analyze_match(config, events);
+        assert_eq!(analysis.player2.total_points.points_won, 0);
+    }
+
+    #[test]
+    fn test_team_tennis_score_text() {
+        let m = TennisMatch::new_with_config(team_tennis_match_config());
+        for _ in 0..20 {
+            m.score_point(Player::Player2);
+        }
+        assert_eq!(m.get_score_text(), "0-5 0-0 0-0* (0-5 games)");
+    }
+
+    #[test]
+    fn test_tiebreak_session() {
+        let config = tiebreak_session_config(7, 2);
+        let m = TennisMatch::new_with_config(config.clone());
+        for _ in 0..7 {
+            m.score_point(Player::Player1);
+        }
+        let score = m.score_point(Player::Player2);
+        assert!(score.is_tiebreak);
+        assert_eq!(score.player1_sets, 1);
+        assert_eq!(m.get_score_text(), "[7-0] [*0-1]");
+
+        let contexts = replay_match_with_context(config.clone(), m.get_point_events());
+        assert_eq!(contexts[7].set_number, 2);
+        assert_eq!(contexts[7].game_number_in_set, 1);
+
+        let analysis = analyze_match(config, m.get_point_events());
+        assert_eq!(analysis.player1.tiebreak.tiebreaks_played, 1);
+    }
+
+    #[test]
+    fn test_comeback_stats() {
+        let config = padel_match_config(Player::Player1);
+        let m = TennisMatch::new_with_config(config.clone());
+        for _ in 0..6 {
+            m.score_point(Player::Player2);
+        }
+        for _ in 0..8 {
+            m.score_point(Player::Player1);
+        }
+        let analysis = analyze_match(config, m.get_point_events());
+        assert_eq!(analysis.player1.comeback.largest_point_deficit_overcome, 2);
+        assert_eq!(analysis.player2.comeback.points_leading, 6);
+        assert!(analysis.player1.comeback.lead_for_good.is_none());
+    }
+
+    #[test]
+    fn test_court_stats() {
+        let config = padel_match_config(Player::Player1);
+        let m = TennisMatch::new_with_config(config.clone());
+        for player in [Player::Player1, Player::Player2, Player::Player1] {
+            m.score_point(player);
+        }
+        let court = analyze_match(config, m.get_point_events()).player1.court;
+        assert_eq!(court.deuce.service_points_total, 2);
+        assert_eq!(court.deuce.service_points_won, 2);
+        assert_eq!(court.ad.service_points_total, 1);
+        assert_eq!(court.ad.service_points_won, 0);
+        assert_eq!(court.by_server_position.len(), 1);
+        assert!(court.by_returner_position.is_empty());
+    }
+
+    #[test]
+    fn test_sudden_death_tiebreak_session() {
+        let mut config = tiebreak_session_config(7, 1);
+        config.tiebreak_win_by = 1;
+        let m = TennisMatch::new_with_config(config);
+        for _ in 0..6 {
+            m.score_point(Player::Player1);
+            m.score_point(Player::Player2);
+        }
+        let score = m.score_point(Player::Player2);
+        assert_eq!(score.winner, Some(Player::Player2));
+    }
+
+    #[test]
+    fn test_win_probability_series() {
+        let config = tiebreak_session_config(7, 1);
+        let m = TennisMatch::new_with_config(config.clone());
+        for _ in 0..7 {
+            m.score_point(Player::Player1);
+        }
+        let series = compute_win_probability_series(config, m.get_point_events(), 0.6, 0.6);
+        assert_eq!(series.len(), 7);
+        assert!((series[0].player1_match_before - 0.5).abs() < 1e-9);
+        assert!(series[5].player1_match_after > series[0].player1_match_after);
+        assert_eq!(series[6].player1_match_after, 1.0);
+
+        let biggest = compute_biggest_points(
+            tiebreak_session_config(7, 1),
+            m.get_point_events(),
+            0.6,
+            0.6,
+            2,
+        );
+        assert_eq!(biggest.len(), 2);
+        assert!(biggest[0].importance >= biggest[1].importance);
+    }
+
+    #[test]
+    fn test_momentum_turning_points() {
+        let config = padel_match_config(Player::Player1);
+        let m = TennisMatch::new_with_config(config.clone());
+        for _ in 0..12 {
+            m.score_point(Player::Player1);
+        }
+        let data = compute_momentum_turning_points(config, m.get_point_events(), None);
+        assert_eq!(data.phases.len(), 1);
+        assert_eq!(data.phases[0].leader, Some(Player::Player1));
+        let run = &data.turning_points[0];
+        assert_eq!(run.reason, "won 3 straight games");
+        assert_eq!(run.point_number, 12);
+        assert_eq!((run.player1_games, run.player2_games), (2, 0));
+        assert_eq!(run.player1_points, "40");
+    }
+
+    #[test]
+    fn test_simulation() {
+        let config = padel_match_config(Player::Player1);
+        let events = simulate_match_events(config.clone(), 0.65, 0.6, 11, 1_000.0).unwrap();
+        assert_eq!(events[0].timestamp_epoch_secs, 1_000.0);
+        assert_eq!(
+            simulate_match_events(config.clone(), 0.65, 0.6, 11, 1_000.0)
+                .unwrap()
+                .len(),
+            events.len()
+        );
+
+        let m = TennisMatch::new_with_config(config);
+        for event in &events {
+            m.score_point(event.player);
+        }
+        assert!(m.get_score().winner.is_some());
+
+        let m = TennisMatch::new();
+        let odds = m.simulate_odds(0.65, 0.6, 50, 5).unwrap();
+        assert_eq!(odds.runs, 50);
+        assert!(odds.player1_win_probability > 0.5);
+        assert_eq!(
+            m.simulate_odds(1.2, 0.6, 50, 5).unwrap_err(),
+            SimulationError::InvalidModel {
+                player: Player::Player1
+            }
+        );
+        assert_eq!(
+            m.simulate_odds(1.0, 1.0, 50, 5).unwrap_err(),
+            SimulationError::PointLimit
+        );
+    }
+
+    #[test]
+    fn test_game_log() {
+        let config = padel_match_config(Player::Player1);
+        let m = TennisMatch::new_with_config(config.clone());
+        for _ in 0..5 {
+            m.score_point(Player::Player1);
+        }
+        let log = compute_game_log(config, m.get_point_events());
+        assert_eq!(log.len(), 2);
+        assert_eq!(log[0].points, "15-0 30-0 40-0 G");
+        assert_eq!(log[0].held, Some(true));
+        assert_eq!(log[1].server, Player::Player2);
+        assert_eq!(log[1].points, "0-15");
+        assert_eq!(log[1].player1_games_before, 1);
+    }
+
+    #[test]
+    fn test_match_report() {
+        let config = padel_match_config(Player::Player1);
+        let m = TennisMatch::new_with_config(config.clone());
+        for _ in 0..28 {
+            m.score_point(Player::Player1);
+        }
+        let report = compute_match_report(config, m.get_point_events());
+        assert_eq!(report.per_set.len(), 2);
+        assert_eq!(report.per_set[1].set_number, 2);
+        assert_eq!(
+            report.per_set[0].analysis.player1.total_points.points_won,
+            24
+        );
+        assert_eq!(report.analysis.player1.total_points.points_won, 28);
+        assert_eq!(report.momentum.per_set_basic.len(), 2);
+        assert_eq!(report.pace.per_set_durations_seconds.len(), 2);
+    }
+
+    #[test]
+    fn test_momentum_model() {
+        let config = padel_match_config(Player::Player1);
+        let m = TennisMatch::new_with_config(config.clone());
+        for _ in 0..5 {
+            m.score_point(Player::Player1);
+        }
+        let events = m.get_point_events();
+
+        let momentum = compute_match_momentum(config.clone(), events.clone(), None);
+        assert_eq!(momentum.model, momentum.basic);
+
+        let model = MomentumModel::RollingWindow { points: 2 };
+        let momentum = compute_match_momentum(config.clone(), events.clone(), Some(model));
+        assert_eq!(momentum.model, vec![1.0, 2.0, 2.0, 2.0, 2.0]);
+
+        let momentum = compute_match_momentum(config, events, Some(MomentumModel::Games));
+        assert_eq!(momentum.model, vec![0.0, 0.0, 0.0, 1.0, 1.0]);
+    }
+
+    #[test]
+    fn test_end_on_time_without_limit() {
+        let m = TennisMatch::new();
+        assert_eq!(m.end_on_time().unwrap_err(), ScoringError::NoTimeLimit);
+    }
+
+    #[test]
+    fn test_claim_bisque() {
+        let mut config = padel_match_config(Player::Player1);
+        config.golden_point_deuce = None;
+        config.player2_bisques = 1;
+        let m = TennisMatch::new_with_config(config.clone());
+        m.score_point(Player::Player1);
+        let score = m.claim_bisque(Player::Player2).unwrap();
+        assert_eq!(
+            score.current_game,
+            GameScore::Points {
+                player1: "15".to_string(),
+                player2: "15".to_string()
+            }
+        );
+        assert_eq!(
+            m.claim_bisque(Player::Player2).unwrap_err(),
+            ScoringError::NoBisquesLeft {
+                player: Player::Player2
+            }
+        );
+        let events = m.get_point_events();
+        assert!(!events[0].is_bisque);
+        assert!(events[1].is_bisque);
+
+        let analysis = analyze_match(config, events);
+        assert_eq!(analysis.player1.service.service_points_total, 1);
+        assert_eq!(analysis.player2.total_points.points_won, 1);
+
+        m.undo();
+        assert_eq!(m.bisques_left(Player::Player2), 1);
+    }
+
+    #[test]
+    fn test_padel_star_point() {
+        let m = TennisMatch::new_with_config(padel_match_config(Player::Player1));
+        for p in [
+            Player::Player1,
+            Player::Player1,
+            Player::Player1,
+            Player::Player2,
+            Player::Player2,
+            Player::Player2,
+        ] {
+            m.score_point(p);
+        }
+        // Two advantages lost, then the star point decides the game
+        for _ in 0..2 {
+            m.score_point(Player::Player1);
+            m.score_point(Player::Player2);
+        }
+        assert_eq!(m.get_score().deuce_count, 3);
+        let score = m.score_point(Player::Player2);
+        assert_eq!(score.player2_games, vec![1]);
+    }
+
+    fn squash_config() -> RallyConfig {
+        RallyConfig {
+            points_to_win: 11,
+            win_by: 2,
+            cap: None,
+            serve_every: None,
+            games_to_win: 3,
+            first_server: Player::Player1,
+        }
+    }
+
+    #[test]
+    fn test_rally_match_scoring_and_undo() {
+        let m = RallyMatch::new(squash_config());
+        m.score_point(Player::Player1);
+        let score = m.score_point(Player::Player2);
+        assert_eq!(score.player1_points, vec![1]);
+        assert_eq!(score.player2_points, vec![1]);
+        assert_eq!(score.serving, Some(Player::Player2));
+        assert_eq!(m.get_score_text(), "1-1*");
+
+        let score = m.undo();
+        assert_eq!(score.serving, Some(Player::Player1));
+        assert_eq!(m.get_point_events().len(), 1);
+
+        m.new_match();
+        assert!(!m.can_undo());
+    }
+
+    #[test]
+    fn test_rally_match_rejects_earlier_timestamp() {
+        let m = RallyMatch::new(squash_config());
+        m.score_point_at(Player::Player1, Some(1000.0)).unwrap();
+        assert!(m.score_point_at(Player::Player2, Some(999.0)).is_err());
+    }
+
+    #[test]
+    fn test_rally_match_table_tennis_serve() {
+        let m = RallyMatch::new(RallyConfig {
+            serve_every: Some(2),
+            ..squash_config()
+        });
+        m.score_point(Player::Player1);
+        let score = m.score_point(Player::Player1);
+        assert_eq!(score.serving, Some(Player::Player2));
+    }
+
     #[test]
     fn test_new_match_score() {
         let m = TennisMatch::new();
@@ -703,6 +2156,19 @@ mod tests {
             no_ad_scoring: true,
             is_doubles: false,
             first_server_team: None,
+            match_tiebreak_points: None,
+            golden_point_deuce: None,
+            player1_handicap_points: 0,
+            player2_handicap_points: 0,
+            player1_bisques: 0,
+            player2_bisques: 0,
+            time_limit: None,
+            cumulative_games: None,
+            tiebreak_only: false,
+            tiebreak_win_by: 2,
+            tiebreak_cap: None,
+            match_tiebreak_win_by: 2,
+            match_tiebreak_cap: None,
         };
         let m = TennisMatch::new_with_config(config);
         let score = m.get_score();
@@ -719,6 +2185,19 @@ mod tests {
             no_ad_scoring: false,
             is_doubles: true,
             first_server_team: Some(Player::Player1),
+            match_tiebreak_points: None,
+            golden_point_deuce: None,
+            player1_handicap_points: 0,
+            player2_handicap_points: 0,
+            player1_bisques: 0,
+            player2_bisques: 0,
+            time_limit: None,
+            cumulative_games: None,
+            tiebreak_only: false,
+            tiebreak_win_by: 2,
+            tiebreak_cap: None,
+            match_tiebreak_win_by: 2,
+            match_tiebreak_cap: None,
         };
         let m = TennisMatch::new_with_config(config);
         let score = m.get_score();
@@ -844,6 +2323,19 @@ mod tests {
             no_ad_scoring: true,
             is_doubles: false,
             first_server_team: None,
+            match_tiebreak_points: None,
+            golden_point_deuce: None,
+            player1_handicap_points: 0,
+            player2_handicap_points: 0,
+            player1_bisques: 0,
+            player2_bisques: 0,
+            time_limit: None,
+            cumulative_games: None,
+            tiebreak_only: false,
+            tiebreak_win_by: 2,
+            tiebreak_cap: None,
+            match_tiebreak_win_by: 2,
+            match_tiebreak_cap: None,
         };
         let m = TennisMatch::new_with_config(config);
         // Get to deuce
@@ -857,4 +2349,72 @@ mod tests {
         let score = m.score_point(Player::Player1);
         assert_eq!(score.player1_games, vec![1]);
     }
+
+    #[test]
+    fn test_score_point_at_explicit_timestamp() {
+        let m = TennisMatch::new();
+        m.score_point_at(Player::Player1, Some(1_000.0)).unwrap();
+        let score = m.score_point_at(Player::Player2, Some(1_030.5)).unwrap();
+        assert_eq!(
+            score.current_game,
+            GameScore::Points {
+                player1: "15".to_string(),
+                player2: "15".to_string(),
+            }
+        );
+
+        let events = m.get_point_events();
+        assert_eq!(events[0].timestamp_epoch_secs, 1_000.0);
+        assert_eq!(events[1].timestamp_epoch_secs, 1_030.5);
+    }
+
+    #[test]
+    fn test_score_point_at_rejects_out_of_order_timestamp() {
+        let m = TennisMatch::new();
+        m.score_point_at(Player::Player1, Some(2_000.0)).unwrap();
+        let err = m
+            .score_point_at(Player::Player2, Some(1_000.0))
+            .unwrap_err();
+        assert_eq!(
+            err,
+            ScoringError::NonMonotonicTimestamp {
+                previous_epoch_secs: 2_000.0,
+                given_epoch_secs: 1_000.0,
+            }
+        );
+        assert_eq!(m.get_point_events().len(), 1);
+    }
+
+    #[test]
+    fn test_score_point_at_without_timestamp_uses_now() {
+        let m = TennisMatch::new();
+        m.score_point_at(Player::Player1, None).unwrap();
+        assert!(m.get_point_events()[0].timestamp_epoch_secs > 0.0);
+    }
+
+    #[test]
+    fn test_score_text() {
+        let m = TennisMatch::new();
+        assert_eq!(m.get_score_text(), "0-0 *0-0");
+        m.score_point(Player::Player2);
+        assert_eq!(m.get_score_text(), "0-0 *0-15");
+    }
+
+    #[test]
+    fn test_parse_score_text() {
+        let parsed = parse_score_text("6-4 3-6 7-6(5)".to_string()).unwrap();
+        assert_eq!(parsed.sets.len(), 3);
+        assert_eq!(parsed.sets[2].tiebreak_loser_points, Some(5));
+        assert_eq!((parsed.player1_sets, parsed.player2_sets), (2, 1));
+        assert!(!parsed.retired);
+
+        let parsed = parse_score_text("6-4 2-3 time".to_string()).unwrap();
+        assert!(parsed.time_called);
+        assert_eq!((parsed.player1_sets, parsed.player2_sets), (1, 0));
+
+        let parsed = parse_score_text("6-4 4-6 [10-8]".to_string()).unwrap();
+        assert!(parsed.sets[2].is_match_tiebreak);
+
+        assert!(parse_score_text("six-four".to_string()).is_err());
+    }
 }
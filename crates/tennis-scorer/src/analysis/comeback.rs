@@ -0,0 +1,289 @@
+use serde::{Deserialize, Serialize};
+
+use crate::types::Player;
+
+use super::momentum::ends_game;
+use super::types::{PointContext, ScoreSnapshot, point_rank};
+
+/// Where the match winner went ahead for good.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LeadForGood {
+    pub point_number: u32,
+    /// Score before the point.
+    pub score: ScoreSnapshot,
+}
+
+/// Leads held and deficits overcome. A player leads while ahead in sets,
+/// or level in sets and ahead in games (total games in a cumulative-games
+/// match, otherwise games in the current set).
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ComebackStats {
+    /// Most sets behind in a match they went on to win.
+    pub largest_set_deficit_overcome: u8,
+    /// Most games behind in a set they went on to win.
+    pub largest_game_deficit_overcome: u8,
+    /// Most points behind in a game or tiebreak they went on to win.
+    pub largest_point_deficit_overcome: u8,
+    pub points_leading: u32,
+    pub time_leading_seconds: f64,
+    /// Only set for the winner of a finished match.
+    pub lead_for_good: Option<LeadForGood>,
+    /// Times broken back while up a break.
+    pub break_leads_lost: u32,
+    /// Sets lost after being up a break in them.
+    pub sets_lost_after_break_up: u32,
+}
+
+pub fn compute_comeback(points: &[PointContext], player: Player) -> ComebackStats {
+    let mut stats = ComebackStats::default();
+    let winner = match_winner(points);
+
+    if winner == Some(player) {
+        stats.largest_set_deficit_overcome = points
+            .iter()
+            .map(|p| {
+                deficit(
+                    p.score_before.player1_sets,
+                    p.score_before.player2_sets,
+                    player,
+                )
+            })
+            .max()
+            .unwrap_or(0);
+        stats.lead_for_good = lead_for_good(points, player);
+    }
+
+    for (i, p) in points.iter().enumerate() {
+        if leader(&p.score_before) != Some(player) {
+            continue;
+        }
+        stats.points_leading += 1;
+        if let Some(previous) = i.checked_sub(1).map(|i| &points[i]) {
+            stats.time_leading_seconds += p
+                .timestamp
+                .duration_since(previous.timestamp)
+                .unwrap_or_default()
+                .as_secs_f64();
+        }
+    }
+
+    let sets: Vec<&[PointContext]> = points
+        .chunk_by(|a, b| a.set_number == b.set_number)
+        .collect();
+    for (i, set) in sets.iter().enumerate() {
+        let last = &set[set.len() - 1];
+        let set_winner = if i + 1 < sets.len() || ends_set(last) {
+            Some(last.scorer)
+        } else {
+            None
+        };
+        let was_break_up = track_breaks(set, player, &mut stats.break_leads_lost);
+
+        if set_winner == Some(player) {
+            let games_deficit = set
+                .iter()
+                .filter_map(|p| p.score_before.sets.last())
+                .map(|s| deficit(s.player1_games, s.player2_games, player))
+                .max()
+                .unwrap_or(0);
+            stats.largest_game_deficit_overcome =
+                stats.largest_game_deficit_overcome.max(games_deficit);
+        } else if set_winner.is_some() && was_break_up {
+            stats.sets_lost_after_break_up += 1;
+        }
+    }
+
+    let games = points.chunk_by(|a, b| {
+        (a.set_number, a.game_number_in_set) == (b.set_number, b.game_number_in_set)
+    });
+    for game in games {
+        let last = &game[game.len() - 1];
+        if ends_game(last) && last.scorer == player {
+            let points_deficit = game
+                .iter()
+                .map(|p| point_deficit(p, player))
+                .max()
+                .unwrap_or(0);
+            stats.largest_point_deficit_overcome =
+                stats.largest_point_deficit_overcome.max(points_deficit);
+        }
+    }
+
+    stats
+}
+
+/// How far `player` trails, or 0 when level or ahead.
+fn deficit(player1: u8, player2: u8, player: Player) -> u8 {
+    match player {
+        Player::Player1 => player2.saturating_sub(player1),
+        Player::Player2 => player1.saturating_sub(player2),
+    }
+}
+
+fn point_deficit(p: &PointContext, player: Player) -> u8 {
+    if p.is_tiebreak {
+        let set = p.score_before.sets.last();
+        return set
+            .and_then(|s| s.tiebreak_player1_points.zip(s.tiebreak_player2_points))
+            .map_or(0, |(p1, p2)| deficit(p1, p2, player));
+    }
+    let game = &p.score_before.current_game;
+    match game.advantage {
+        Some(ahead) => u8::from(ahead != player),
+        None => {
+            // Points from -30 (owed) to 40, shifted to count up from zero
+            let rank = |points: &str| (point_rank(points).unwrap_or(0) + 2) as u8;
+            deficit(
+                rank(&game.player1_points),
+                rank(&game.player2_points),
+                player,
+            )
+        }
+    }
+}
+
+fn leader(score: &ScoreSnapshot) -> Option<Player> {
+    let (player1, player2) = if score.player1_sets != score.player2_sets {
+        (score.player1_sets as u16, score.player2_sets as u16)
+    } else if let Some(total) = score.total_games {
+        total
+    } else {
+        score
+            .sets
+            .last()
+            .map_or((0, 0), |s| (s.player1_games as u16, s.player2_games as u16))
+    };
+    match player1.cmp(&player2) {
+        std::cmp::Ordering::Greater => Some(Player::Player1),
+        std::cmp::Ordering::Less => Some(Player::Player2),
+        std::cmp::Ordering::Equal => None,
+    }
+}
+
+/// Whether `p` won the set it was played in: it ended a tiebreak, or a
+/// game on set point that left the scorer ahead.
+fn ends_set(p: &PointContext) -> bool {
+    if !ends_game(p) {
+        return false;
+    }
+    if p.is_tiebreak {
+        return true;
+    }
+    p.is_set_point
+        && p.score_before.sets.last().is_some_and(|s| match p.scorer {
+            Player::Player1 => s.player1_games >= s.player2_games,
+            Player::Player2 => s.player2_games >= s.player1_games,
+        })
+}
+
+/// The winner, if the last point finished the match.
+fn match_winner(points: &[PointContext]) -> Option<Player> {
+    let last = points.last()?;
+    (last.is_match_point && ends_set(last)).then_some(last.scorer)
+}
+
+fn lead_for_good(points: &[PointContext], player: Player) -> Option<LeadForGood> {
+    // The match-winning point always leaves the winner ahead, so walk back
+    // from it to the last point before which they were not leading
+    let point = points
+        .iter()
+        .rev()
+        .find(|p| leader(&p.score_before) != Some(player))?;
+    Some(LeadForGood {
+        point_number: point.point_number,
+        score: point.score_before.clone(),
+    })
+}
+
+/// Counts `player` being broken back while up a break in `set`, and
+/// returns whether they were ever up a break in it.
+fn track_breaks(set: &[PointContext], player: Player, break_leads_lost: &mut u32) -> bool {
+    let mut own_breaks = 0u32;
+    let mut opponent_breaks = 0u32;
+    let mut was_break_up = false;
+    for p in set.iter().filter(|p| ends_game(p) && !p.is_tiebreak) {
+        if p.scorer == p.serving_player {
+            continue;
+        }
+        if p.scorer == player {
+            own_breaks += 1;
+        } else {
+            if own_breaks > opponent_breaks {
+                *break_leads_lost += 1;
+            }
+            opponent_breaks += 1;
+        }
+        was_break_up |= own_breaks > opponent_breaks;
+    }
+    was_break_up
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::replay::replay_with_context;
+    use crate::config::MatchConfig;
+    use std::time::{Duration, SystemTime};
+
+    fn ts(secs: u64) -> SystemTime {
+        SystemTime::UNIX_EPOCH + Duration::from_secs(1_000_000 + secs)
+    }
+
+    fn make_events(scorers: &[Player]) -> Vec<(Player, SystemTime)> {
+        scorers
+            .iter()
+            .enumerate()
+            .map(|(i, p)| (*p, ts(i as u64 * 30)))
+            .collect()
+    }
+
+    fn comeback(scorers: &[Player], player: Player) -> ComebackStats {
+        let contexts = replay_with_context(&MatchConfig::default(), &make_events(scorers));
+        compute_comeback(&contexts, player)
+    }
+
+    #[test]
+    fn test_comeback_from_a_set_down() {
+        use Player::*;
+        // Player2 takes the first set 6-0 and leads the second 3-0; Player1
+        // wins the next game from 0-40 and the match 0-6 6-3 6-0
+        let mut scorers = vec![Player2; 24 + 12 + 3];
+        scorers.extend([Player1; 5 + 4 * 5 + 24]);
+        let stats = comeback(&scorers, Player1);
+
+        assert_eq!(stats.largest_set_deficit_overcome, 1);
+        assert_eq!(stats.largest_game_deficit_overcome, 3);
+        assert_eq!(stats.largest_point_deficit_overcome, 3);
+        // Level at a set all until Player1 wins the decider's first game
+        let lead = stats.lead_for_good.as_ref().unwrap();
+        assert_eq!(lead.point_number, 24 + 12 + 3 + 5 + 20 + 4);
+        assert_eq!((lead.score.player1_sets, lead.score.player2_sets), (1, 1));
+        assert_eq!(lead.score.sets.last().unwrap().player1_games, 0);
+        assert_eq!(lead.score.current_game.player1_points, "40");
+
+        let loser = comeback(&scorers, Player2);
+        assert_eq!(loser.largest_set_deficit_overcome, 0);
+        assert!(loser.lead_for_good.is_none());
+        assert_eq!(loser.points_leading, 20 + 40);
+        assert_eq!(loser.time_leading_seconds, 60.0 * 30.0);
+    }
+
+    #[test]
+    fn test_break_lead_lost() {
+        use Player::*;
+        // Player1 holds and breaks for 2-0, is broken back, then loses
+        // the set 2-6
+        let mut scorers = vec![Player1; 8];
+        scorers.extend([Player2; 4 * 6]);
+        let stats = comeback(&scorers, Player1);
+
+        assert_eq!(stats.break_leads_lost, 1);
+        assert_eq!(stats.sets_lost_after_break_up, 1);
+        assert_eq!(stats.points_leading, 4 * 3);
+        assert!(stats.lead_for_good.is_none());
+
+        let winner = comeback(&scorers, Player2);
+        assert_eq!(winner.largest_game_deficit_overcome, 2);
+        assert_eq!(winner.break_leads_lost, 0);
+    }
+}
@@ -0,0 +1,262 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::types::Player;
+
+use super::stats::rate;
+use super::types::{PointContext, point_rank};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Court {
+    Deuce,
+    Ad,
+}
+
+impl Court {
+    /// The court a point is served into: deuce when the points played in
+    /// the game (or tiebreak) so far add up to an even number.
+    pub fn of(point: &PointContext) -> Court {
+        let played = if point.is_tiebreak {
+            point.score_before.sets.last().map_or(0, |s| {
+                u32::from(s.tiebreak_player1_points.unwrap_or(0))
+                    + u32::from(s.tiebreak_player2_points.unwrap_or(0))
+            })
+        } else {
+            let game = &point.score_before.current_game;
+            let rank = |points: &str| i32::from(point_rank(points).unwrap_or(0));
+            let played = rank(&game.player1_points)
+                + rank(&game.player2_points)
+                + i32::from(game.advantage.is_some());
+            played.unsigned_abs()
+        };
+        if played % 2 == 0 {
+            Court::Deuce
+        } else {
+            Court::Ad
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CourtSplit {
+    pub service_points_won: u32,
+    pub service_points_total: u32,
+    pub return_points_won: u32,
+    pub return_points_total: u32,
+    pub break_points_created: u32,
+    pub break_points_converted: u32,
+    pub break_point_conversion_rate: f64,
+}
+
+/// Service points for one server: a singles player, or one member of a
+/// doubles team.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ServerCourtSplit {
+    /// As in the `serve_order` entries.
+    pub position: u8,
+    pub deuce_points_won: u32,
+    pub deuce_points_total: u32,
+    pub ad_points_won: u32,
+    pub ad_points_total: u32,
+}
+
+/// Return points for one returner: a singles player, or one member of a
+/// doubles team.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ReturnerCourtSplit {
+    /// As in `PointContext::returner_position`.
+    pub position: u8,
+    pub return_points_won: u32,
+    pub return_points_total: u32,
+    pub break_points_created: u32,
+    pub break_points_converted: u32,
+    pub break_point_conversion_rate: f64,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CourtStats {
+    pub deuce: CourtSplit,
+    pub ad: CourtSplit,
+    /// One entry per team member who served, by position. Singles players
+    /// have a single entry at position 0.
+    pub by_server_position: Vec<ServerCourtSplit>,
+    /// One entry per team member who returned, by position. Singles
+    /// players have a single entry at position 0.
+    pub by_returner_position: Vec<ReturnerCourtSplit>,
+}
+
+pub fn compute_court_stats(points: &[PointContext], player: Player) -> CourtStats {
+    let mut stats = CourtStats::default();
+    let mut positions: BTreeMap<u8, ServerCourtSplit> = BTreeMap::new();
+    let mut returners: BTreeMap<u8, ReturnerCourtSplit> = BTreeMap::new();
+
+    // Bisques are scored without a rally, so are neither served nor returned
+    for p in points.iter().filter(|p| !p.is_bisque) {
+        let court = Court::of(p);
+        let won = p.scorer == player;
+        let split = match court {
+            Court::Deuce => &mut stats.deuce,
+            Court::Ad => &mut stats.ad,
+        };
+
+        if p.serving_player == player {
+            split.service_points_total += 1;
+            split.service_points_won += u32::from(won);
+
+            let server = positions
+                .entry(p.server_position)
+                .or_insert_with(|| ServerCourtSplit {
+                    position: p.server_position,
+                    ..ServerCourtSplit::default()
+                });
+            match court {
+                Court::Deuce => {
+                    server.deuce_points_total += 1;
+                    server.deuce_points_won += u32::from(won);
+                }
+                Court::Ad => {
+                    server.ad_points_total += 1;
+                    server.ad_points_won += u32::from(won);
+                }
+            }
+        } else {
+            split.return_points_total += 1;
+            split.return_points_won += u32::from(won);
+
+            let returner =
+                returners
+                    .entry(p.returner_position)
+                    .or_insert_with(|| ReturnerCourtSplit {
+                        position: p.returner_position,
+                        ..ReturnerCourtSplit::default()
+                    });
+            returner.return_points_total += 1;
+            returner.return_points_won += u32::from(won);
+            if p.is_break_point {
+                split.break_points_created += 1;
+                split.break_points_converted += u32::from(won);
+                returner.break_points_created += 1;
+                returner.break_points_converted += u32::from(won);
+            }
+        }
+    }
+
+    for split in [&mut stats.deuce, &mut stats.ad] {
+        split.break_point_conversion_rate =
+            rate(split.break_points_converted, split.break_points_created);
+    }
+    for returner in returners.values_mut() {
+        returner.break_point_conversion_rate = rate(
+            returner.break_points_converted,
+            returner.break_points_created,
+        );
+    }
+    stats.by_server_position = positions.into_values().collect();
+    stats.by_returner_position = returners.into_values().collect();
+    stats
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::replay::{replay_with, replay_with_context};
+    use crate::config::{MatchConfig, MatchType};
+    use crate::match_state::MatchState;
+    use crate::rules::TiebreakOnlyRules;
+    use std::time::{Duration, SystemTime};
+
+    fn ts(secs: u64) -> SystemTime {
+        SystemTime::UNIX_EPOCH + Duration::from_secs(1_000_000 + secs)
+    }
+
+    fn make_events(scorers: &[Player]) -> Vec<(Player, SystemTime)> {
+        scorers
+            .iter()
+            .enumerate()
+            .map(|(i, p)| (*p, ts(i as u64 * 30)))
+            .collect()
+    }
+
+    #[test]
+    fn test_courts_follow_points_played() {
+        use Player::*;
+        // Player1 serves: 15-0 30-0 30-15 30-30 40-30, deuce, advantage
+        // Player1, deuce, then Player2 breaks
+        let scorers = [
+            Player1, Player1, Player2, Player2, Player1, Player2, Player1, Player2, Player2,
+            Player2,
+        ];
+        let contexts = replay_with_context(&MatchConfig::default(), &make_events(&scorers));
+        let courts: Vec<Court> = contexts.iter().map(Court::of).collect();
+        use Court::*;
+        assert_eq!(
+            courts,
+            [Deuce, Ad, Deuce, Ad, Deuce, Ad, Deuce, Ad, Deuce, Ad]
+        );
+
+        let server = compute_court_stats(&contexts, Player1);
+        assert_eq!(server.deuce.service_points_total, 5);
+        assert_eq!(server.deuce.service_points_won, 3);
+        assert_eq!(server.ad.service_points_won, 1);
+        assert_eq!(server.by_server_position.len(), 1);
+        assert_eq!(server.by_server_position[0].ad_points_total, 5);
+
+        let returner = compute_court_stats(&contexts, Player2);
+        assert_eq!(returner.ad.return_points_won, 4);
+        assert_eq!(returner.ad.break_points_created, 1);
+        assert_eq!(returner.ad.break_point_conversion_rate, 1.0);
+        assert_eq!(returner.deuce.break_points_created, 0);
+        assert!(returner.by_server_position.is_empty());
+        assert_eq!(returner.by_returner_position.len(), 1);
+        assert_eq!(returner.by_returner_position[0].return_points_won, 6);
+        assert_eq!(returner.by_returner_position[0].break_points_converted, 1);
+        assert!(server.by_returner_position.is_empty());
+    }
+
+    #[test]
+    fn test_tiebreak_courts_and_doubles_positions() {
+        let config = MatchConfig::tiebreaks(7, 1);
+        let scorers = [Player::Player1, Player::Player2, Player::Player2];
+        let contexts = replay_with(
+            &MatchState::new_with(config, &TiebreakOnlyRules),
+            &make_events(&scorers),
+            &TiebreakOnlyRules,
+            None,
+        );
+        let courts: Vec<Court> = contexts.iter().map(Court::of).collect();
+        assert_eq!(courts, [Court::Deuce, Court::Ad, Court::Deuce]);
+
+        let doubles = MatchConfig {
+            match_type: MatchType::Doubles,
+            serve_order: vec![
+                (Player::Player1, 0),
+                (Player::Player2, 0),
+                (Player::Player1, 1),
+                (Player::Player2, 1),
+            ],
+            ..MatchConfig::default()
+        };
+        // Both of Player1's servers hold to love, around a break of Player2
+        let scorers = [Player::Player1; 4 * 3];
+        let contexts = replay_with_context(&doubles, &make_events(&scorers));
+        assert_eq!(contexts[8].server_position, 1);
+
+        let stats = compute_court_stats(&contexts, Player::Player1);
+        let positions: Vec<(u8, u32, u32)> = stats
+            .by_server_position
+            .iter()
+            .map(|s| (s.position, s.deuce_points_won, s.ad_points_won))
+            .collect();
+        assert_eq!(positions, [(0, 2, 2), (1, 2, 2)]);
+
+        // Partners keep their courts, so the break point at 0-40 is returned
+        // from the ad court
+        let returners: Vec<(u8, u32, u32)> = stats
+            .by_returner_position
+            .iter()
+            .map(|r| (r.position, r.return_points_won, r.break_points_created))
+            .collect();
+        assert_eq!(returners, [(0, 2, 0), (1, 2, 1)]);
+    }
+}
@@ -0,0 +1,325 @@
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::types::Player;
+
+use super::momentum::ends_game;
+use super::stats::rate;
+use super::types::PointContext;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FatigueOptions {
+    /// Length of each elapsed-time bucket. Only buckets that hold a point
+    /// are reported, and a zero length reports none.
+    pub bucket: Duration,
+    /// Upper bounds, in seconds and ascending, of the pace buckets; the
+    /// last bucket takes every slower point.
+    pub pace_bounds_seconds: Vec<f64>,
+    /// How far the service points won rate must move between the two
+    /// halves of the match to count as a trend.
+    pub trend_threshold: f64,
+}
+
+impl Default for FatigueOptions {
+    fn default() -> Self {
+        Self {
+            bucket: Duration::from_secs(20 * 60),
+            pace_bounds_seconds: vec![25.0, 40.0, 60.0],
+            trend_threshold: 0.05,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PhaseStats {
+    pub points_won: u32,
+    pub points_won_percentage: f64,
+    pub service_games_played: u32,
+    pub service_games_held: u32,
+    pub hold_percentage: f64,
+}
+
+/// Points started between `start_seconds` and `end_seconds` after the
+/// first point.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TimeBucket {
+    pub start_seconds: f64,
+    pub end_seconds: f64,
+    pub points_played: u32,
+    /// Average gap before each point in the bucket, not counting the
+    /// opening point of the match.
+    pub average_point_interval_seconds: f64,
+    pub player1: PhaseStats,
+    pub player2: PhaseStats,
+}
+
+/// Points that followed the previous point by `min_interval_seconds` up to
+/// `max_interval_seconds`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PaceBucket {
+    pub min_interval_seconds: f64,
+    /// `None` for the slowest bucket.
+    pub max_interval_seconds: Option<f64>,
+    pub points_played: u32,
+    pub average_point_interval_seconds: f64,
+    pub player1: PhaseStats,
+    pub player2: PhaseStats,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Trend {
+    Improving,
+    Steady,
+    Fading,
+}
+
+/// Service points won in the first and second half of the match by
+/// elapsed time. Serve is used rather than all points so that one
+/// player's trend does not simply mirror the other's.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PlayerTrend {
+    pub early_service_points_won_percentage: f64,
+    pub late_service_points_won_percentage: f64,
+    pub change: f64,
+    pub trend: Trend,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FatigueAnalysis {
+    pub by_time: Vec<TimeBucket>,
+    pub by_pace: Vec<PaceBucket>,
+    pub player1_trend: PlayerTrend,
+    pub player2_trend: PlayerTrend,
+}
+
+/// How each player's results change with time on court and with the pace
+/// of play.
+pub fn compute_fatigue(points: &[PointContext], options: &FatigueOptions) -> FatigueAnalysis {
+    let elapsed: Vec<f64> = points
+        .iter()
+        .map(|p| {
+            p.timestamp
+                .duration_since(points[0].timestamp)
+                .unwrap_or_default()
+                .as_secs_f64()
+        })
+        .collect();
+    let intervals: Vec<Option<f64>> = (0..points.len())
+        .map(|i| {
+            i.checked_sub(1)
+                .map(|previous| elapsed[i] - elapsed[previous])
+        })
+        .collect();
+
+    let bucket_seconds = options.bucket.as_secs_f64();
+    let mut buckets: BTreeMap<u64, Vec<usize>> = BTreeMap::new();
+    if bucket_seconds > 0.0 {
+        for (i, e) in elapsed.iter().enumerate() {
+            buckets
+                .entry((e / bucket_seconds) as u64)
+                .or_default()
+                .push(i);
+        }
+    }
+    let by_time = buckets
+        .into_iter()
+        .map(|(b, indices)| {
+            let start_seconds = b as f64 * bucket_seconds;
+            TimeBucket {
+                start_seconds,
+                end_seconds: start_seconds + bucket_seconds,
+                points_played: indices.len() as u32,
+                average_point_interval_seconds: average_interval(&indices, &intervals),
+                player1: phase_stats(points, &indices, Player::Player1),
+                player2: phase_stats(points, &indices, Player::Player2),
+            }
+        })
+        .collect();
+
+    let mut by_pace = Vec::new();
+    if points.len() > 1 {
+        let mut min_interval_seconds = 0.0;
+        let bounds = options.pace_bounds_seconds.iter().copied().map(Some);
+        for max_interval_seconds in bounds.chain([None]) {
+            let indices: Vec<usize> = (0..points.len())
+                .filter(|&i| {
+                    intervals[i].is_some_and(|gap| {
+                        gap >= min_interval_seconds
+                            && max_interval_seconds.is_none_or(|max| gap < max)
+                    })
+                })
+                .collect();
+            by_pace.push(PaceBucket {
+                min_interval_seconds,
+                max_interval_seconds,
+                points_played: indices.len() as u32,
+                average_point_interval_seconds: average_interval(&indices, &intervals),
+                player1: phase_stats(points, &indices, Player::Player1),
+                player2: phase_stats(points, &indices, Player::Player2),
+            });
+            min_interval_seconds = max_interval_seconds.unwrap_or(min_interval_seconds);
+        }
+    }
+
+    let halfway = elapsed.last().copied().unwrap_or(0.0) / 2.0;
+    let split = elapsed.partition_point(|&e| e < halfway);
+    FatigueAnalysis {
+        by_time,
+        by_pace,
+        player1_trend: player_trend(points, split, Player::Player1, options.trend_threshold),
+        player2_trend: player_trend(points, split, Player::Player2, options.trend_threshold),
+    }
+}
+
+fn average_interval(indices: &[usize], intervals: &[Option<f64>]) -> f64 {
+    let gaps: Vec<f64> = indices.iter().filter_map(|&i| intervals[i]).collect();
+    if gaps.is_empty() {
+        0.0
+    } else {
+        gaps.iter().sum::<f64>() / gaps.len() as f64
+    }
+}
+
+/// Stats over the points at `indices`. A service game counts in the
+/// bucket where it ended.
+fn phase_stats(points: &[PointContext], indices: &[usize], player: Player) -> PhaseStats {
+    let mut stats = PhaseStats::default();
+    for p in indices.iter().map(|&i| &points[i]) {
+        if p.scorer == player {
+            stats.points_won += 1;
+        }
+        if p.serving_player == player && !p.is_tiebreak && ends_game(p) {
+            stats.service_games_played += 1;
+            if p.scorer == player {
+                stats.service_games_held += 1;
+            }
+        }
+    }
+    stats.points_won_percentage = rate(stats.points_won, indices.len() as u32);
+    stats.hold_percentage = rate(stats.service_games_held, stats.service_games_played);
+    stats
+}
+
+fn player_trend(
+    points: &[PointContext],
+    split: usize,
+    player: Player,
+    threshold: f64,
+) -> PlayerTrend {
+    let serve_rate = |points: &[PointContext]| {
+        let served = points.iter().filter(|p| p.serving_player == player);
+        let won = served.clone().filter(|p| p.scorer == player).count();
+        rate(won as u32, served.count() as u32)
+    };
+    let early = serve_rate(&points[..split]);
+    let late = serve_rate(&points[split..]);
+    let change = late - early;
+    let trend = if change >= threshold {
+        Trend::Improving
+    } else if change <= -threshold {
+        Trend::Fading
+    } else {
+        Trend::Steady
+    };
+    PlayerTrend {
+        early_service_points_won_percentage: early,
+        late_service_points_won_percentage: late,
+        change,
+        trend,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::replay::replay_with_context;
+    use crate::config::MatchConfig;
+    use std::time::SystemTime;
+
+    fn contexts(scorers: &[Player], gap_secs: u64) -> Vec<PointContext> {
+        let events: Vec<(Player, SystemTime)> = scorers
+            .iter()
+            .enumerate()
+            .map(|(i, p)| {
+                let ts = SystemTime::UNIX_EPOCH + Duration::from_secs(i as u64 * gap_secs);
+                (*p, ts)
+            })
+            .collect();
+        replay_with_context(&MatchConfig::default(), &events)
+    }
+
+    #[test]
+    fn test_time_buckets_and_trend() {
+        // Six holds to love at a minute a point, then Player1 loses every
+        // point for six games: Player1 fades, while Player2, who holds
+        // throughout, stays steady
+        let mut scorers = Vec::new();
+        for game in 0..6 {
+            let server = if game % 2 == 0 {
+                Player::Player1
+            } else {
+                Player::Player2
+            };
+            scorers.extend([server; 4]);
+        }
+        scorers.extend([Player::Player2; 24]);
+        let points = contexts(&scorers, 60);
+        let analysis = compute_fatigue(&points, &FatigueOptions::default());
+
+        assert_eq!(analysis.by_time.len(), 3);
+        let first = &analysis.by_time[0];
+        assert_eq!(first.points_played, 20);
+        assert_eq!(first.average_point_interval_seconds, 60.0);
+        assert_eq!(first.player1.points_won, 12);
+        assert_eq!(first.player1.service_games_played, 3);
+        assert_eq!(first.player1.hold_percentage, 1.0);
+        let last = &analysis.by_time[2];
+        assert_eq!(last.points_played, 8);
+        assert_eq!(last.player2.points_won_percentage, 1.0);
+
+        assert_eq!(analysis.player1_trend.trend, Trend::Fading);
+        assert_eq!(
+            analysis.player1_trend.early_service_points_won_percentage,
+            1.0
+        );
+        assert_eq!(analysis.player2_trend.trend, Trend::Steady);
+    }
+
+    #[test]
+    fn test_pace_buckets() {
+        let points = contexts(&[Player::Player1; 8], 30);
+        let analysis = compute_fatigue(&points, &FatigueOptions::default());
+
+        let counts: Vec<u32> = analysis.by_pace.iter().map(|b| b.points_played).collect();
+        assert_eq!(counts, [0, 7, 0, 0]);
+        assert_eq!(analysis.by_pace[1].min_interval_seconds, 25.0);
+        assert_eq!(analysis.by_pace[1].max_interval_seconds, Some(40.0));
+        assert_eq!(analysis.by_pace[3].max_interval_seconds, None);
+        assert_eq!(analysis.by_pace[1].player1.service_games_held, 1);
+
+        let empty = compute_fatigue(&[], &FatigueOptions::default());
+        assert!(empty.by_time.is_empty());
+        assert!(empty.by_pace.is_empty());
+        assert_eq!(empty.player1_trend.trend, Trend::Steady);
+    }
+
+    #[test]
+    fn test_degenerate_bucket_sizes() {
+        let points = contexts(&[Player::Player1; 8], 30);
+        let with_bucket = |bucket| FatigueOptions {
+            bucket,
+            ..FatigueOptions::default()
+        };
+
+        let none = compute_fatigue(&points, &with_bucket(Duration::ZERO));
+        assert!(none.by_time.is_empty());
+        assert_eq!(none.by_pace[1].points_played, 7);
+
+        // One bucket per point rather than one per nanosecond played
+        let tiny = compute_fatigue(&points, &with_bucket(Duration::from_nanos(1)));
+        assert_eq!(tiny.by_time.len(), 8);
+        assert!(tiny.by_time.iter().all(|b| b.points_played == 1));
+    }
+}
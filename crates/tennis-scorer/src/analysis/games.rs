@@ -0,0 +1,239 @@
+use serde::{Deserialize, Serialize};
+
+use crate::types::Player;
+
+use super::momentum::ends_game;
+use super::types::{GameScore, PointContext, ScoreSnapshot, before_time_called, parse_point};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GameLogEntry {
+    pub set_number: u32,
+    pub game_number: u32,
+    pub server: Player,
+    /// `None` while the game is unfinished.
+    pub winner: Option<Player>,
+    /// Whether the server won; `None` for tiebreaks and unfinished games.
+    pub held: Option<bool>,
+    pub is_tiebreak: bool,
+    /// Score after each point, server first, e.g. "15-0 30-0 30-15 40-15 G".
+    pub points: String,
+    pub deuce_count: u8,
+    pub duration_seconds: f64,
+    pub score_before: ScoreSnapshot,
+}
+
+/// One entry per game (a tiebreak counts as one game), in match order.
+/// Points played after time was called belong to no game.
+pub fn compute_game_log(points: &[PointContext]) -> Vec<GameLogEntry> {
+    before_time_called(points)
+        .chunk_by(|a, b| {
+            (a.set_number, a.game_number_in_set) == (b.set_number, b.game_number_in_set)
+        })
+        .map(game_entry)
+        .collect()
+}
+
+fn game_entry(game: &[PointContext]) -> GameLogEntry {
+    let first = &game[0];
+    let last = &game[game.len() - 1];
+    let server = first.serving_player;
+    let winner = ends_game(last).then_some(last.scorer);
+
+    // Score after each point; the game-winning point has none
+    let after: Vec<Option<GameScore>> = game
+        .iter()
+        .enumerate()
+        .map(|(i, p)| match game.get(i + 1) {
+            Some(next) => Some(next.score_before.current_game.clone()),
+            None if winner.is_some() => None,
+            None => Some(advance(
+                &p.score_before.current_game,
+                p.scorer,
+                p.is_tiebreak,
+            )),
+        })
+        .collect();
+    let labels: Vec<String> = after
+        .iter()
+        .map(|score| match score {
+            Some(score) => label(score, server),
+            None => "G".to_string(),
+        })
+        .collect();
+
+    GameLogEntry {
+        set_number: first.set_number,
+        game_number: first.game_number_in_set,
+        server,
+        winner,
+        held: winner.filter(|_| !first.is_tiebreak).map(|w| w == server),
+        is_tiebreak: first.is_tiebreak,
+        points: labels.join(" "),
+        deuce_count: after
+            .iter()
+            .flatten()
+            .chain([&first.score_before.current_game])
+            .map(|score| score.deuce_count)
+            .max()
+            .unwrap_or(0),
+        duration_seconds: last
+            .timestamp
+            .duration_since(first.timestamp)
+            .unwrap_or_default()
+            .as_secs_f64(),
+        score_before: first.score_before.clone(),
+    }
+}
+
+/// "30-15" with the server's score first; "A-40" or "40-A" on advantage.
+pub(super) fn label(score: &GameScore, server: Player) -> String {
+    let (server_points, returner_points) = label_sides(score, server);
+    format!("{server_points}-{returner_points}")
+}
+
+/// The server's and returner's sides of `label`.
+pub(super) fn label_sides(score: &GameScore, server: Player) -> (&str, &str) {
+    let (server_points, returner_points) = match server {
+        Player::Player1 => (&score.player1_points, &score.player2_points),
+        Player::Player2 => (&score.player2_points, &score.player1_points),
+    };
+    match score.advantage {
+        Some(player) if player == server => ("A", returner_points),
+        Some(_) => (server_points, "A"),
+        None => (server_points, returner_points),
+    }
+}
+
+/// The score after `scorer` wins a point that did not end the game.
+fn advance(score: &GameScore, scorer: Player, is_tiebreak: bool) -> GameScore {
+    let mut next = score.clone();
+    let (own, other) = match scorer {
+        Player::Player1 => (&mut next.player1_points, &next.player2_points),
+        Player::Player2 => (&mut next.player2_points, &next.player1_points),
+    };
+    if is_tiebreak {
+        *own = (own.parse::<u32>().unwrap_or(0) + 1).to_string();
+        return next;
+    }
+    if score.is_deuce {
+        next.is_deuce = false;
+        next.advantage = Some(scorer);
+    } else if score.advantage.is_some() {
+        next.advantage = None;
+        next.is_deuce = true;
+        next.deuce_count += 1;
+    } else if own == "30" && other == "40" {
+        *own = "40".to_string();
+        next.is_deuce = true;
+        next.deuce_count += 1;
+    } else {
+        *own = parse_point(own)
+            .and_then(|point| point.increment())
+            .map_or_else(|| "40".to_string(), |point| point.to_string());
+    }
+    next
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::replay::{replay_with, replay_with_context};
+    use crate::config::{Handicap, MatchConfig};
+    use crate::match_state::MatchState;
+    use crate::rules::{ScoringRules, TiebreakOnlyRules};
+    use std::time::{Duration, SystemTime};
+
+    fn ts(secs: u64) -> SystemTime {
+        SystemTime::UNIX_EPOCH + Duration::from_secs(1_000_000 + secs)
+    }
+
+    fn make_events(scorers: &[Player]) -> Vec<(Player, SystemTime)> {
+        scorers
+            .iter()
+            .enumerate()
+            .map(|(i, p)| (*p, ts(i as u64 * 30)))
+            .collect()
+    }
+
+    fn game_log(config: &MatchConfig, scorers: &[Player]) -> Vec<GameLogEntry> {
+        compute_game_log(&replay_with_context(config, &make_events(scorers)))
+    }
+
+    fn game_log_with(
+        config: &MatchConfig,
+        scorers: &[Player],
+        rules: &dyn ScoringRules,
+    ) -> Vec<GameLogEntry> {
+        let start = MatchState::new_with(config.clone(), rules);
+        compute_game_log(&replay_with(&start, &make_events(scorers), rules, None))
+    }
+
+    #[test]
+    fn test_hold_and_break() {
+        use Player::*;
+        let scorers = [
+            Player1, Player1, Player2, Player1, Player1, // hold: 15-0 30-0 30-15 40-15 G
+            Player1, Player1, Player1, Player1, // Player1 breaks to love
+        ];
+        let log = game_log(&MatchConfig::default(), &scorers);
+
+        assert_eq!(log.len(), 2);
+        assert_eq!(log[0].points, "15-0 30-0 30-15 40-15 G");
+        assert_eq!(log[0].server, Player1);
+        assert_eq!(log[0].held, Some(true));
+        assert_eq!(log[0].duration_seconds, 120.0);
+
+        assert_eq!(log[1].points, "0-15 0-30 0-40 G");
+        assert_eq!(log[1].winner, Some(Player1));
+        assert_eq!(log[1].held, Some(false));
+        assert_eq!(log[1].game_number, 2);
+        assert_eq!(log[1].score_before.sets[0].player1_games, 1);
+    }
+
+    #[test]
+    fn test_deuce_game_and_unfinished_point() {
+        use Player::*;
+        let mut scorers = vec![Player1, Player1, Player1, Player2, Player2, Player2];
+        scorers.extend([Player2, Player1, Player1, Player2]);
+        let log = game_log(&MatchConfig::default(), &scorers);
+
+        assert_eq!(log.len(), 1);
+        assert_eq!(
+            log[0].points,
+            "15-0 30-0 40-0 40-15 40-30 40-40 40-A 40-40 A-40 40-40"
+        );
+        assert_eq!(log[0].winner, None);
+        assert_eq!(log[0].held, None);
+        assert_eq!(log[0].deuce_count, 3);
+    }
+
+    #[test]
+    fn test_tiebreak_game() {
+        let config = MatchConfig::tiebreaks(7, 1);
+        let mut scorers = vec![Player::Player2];
+        scorers.extend([Player::Player1; 7]);
+        let log = game_log_with(&config, &scorers, &TiebreakOnlyRules);
+
+        assert_eq!(log.len(), 1);
+        assert!(log[0].is_tiebreak);
+        assert_eq!(log[0].held, None);
+        assert_eq!(log[0].winner, Some(Player::Player1));
+        assert_eq!(log[0].points, "0-1 1-1 2-1 3-1 4-1 5-1 6-1 G");
+    }
+
+    #[test]
+    fn test_handicap_game_climbs_from_owed_points() {
+        let config = MatchConfig {
+            handicap: Handicap {
+                player1_points: -1,
+                player2_points: 1,
+                ..Default::default()
+            },
+            ..MatchConfig::default()
+        };
+        let log = game_log(&config, &[Player::Player1; 5]);
+
+        assert_eq!(log[0].points, "0-15 15-15 30-15 40-15 G");
+        assert_eq!(log[0].held, Some(true));
+    }
+}
@@ -1,14 +1,49 @@
+mod comeback;
+mod court;
+mod fatigue;
+mod games;
 mod momentum;
 mod pace;
+mod pickleball;
+mod probability;
 mod replay;
+mod score_states;
+mod segments;
 mod stats;
+mod turning_points;
 mod types;
 
-pub use momentum::{MomentumData, compute_momentum};
-pub use pace::{GameDuration, PaceData, SetDuration, compute_pace};
-pub use replay::replay_with_context;
+pub use comeback::{ComebackStats, LeadForGood};
+pub use court::{Court, CourtSplit, CourtStats, ReturnerCourtSplit, ServerCourtSplit};
+pub use fatigue::{
+    FatigueAnalysis, FatigueOptions, PaceBucket, PhaseStats, PlayerTrend, TimeBucket, Trend,
+    compute_fatigue,
+};
+pub use games::{GameLogEntry, compute_game_log};
+pub use momentum::{
+    Cumulative, Ewma, GamesBased, MomentumData, MomentumKind, MomentumModel, RollingWindow,
+    Weighted, compute_momentum, compute_momentum_with,
+};
+pub use pace::{GameDuration, PaceData, SetDuration, compute_pace, compute_pace_with_limit};
+pub use pickleball::replay_pickleball;
+pub use probability::{
+    ServeWinProbabilities, WinProbabilities, WinProbabilityPoint, annotate_importance,
+    annotate_importance_with, biggest_points, win_probabilities, win_probabilities_with,
+    win_probability_series, win_probability_series_with,
+};
+pub use replay::{replay_recording, replay_with, replay_with_context, replay_with_time_called};
+pub use score_states::{
+    GameOutcomeStats, PlayerScoreStates, ScoreStateAnalysis, ScoreStateStats, compute_score_states,
+};
+pub use segments::{
+    MatchReport, Segment, SegmentAnalysis, SetAnalysis, compute_report, compute_segment_analysis,
+    compute_set_analysis, filter_points,
+};
 pub use stats::{
     BreakPointStats, ClutchStats, ConversionRateStats, DeuceStats, MatchAnalysis, PlayerStats,
     ServiceStats, StreakStats, TiebreakStats, TotalPointsStats, compute_analysis,
 };
+pub use turning_points::{
+    MomentumPhase, TurningPoint, TurningPointData, TurningPointOptions, compute_turning_points,
+};
 pub use types::{GameScore, PointContext, PointEndType, ScoreSnapshot, SetScore};
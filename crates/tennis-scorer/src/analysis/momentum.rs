@@ -10,84 +10,192 @@ pub struct MomentumData {
     pub weighted: Vec<f64>,
     pub per_set_basic: Vec<Vec<f64>>,
     pub per_set_weighted: Vec<Vec<f64>>,
+    /// The line of the model passed to `compute_momentum_with`.
+    #[serde(default)]
+    pub model: Vec<f64>,
+    #[serde(default)]
+    pub per_set_model: Vec<Vec<f64>>,
 }
 
-pub fn compute_momentum(points: &[PointContext]) -> MomentumData {
-    if points.is_empty() {
-        return MomentumData {
-            basic: vec![],
-            weighted: vec![],
-            per_set_basic: vec![],
-            per_set_weighted: vec![],
-        };
+/// Turns a run of points into a momentum line: one value per point,
+/// positive while Player1 has the momentum.
+pub trait MomentumModel {
+    fn line(&self, points: &[PointContext]) -> Vec<f64>;
+}
+
+/// Running total of points won, +1 for Player1 and -1 for Player2.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Cumulative;
+
+/// Running total with break, set and deuce points weighted up.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Weighted;
+
+/// Exponentially weighted moving average of points won, between -1 and 1.
+/// A point's weight halves every `half_life` points after it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Ewma {
+    pub half_life: f64,
+}
+
+/// Net points won over the last `points` points.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RollingWindow {
+    pub points: usize,
+}
+
+/// Running total of games won, stepping on the last point of each game.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct GamesBased;
+
+/// A momentum model chosen at runtime, e.g. from a request parameter.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "model", rename_all = "snake_case")]
+pub enum MomentumKind {
+    Cumulative,
+    Weighted,
+    Ewma { half_life: f64 },
+    RollingWindow { points: usize },
+    Games,
+}
+
+fn sign(p: &PointContext) -> f64 {
+    if p.scorer == Player::Player1 {
+        1.0
+    } else {
+        -1.0
     }
+}
 
-    let mut basic = Vec::with_capacity(points.len());
-    let mut weighted = Vec::with_capacity(points.len());
+impl MomentumModel for Cumulative {
+    fn line(&self, points: &[PointContext]) -> Vec<f64> {
+        points
+            .iter()
+            .scan(0.0, |total, p| {
+                *total += sign(p);
+                Some(*total)
+            })
+            .collect()
+    }
+}
 
-    let mut cumulative_basic = 0.0f64;
-    let mut cumulative_weighted = 0.0f64;
+impl MomentumModel for Weighted {
+    fn line(&self, points: &[PointContext]) -> Vec<f64> {
+        points
+            .iter()
+            .scan(0.0, |total, p| {
+                *total += sign(p) * compute_weight(p);
+                Some(*total)
+            })
+            .collect()
+    }
+}
 
-    for p in points {
-        let sign = if p.scorer == Player::Player1 {
-            1.0
+impl MomentumModel for Ewma {
+    fn line(&self, points: &[PointContext]) -> Vec<f64> {
+        let alpha = if self.half_life > 0.0 {
+            1.0 - 0.5f64.powf(1.0 / self.half_life)
         } else {
-            -1.0
+            1.0
         };
+        points
+            .iter()
+            .scan(0.0, |average, p| {
+                *average += alpha * (sign(p) - *average);
+                Some(*average)
+            })
+            .collect()
+    }
+}
 
-        cumulative_basic += sign;
-        basic.push(cumulative_basic);
+impl MomentumModel for RollingWindow {
+    fn line(&self, points: &[PointContext]) -> Vec<f64> {
+        let window = self.points.max(1);
+        let mut total = 0.0;
+        points
+            .iter()
+            .enumerate()
+            .map(|(i, p)| {
+                total += sign(p);
+                if i >= window {
+                    total -= sign(&points[i - window]);
+                }
+                total
+            })
+            .collect()
+    }
+}
 
-        let weight = compute_weight(p);
-        cumulative_weighted += sign * weight;
-        weighted.push(cumulative_weighted);
+impl MomentumModel for GamesBased {
+    fn line(&self, points: &[PointContext]) -> Vec<f64> {
+        points
+            .iter()
+            .scan(0.0, |total, p| {
+                if ends_game(p) {
+                    *total += sign(p);
+                }
+                Some(*total)
+            })
+            .collect()
     }
+}
 
-    // Per-set partitioning
-    let mut per_set_basic: Vec<Vec<f64>> = Vec::new();
-    let mut per_set_weighted: Vec<Vec<f64>> = Vec::new();
-    let mut current_set = 0u32;
-    let mut set_basic = Vec::new();
-    let mut set_weighted = Vec::new();
-    let mut set_cum_basic = 0.0f64;
-    let mut set_cum_weighted = 0.0f64;
+/// Whether the scorer had game (or tiebreak) point, so the point won them
+/// the game.
+pub(super) fn ends_game(p: &PointContext) -> bool {
+    if !p.is_game_point {
+        return false;
+    }
+    if p.is_tiebreak {
+        let set = p.score_before.sets.last();
+        let points = set.and_then(|s| s.tiebreak_player1_points.zip(s.tiebreak_player2_points));
+        return points.is_some_and(|(p1, p2)| match p.scorer {
+            Player::Player1 => p1 >= p2,
+            Player::Player2 => p2 >= p1,
+        });
+    }
+    if p.scorer == p.serving_player {
+        !p.is_break_point || p.is_golden_point
+    } else {
+        p.is_break_point
+    }
+}
 
-    for p in points {
-        if p.set_number != current_set {
-            if current_set != 0 {
-                per_set_basic.push(set_basic);
-                per_set_weighted.push(set_weighted);
+impl MomentumModel for MomentumKind {
+    fn line(&self, points: &[PointContext]) -> Vec<f64> {
+        match *self {
+            MomentumKind::Cumulative => Cumulative.line(points),
+            MomentumKind::Weighted => Weighted.line(points),
+            MomentumKind::Ewma { half_life } => Ewma { half_life }.line(points),
+            MomentumKind::RollingWindow { points: window } => {
+                RollingWindow { points: window }.line(points)
             }
-            current_set = p.set_number;
-            set_basic = Vec::new();
-            set_weighted = Vec::new();
-            set_cum_basic = 0.0;
-            set_cum_weighted = 0.0;
+            MomentumKind::Games => GamesBased.line(points),
         }
+    }
+}
 
-        let sign = if p.scorer == Player::Player1 {
-            1.0
-        } else {
-            -1.0
-        };
-        set_cum_basic += sign;
-        set_basic.push(set_cum_basic);
+pub fn compute_momentum(points: &[PointContext]) -> MomentumData {
+    compute_momentum_with(points, &Cumulative)
+}
 
-        let weight = compute_weight(p);
-        set_cum_weighted += sign * weight;
-        set_weighted.push(set_cum_weighted);
-    }
-    // Push the last set
-    if !set_basic.is_empty() {
-        per_set_basic.push(set_basic);
-        per_set_weighted.push(set_weighted);
-    }
+/// The basic and weighted lines plus `model`'s, each for the whole match
+/// and restarting every set.
+pub fn compute_momentum_with(points: &[PointContext], model: &dyn MomentumModel) -> MomentumData {
+    let sets: Vec<&[PointContext]> = points
+        .chunk_by(|a, b| a.set_number == b.set_number)
+        .collect();
+    let per_set = |model: &dyn MomentumModel| -> Vec<Vec<f64>> {
+        sets.iter().map(|set| model.line(set)).collect()
+    };
 
     MomentumData {
-        basic,
-        weighted,
-        per_set_basic,
-        per_set_weighted,
+        basic: Cumulative.line(points),
+        weighted: Weighted.line(points),
+        per_set_basic: per_set(&Cumulative),
+        per_set_weighted: per_set(&Weighted),
+        model: model.line(points),
+        per_set_model: per_set(model),
     }
 }
 
@@ -120,8 +228,10 @@ fn compute_weight(p: &PointContext) -> f64 {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::analysis::replay::replay_with_context;
+    use crate::analysis::replay::{replay_with, replay_with_context};
     use crate::config::MatchConfig;
+    use crate::match_state::MatchState;
+    use crate::rules::TiebreakOnlyRules;
     use std::time::{Duration, SystemTime};
 
     fn ts(secs: u64) -> SystemTime {
@@ -165,6 +275,75 @@ mod tests {
         assert_eq!(m.basic, vec![1.0, 0.0, 1.0]);
     }
 
+    fn line(model: &dyn MomentumModel, scorers: &[Player]) -> Vec<f64> {
+        let contexts = replay_with_context(&MatchConfig::default(), &make_events(scorers));
+        model.line(&contexts)
+    }
+
+    #[test]
+    fn test_ewma() {
+        use Player::*;
+        let m = line(&Ewma { half_life: 1.0 }, &[Player1, Player1, Player2]);
+        assert_eq!(m, vec![0.5, 0.75, -0.125]);
+
+        let m = line(&Ewma { half_life: 0.0 }, &[Player1, Player2]);
+        assert_eq!(m, vec![1.0, -1.0]);
+    }
+
+    #[test]
+    fn test_rolling_window() {
+        use Player::*;
+        let m = line(
+            &RollingWindow { points: 2 },
+            &[Player1, Player1, Player2, Player2, Player1],
+        );
+        assert_eq!(m, vec![1.0, 2.0, 0.0, -2.0, 0.0]);
+    }
+
+    #[test]
+    fn test_games_based() {
+        let mut scorers = vec![Player::Player1; 4];
+        scorers.extend([Player::Player2; 4]);
+        scorers.extend([Player::Player2; 2]);
+        let m = line(&GamesBased, &scorers);
+        assert_eq!(m[2], 0.0);
+        assert_eq!(m[3], 1.0);
+        assert_eq!(m[7], 0.0);
+        assert_eq!(m[9], 0.0);
+
+        // The selectable kind matches the model it names
+        assert_eq!(line(&MomentumKind::Games, &scorers), m);
+
+        let mut scorers = vec![Player::Player1; 5];
+        scorers.extend([Player::Player2; 7]);
+        scorers.push(Player::Player1);
+        let config = MatchConfig::tiebreaks(7, 2);
+        let contexts = replay_with(
+            &MatchState::new_with(config, &TiebreakOnlyRules),
+            &make_events(&scorers),
+            &TiebreakOnlyRules,
+            None,
+        );
+        let m = GamesBased.line(&contexts);
+        assert_eq!(m[10], 0.0);
+        assert_eq!(m[11], -1.0);
+        assert_eq!(m[12], -1.0);
+    }
+
+    #[test]
+    fn test_model_restarts_each_set() {
+        let mut scorers = vec![Player::Player1; 24];
+        scorers.extend([Player::Player2; 2]);
+        let contexts = replay_with_context(&MatchConfig::default(), &make_events(&scorers));
+        let m = compute_momentum_with(&contexts, &GamesBased);
+
+        assert_eq!(m.model.len(), 26);
+        assert_eq!(m.model[23], 6.0);
+        assert_eq!(m.per_set_model.len(), 2);
+        assert_eq!(m.per_set_model[1], vec![0.0, 0.0]);
+        assert_eq!(m.basic, compute_momentum(&contexts).model);
+    }
+
     #[test]
     fn test_per_set_partitioning() {
         let config = MatchConfig::default();
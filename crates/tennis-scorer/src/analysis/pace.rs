@@ -1,3 +1,5 @@
+use std::time::Duration;
+
 use serde::{Deserialize, Serialize};
 
 use super::types::PointContext;
@@ -22,6 +24,13 @@ pub struct PaceData {
     pub per_set_durations: Vec<SetDuration>,
     pub total_duration_seconds: f64,
     pub point_intervals: Vec<f64>,
+    /// Slot length of a timed match (see [`compute_pace_with_limit`]).
+    #[serde(default)]
+    pub time_limit_seconds: Option<f64>,
+    /// Points played after time was called, i.e. the sudden-death point or
+    /// tiebreak.
+    #[serde(default)]
+    pub points_after_time_limit: u32,
 }
 
 pub fn compute_pace(points: &[PointContext]) -> PaceData {
@@ -32,6 +41,8 @@ pub fn compute_pace(points: &[PointContext]) -> PaceData {
             per_set_durations: vec![],
             total_duration_seconds: 0.0,
             point_intervals: vec![],
+            time_limit_seconds: None,
+            points_after_time_limit: 0,
         };
     }
 
@@ -132,16 +143,29 @@ pub fn compute_pace(points: &[PointContext]) -> PaceData {
         per_set_durations: set_durations,
         total_duration_seconds: total,
         point_intervals: intervals,
+        time_limit_seconds: None,
+        points_after_time_limit: 0,
+    }
+}
+
+/// Pace of a timed match: as [`compute_pace`], plus how many points were
+/// played after time was called (those flagged `is_overtime`).
+pub fn compute_pace_with_limit(points: &[PointContext], time_limit: Duration) -> PaceData {
+    let points_after_time_limit = points.iter().filter(|p| p.is_overtime).count() as u32;
+    PaceData {
+        time_limit_seconds: Some(time_limit.as_secs_f64()),
+        points_after_time_limit,
+        ..compute_pace(points)
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::analysis::replay::replay_with_context;
+    use crate::analysis::replay::{replay_with_context, replay_with_time_called};
     use crate::config::MatchConfig;
     use crate::types::Player;
-    use std::time::{Duration, SystemTime};
+    use std::time::SystemTime;
 
     fn ts(secs: u64) -> SystemTime {
         SystemTime::UNIX_EPOCH + Duration::from_secs(1_000_000 + secs)
@@ -213,4 +237,18 @@ mod tests {
         assert_eq!(pace.per_game_durations[0].set_number, 1);
         assert_eq!(pace.per_game_durations[0].game_number, 1);
     }
+
+    #[test]
+    fn test_pace_with_time_limit() {
+        let config = MatchConfig::default();
+        // Points every 30 seconds; time is called before the last
+        let events = make_events(&[Player::Player1; 6]);
+        let contexts = replay_with_time_called(&config, &events, 5);
+        let pace = compute_pace_with_limit(&contexts, Duration::from_secs(120));
+
+        assert_eq!(pace.time_limit_seconds, Some(120.0));
+        assert_eq!(pace.points_after_time_limit, 1);
+        assert_eq!(pace.total_duration_seconds, 150.0);
+        assert_eq!(compute_pace(&contexts).time_limit_seconds, None);
+    }
 }
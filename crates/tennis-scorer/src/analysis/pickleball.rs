@@ -0,0 +1,166 @@
+use std::time::SystemTime;
+
+use crate::pickleball::{PickleballConfig, PickleballGame, PickleballState};
+use crate::types::Player;
+
+use super::types::{GameScore, PointContext, ScoreSnapshot, SetScore};
+
+/// Replay pickleball rallies into point contexts, so that `compute_pace` and
+/// `compute_momentum` can be used on them.
+///
+/// Pickleball games take the place of sets: `set_number` is the game number,
+/// `game_number_in_set` is always 1, and completed games appear in
+/// `score_before.sets` with their points as games. Only the serving side can
+/// score, so a rally is a game, set or match point when winning it on serve
+/// would decide that; there are no break points.
+pub fn replay_pickleball(
+    config: &PickleballConfig,
+    events: &[(Player, SystemTime)],
+) -> Vec<PointContext> {
+    let mut state = PickleballState::new(config.clone());
+    let mut contexts = Vec::with_capacity(events.len());
+
+    for (i, &(scorer, timestamp)) in events.iter().enumerate() {
+        let Some(serving) = state.serving() else {
+            break;
+        };
+        let after_serve_win = state.score_rally(serving);
+        let games_before = state.games().len();
+        let is_game_point =
+            after_serve_win.games().len() > games_before || after_serve_win.winner().is_some();
+
+        contexts.push(PointContext {
+            point_number: i as u32 + 1,
+            scorer,
+            timestamp,
+            serving_player: serving,
+            score_before: snapshot(&state),
+            is_break_point: false,
+            is_game_point,
+            is_set_point: is_game_point,
+            is_match_point: after_serve_win.winner().is_some(),
+            game_number_in_set: 1,
+            set_number: games_before as u32,
+            is_tiebreak: false,
+            point_end_type: None,
+            is_golden_point: false,
+            importance: None,
+            server_position: 0,
+            returner_position: 0,
+            is_bisque: false,
+            is_overtime: false,
+        });
+
+        state = state.score_rally(scorer);
+    }
+
+    contexts
+}
+
+fn snapshot(state: &PickleballState) -> ScoreSnapshot {
+    let (player1_sets, player2_sets) = state.games_won();
+    let sets = state
+        .games()
+        .iter()
+        .filter(|game| game.winner().is_some())
+        .map(|game| {
+            let (player1_games, player2_games) = game.points();
+            SetScore {
+                player1_games,
+                player2_games,
+                is_tiebreak: false,
+                tiebreak_player1_points: None,
+                tiebreak_player2_points: None,
+            }
+        })
+        .collect();
+    let (player1_points, player2_points) = state
+        .games()
+        .last()
+        .filter(|game| matches!(game, PickleballGame::Playing { .. }))
+        .map(PickleballGame::points)
+        .unwrap_or_default();
+
+    ScoreSnapshot {
+        sets,
+        current_game: GameScore {
+            player1_points: player1_points.to_string(),
+            player2_points: player2_points.to_string(),
+            is_deuce: false,
+            advantage: None,
+            deuce_count: 0,
+        },
+        player1_sets,
+        player2_sets,
+        total_games: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::{compute_momentum, compute_pace};
+    use crate::config::MatchType;
+    use std::time::{Duration, UNIX_EPOCH};
+
+    fn ts(secs: u64) -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(1_000_000 + secs)
+    }
+
+    fn singles() -> PickleballConfig {
+        PickleballConfig {
+            match_type: MatchType::Singles,
+            ..PickleballConfig::default()
+        }
+    }
+
+    fn make_events(winners: &[Player]) -> Vec<(Player, SystemTime)> {
+        winners
+            .iter()
+            .enumerate()
+            .map(|(i, &p)| (p, ts(i as u64 * 30)))
+            .collect()
+    }
+
+    #[test]
+    fn test_replay_flags_game_and_match_points() {
+        // Each side wins a game to love, then player 1 serves out the decider
+        let mut winners = vec![Player::Player1; 11];
+        winners.extend([Player::Player2; 11]);
+        winners.extend([Player::Player1; 11]);
+        let contexts = replay_pickleball(&singles(), &make_events(&winners));
+
+        assert!(contexts[10].is_game_point);
+        assert!(!contexts[10].is_match_point);
+        assert!(!contexts[9].is_game_point);
+        assert_eq!(contexts[11].set_number, 2);
+        assert_eq!(contexts[11].score_before.player1_sets, 1);
+        assert_eq!(contexts[11].score_before.sets[0].player1_games, 11);
+        assert!(contexts.last().unwrap().is_match_point);
+        assert!(contexts.iter().all(|c| !c.is_break_point));
+    }
+
+    #[test]
+    fn test_pace_and_momentum_apply() {
+        let mut winners = vec![Player::Player1; 11];
+        winners.extend([Player::Player2; 3]);
+        let contexts = replay_pickleball(&singles(), &make_events(&winners));
+        assert_eq!(contexts.len(), 14);
+
+        let pace = compute_pace(&contexts);
+        assert_eq!(pace.per_set_durations.len(), 2);
+        assert_eq!(pace.per_set_durations[0].duration_seconds, 300.0);
+
+        let momentum = compute_momentum(&contexts);
+        assert_eq!(momentum.basic[10], 11.0);
+        assert_eq!(momentum.per_set_basic.len(), 2);
+    }
+
+    #[test]
+    fn test_replay_stops_at_match_end() {
+        // Game two costs player 1 one rally to win the serve back
+        let winners = vec![Player::Player1; 30];
+        let contexts = replay_pickleball(&singles(), &make_events(&winners));
+        assert_eq!(contexts.len(), 23);
+    }
+}
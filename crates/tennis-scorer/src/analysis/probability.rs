@@ -0,0 +1,920 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::MatchConfig;
+use crate::game::GameState;
+use crate::invariant::InvariantError;
+use crate::match_state::MatchState;
+use crate::rules::{ScoringRules, SetContext, SetKind, StandardRules};
+use crate::set::SetState;
+use crate::tiebreak::TiebreakState;
+use crate::types::Player;
+
+use super::types::PointContext;
+
+/// Level scores (games in a set played out, points in a tiebreak) from which
+/// the rest of a win-by-two race is solved in closed form. Reaching them is
+/// so unlikely that the result is exact to floating-point precision.
+const LEVEL_LIMIT: u8 = 50;
+
+/// Each player's chance of winning a point on their own serve, between 0
+/// and 1. In doubles these are per team.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ServeWinProbabilities {
+    pub player1: f64,
+    pub player2: f64,
+}
+
+impl ServeWinProbabilities {
+    /// Player1's chance of winning a point served by `server`.
+    fn player1_wins_point(&self, server: Player) -> f64 {
+        match server {
+            Player::Player1 => self.player1,
+            Player::Player2 => 1.0 - self.player2,
+        }
+    }
+}
+
+/// Player1's chances of winning the current game (or tiebreak), set and
+/// match; Player2's are the complements.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct WinProbabilities {
+    pub player1_game: f64,
+    pub player1_set: f64,
+    pub player1_match: f64,
+}
+
+/// Win probabilities either side of one point.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WinProbabilityPoint {
+    pub point_number: u32,
+    pub scorer: Player,
+    pub before: WinProbabilities,
+    pub after: WinProbabilities,
+}
+
+/// Exact chances of winning from `state`, treating every point as
+/// independent with the given serve-point-win probabilities.
+///
+/// The Markov chain follows the match's config through the standard rules:
+/// no-ad and golden-point games, handicap starts, tiebreak targets, margins
+/// and caps, match tiebreaks, advantage final sets and tiebreak-only
+/// matches. A time limit is not modelled.
+///
+/// A match in progress is validated first, so a hand-built or imported
+/// state that scoring could not reach is an error.
+pub fn win_probabilities(
+    state: &MatchState,
+    serve: &ServeWinProbabilities,
+) -> Result<WinProbabilities, InvariantError> {
+    win_probabilities_with(state, serve, &StandardRules)
+}
+
+/// As [`win_probabilities`], for a match played under `rules`. Between sets
+/// the rules are assumed to look only at sets won and played and, for a
+/// format decided on total games, the games lead.
+pub fn win_probabilities_with(
+    state: &MatchState,
+    serve: &ServeWinProbabilities,
+    rules: &dyn ScoringRules,
+) -> Result<WinProbabilities, InvariantError> {
+    match state {
+        MatchState::Completed { winner, .. } => Ok(settled(*winner)),
+        MatchState::Playing { config, .. } => {
+            state.validate_with(rules)?;
+            Ok(Model::new(config, rules, *serve).live(state))
+        }
+    }
+}
+
+/// Win probabilities before and after each point of a match replayed from
+/// its start, e.g. the output of `replay_with_context`.
+pub fn win_probability_series(
+    config: &MatchConfig,
+    points: &[PointContext],
+    serve: &ServeWinProbabilities,
+) -> Vec<WinProbabilityPoint> {
+    win_probability_series_with(config, points, serve, &StandardRules)
+}
+
+/// As [`win_probability_series`], for a match played under `rules`.
+pub fn win_probability_series_with(
+    config: &MatchConfig,
+    points: &[PointContext],
+    serve: &ServeWinProbabilities,
+    rules: &dyn ScoringRules,
+) -> Vec<WinProbabilityPoint> {
+    let mut model = Model::new(config, rules, *serve);
+    let mut state = MatchState::new_with(config.clone(), rules);
+    let mut before = model.live(&state);
+    let mut series = Vec::with_capacity(points.len());
+
+    for point in points {
+        state = state.score_point_with(point.scorer, rules);
+        let after = model.live(&state);
+        series.push(WinProbabilityPoint {
+            point_number: point.point_number,
+            scorer: point.scorer,
+            before,
+            after,
+        });
+        before = after;
+    }
+
+    series
+}
+
+/// Fill in each point's `importance`: how far Player1's chance of winning
+/// the match swings on it, following Carl Morris. The points must be a
+/// replay of the whole match, e.g. the output of `replay_with_context`.
+pub fn annotate_importance(
+    config: &MatchConfig,
+    points: &mut [PointContext],
+    serve: &ServeWinProbabilities,
+) {
+    annotate_importance_with(config, points, serve, &StandardRules);
+}
+
+/// As [`annotate_importance`], for a match played under `rules`.
+pub fn annotate_importance_with(
+    config: &MatchConfig,
+    points: &mut [PointContext],
+    serve: &ServeWinProbabilities,
+    rules: &dyn ScoringRules,
+) {
+    let mut model = Model::new(config, rules, *serve);
+    let mut state = MatchState::new_with(config.clone(), rules);
+
+    for point in points {
+        let mut player1_match = |scorer| {
+            let after = state.score_point_with(scorer, rules);
+            match after.winner() {
+                Some(winner) => indicator(winner),
+                None => model.live(&after).player1_match,
+            }
+        };
+        let won = player1_match(Player::Player1);
+        let lost = player1_match(Player::Player2);
+        point.importance = Some(won - lost);
+        state = state.score_point_with(point.scorer, rules);
+    }
+}
+
+/// The `limit` most important annotated points, biggest first. Ties keep
+/// match order.
+pub fn biggest_points(points: &[PointContext], limit: usize) -> Vec<&PointContext> {
+    let mut ranked: Vec<&PointContext> = points.iter().filter(|p| p.importance.is_some()).collect();
+    ranked.sort_by(|a, b| b.importance.unwrap().total_cmp(&a.importance.unwrap()));
+    ranked.truncate(limit);
+    ranked
+}
+
+/// Probabilities once `winner` has won the match.
+fn settled(winner: Player) -> WinProbabilities {
+    let won = indicator(winner);
+    WinProbabilities {
+        player1_game: won,
+        player1_set: won,
+        player1_match: won,
+    }
+}
+
+fn indicator(player: Player) -> f64 {
+    match player {
+        Player::Player1 => 1.0,
+        Player::Player2 => 0.0,
+    }
+}
+
+/// Player1's chance of winning a race that is level and needs a two-point
+/// (or two-game) lead, playing pairs in which Player1 wins each with
+/// `first` and `second`.
+fn win_by_two(first: f64, second: f64) -> f64 {
+    let won = first * second;
+    let lost = (1.0 - first) * (1.0 - second);
+    if won + lost > 0.0 {
+        won / (won + lost)
+    } else {
+        0.5
+    }
+}
+
+/// How a set ended, and who serves the game after it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct SetEnd {
+    player1_games: u8,
+    player2_games: u8,
+    winner: Player,
+    next_server: Player,
+}
+
+type SetOutcomes = HashMap<SetEnd, f64>;
+
+fn add_outcomes(into: &mut SetOutcomes, from: &SetOutcomes, weight: f64) {
+    for (end, probability) in from {
+        *into.entry(*end).or_default() += weight * probability;
+    }
+}
+
+/// Tiebreak results by [winner][last point's server], Player1 first.
+type TiebreakOutcomes = [[f64; 2]; 2];
+
+fn index(player: Player) -> usize {
+    match player {
+        Player::Player1 => 0,
+        Player::Player2 => 1,
+    }
+}
+
+const PLAYERS: [Player; 2] = [Player::Player1, Player::Player2];
+
+/// Where a match stands between sets, as far as the match-level rules are
+/// assumed to look.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct MatchStage {
+    player1_sets: u8,
+    player2_sets: u8,
+    played: usize,
+    /// Player1's games minus Player2's, kept when total games decide.
+    lead: i16,
+    is_final_set: bool,
+    kind: SetKind,
+}
+
+struct Model<'a> {
+    config: &'a MatchConfig,
+    rules: &'a dyn ScoringRules,
+    serve: ServeWinProbabilities,
+    /// Set outcomes from the start of a game, by set and by games and server.
+    sets: HashMap<(bool, SetKind, u8, u8, Player), SetOutcomes>,
+    matches: HashMap<(MatchStage, Player), f64>,
+}
+
+impl<'a> Model<'a> {
+    fn new(
+        config: &'a MatchConfig,
+        rules: &'a dyn ScoringRules,
+        serve: ServeWinProbabilities,
+    ) -> Self {
+        Self {
+            config,
+            rules,
+            serve,
+            sets: HashMap::new(),
+            matches: HashMap::new(),
+        }
+    }
+
+    /// Probabilities from `state`, which scoring reached or which has been
+    /// validated. Without any sets the match is taken from its first point.
+    fn live(&mut self, state: &MatchState) -> WinProbabilities {
+        let sets = match state {
+            MatchState::Completed { winner, .. } => return settled(*winner),
+            MatchState::Playing { sets, .. } => sets,
+        };
+        let first_set;
+        let (current, completed) = match sets.split_last() {
+            Some(split) => split,
+            None => {
+                let set = self.rules.set_context(self.config, &[]);
+                first_set = SetState::new_with(self.rules, &set);
+                (&first_set, &[][..])
+            }
+        };
+        let set = self.rules.set_context(self.config, completed);
+        let server = state.serving_player().unwrap_or(Player::Player1);
+
+        let (player1_game, outcomes) = match current {
+            SetState::Playing {
+                player1_games,
+                player2_games,
+                tiebreak:
+                    Some(TiebreakState::Playing {
+                        player1_points,
+                        player2_points,
+                        target_points,
+                    }),
+                ..
+            } => {
+                let played = player1_points + player2_points;
+                let first_server = if MatchState::tiebreak_server_offset(played) % 2 == 1 {
+                    server.opponent()
+                } else {
+                    server
+                };
+                let tiebreak = self.tiebreak(
+                    &set,
+                    *target_points,
+                    (*player1_points, *player2_points),
+                    first_server,
+                    &mut HashMap::new(),
+                );
+                let outcomes = self.tiebreak_set_ends(
+                    &set,
+                    &tiebreak,
+                    (*player1_games, *player2_games),
+                    first_server,
+                );
+                (tiebreak[0][0] + tiebreak[0][1], outcomes)
+            }
+            SetState::Playing {
+                player1_games,
+                player2_games,
+                current_game,
+                ..
+            } => {
+                let player1_game = self.game(current_game, server);
+                let mut outcomes = SetOutcomes::new();
+                for (winner, probability) in [
+                    (Player::Player1, player1_game),
+                    (Player::Player2, 1.0 - player1_game),
+                ] {
+                    let after =
+                        self.after_game(&set, (*player1_games, *player2_games), winner, server);
+                    add_outcomes(&mut outcomes, &after, probability);
+                }
+                (player1_game, outcomes)
+            }
+            // Not reached by scoring, which starts the next set at once
+            SetState::Completed { winner, .. } => {
+                let won = indicator(*winner);
+                return WinProbabilities {
+                    player1_game: won,
+                    player1_set: won,
+                    player1_match: self.match_from(&mut sets.to_vec(), server),
+                };
+            }
+        };
+
+        let player1_set = outcomes
+            .iter()
+            .filter(|(end, _)| end.winner == Player::Player1)
+            .map(|(_, probability)| probability)
+            .sum();
+
+        let mut completed = completed.to_vec();
+        let player1_match = outcomes
+            .iter()
+            .map(|(end, probability)| probability * self.after_set(&mut completed, end))
+            .sum();
+
+        WinProbabilities {
+            player1_game,
+            player1_set,
+            player1_match,
+        }
+    }
+
+    /// Player1's chance of winning `game` with `server` serving.
+    fn game(&self, game: &GameState, server: Player) -> f64 {
+        let deciding_deuce = self.config.deciding_deuce();
+        let point = self.serve.player1_wins_point(server);
+        match game {
+            GameState::Completed(winner) => indicator(*winner),
+            GameState::Deuce { .. } if deciding_deuce.is_none() => win_by_two(point, point),
+            _ => {
+                let won = game.score_point_deciding_at(Player::Player1, deciding_deuce);
+                let lost = game.score_point_deciding_at(Player::Player2, deciding_deuce);
+                point * self.game(&won, server) + (1.0 - point) * self.game(&lost, server)
+            }
+        }
+    }
+
+    /// Results of a tiebreak to `target` from `points`, whose first point
+    /// was served by `first_server`.
+    fn tiebreak(
+        &self,
+        set: &SetContext,
+        target: u8,
+        points: (u8, u8),
+        first_server: Player,
+        memo: &mut HashMap<(u8, u8), TiebreakOutcomes>,
+    ) -> TiebreakOutcomes {
+        if let Some(outcomes) = memo.get(&points) {
+            return *outcomes;
+        }
+        let (p1, p2) = points;
+        let server_of = |played: u8| {
+            if MatchState::tiebreak_server_offset(played) % 2 == 1 {
+                first_server.opponent()
+            } else {
+                first_server
+            }
+        };
+        let played = p1 + p2;
+        let server = server_of(played);
+        let mut outcomes = [[0.0; 2]; 2];
+
+        if p1 == p2 && p1 >= LEVEL_LIMIT.max(target).min(250) {
+            let next_server = server_of(played + 1);
+            let player1 = win_by_two(
+                self.serve.player1_wins_point(server),
+                self.serve.player1_wins_point(next_server),
+            );
+            outcomes[0][index(next_server)] = player1;
+            outcomes[1][index(next_server)] = 1.0 - player1;
+        } else {
+            let point = self.serve.player1_wins_point(server);
+            for (scorer, probability) in [(Player::Player1, point), (Player::Player2, 1.0 - point)]
+            {
+                let next = match scorer {
+                    Player::Player1 => (p1 + 1, p2),
+                    Player::Player2 => (p1, p2 + 1),
+                };
+                match self.rules.tiebreak_winner(next.0, next.1, target, set) {
+                    Some(winner) => outcomes[index(winner)][index(server)] += probability,
+                    None => {
+                        let rest = self.tiebreak(set, target, next, first_server, memo);
+                        for winner in 0..2 {
+                            for last in 0..2 {
+                                outcomes[winner][last] += probability * rest[winner][last];
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        memo.insert(points, outcomes);
+        outcomes
+    }
+
+    /// Set ends after a tiebreak played at `games`.
+    fn tiebreak_set_ends(
+        &self,
+        set: &SetContext,
+        tiebreak: &TiebreakOutcomes,
+        games: (u8, u8),
+        first_server: Player,
+    ) -> SetOutcomes {
+        let mut outcomes = SetOutcomes::new();
+        for winner in PLAYERS {
+            for last_server in PLAYERS {
+                let probability = tiebreak[index(winner)][index(last_server)];
+                if probability == 0.0 {
+                    continue;
+                }
+                // Mirrors the state machine: without a serve order service
+                // follows the game count, with one the rules pick
+                let next_server = if self.config.serve_order.is_empty() {
+                    first_server.opponent()
+                } else {
+                    PLAYERS[self.rules.next_set_first_server(
+                        index(first_server),
+                        index(last_server),
+                        PLAYERS.len(),
+                        set,
+                    )]
+                };
+                let (player1_games, player2_games) = match winner {
+                    Player::Player1 => (games.0 + 1, games.1),
+                    Player::Player2 => (games.0, games.1 + 1),
+                };
+                *outcomes
+                    .entry(SetEnd {
+                        player1_games,
+                        player2_games,
+                        winner,
+                        next_server,
+                    })
+                    .or_default() += probability;
+            }
+        }
+        outcomes
+    }
+
+    /// Set outcomes once `winner` takes the game `server` served at `games`.
+    fn after_game(
+        &mut self,
+        set: &SetContext,
+        games: (u8, u8),
+        winner: Player,
+        server: Player,
+    ) -> SetOutcomes {
+        let (player1_games, player2_games) = match winner {
+            Player::Player1 => (games.0 + 1, games.1),
+            Player::Player2 => (games.0, games.1 + 1),
+        };
+        match self.rules.set_winner(player1_games, player2_games, set) {
+            Some(winner) => SetOutcomes::from([(
+                SetEnd {
+                    player1_games,
+                    player2_games,
+                    winner,
+                    next_server: server.opponent(),
+                },
+                1.0,
+            )]),
+            None => self.set_from(set, (player1_games, player2_games), server.opponent()),
+        }
+    }
+
+    /// Set outcomes from the start of a game at `games`, `server` to serve.
+    fn set_from(&mut self, set: &SetContext, games: (u8, u8), server: Player) -> SetOutcomes {
+        let key = (set.is_final_set, set.kind, games.0, games.1, server);
+        if let Some(outcomes) = self.sets.get(&key) {
+            return outcomes.clone();
+        }
+
+        let starts_in_tiebreak = games == (0, 0) && self.rules.set_starts_in_tiebreak(set);
+        let outcomes = if starts_in_tiebreak || self.rules.tiebreak_due(games.0, games.1, set) {
+            let target = self.rules.tiebreak_target(set);
+            let tiebreak = self.tiebreak(set, target, (0, 0), server, &mut HashMap::new());
+            self.tiebreak_set_ends(set, &tiebreak, games, server)
+        } else {
+            let new_game = self.rules.new_game(set);
+            let hold = self.game(&new_game, server);
+            if games.0 == games.1 && games.0 >= LEVEL_LIMIT {
+                let player1 = win_by_two(hold, self.game(&new_game, server.opponent()));
+                let (level, ahead) = (games.0, games.0 + 2);
+                SetOutcomes::from([
+                    (
+                        SetEnd {
+                            player1_games: ahead,
+                            player2_games: level,
+                            winner: Player::Player1,
+                            next_server: server,
+                        },
+                        player1,
+                    ),
+                    (
+                        SetEnd {
+                            player1_games: level,
+                            player2_games: ahead,
+                            winner: Player::Player2,
+                            next_server: server,
+                        },
+                        1.0 - player1,
+                    ),
+                ])
+            } else {
+                let mut outcomes = SetOutcomes::new();
+                for (winner, probability) in
+                    [(Player::Player1, hold), (Player::Player2, 1.0 - hold)]
+                {
+                    let after = self.after_game(set, games, winner, server);
+                    add_outcomes(&mut outcomes, &after, probability);
+                }
+                outcomes
+            }
+        };
+
+        self.sets.insert(key, outcomes.clone());
+        outcomes
+    }
+
+    /// Player1's chance of winning the match once the set after the
+    /// `completed` sets has ended as `end`.
+    fn after_set(&mut self, completed: &mut Vec<SetState>, end: &SetEnd) -> f64 {
+        completed.push(SetState::Completed {
+            winner: end.winner,
+            player1_games: end.player1_games,
+            player2_games: end.player2_games,
+            tiebreak_score: None,
+        });
+        let probability = self.match_from(completed, end.next_server);
+        completed.pop();
+        probability
+    }
+
+    /// Player1's chance of winning the match from the start of the set
+    /// after the `completed` sets.
+    fn match_from(&mut self, completed: &mut Vec<SetState>, server: Player) -> f64 {
+        if let Some(winner) = self.rules.match_winner(self.config, completed) {
+            return indicator(winner);
+        }
+        let set = self.rules.set_context(self.config, completed);
+        let key = (self.stage(completed, &set), server);
+        if let Some(probability) = self.matches.get(&key) {
+            return *probability;
+        }
+
+        let outcomes = self.set_from(&set, (0, 0), server);
+        let probability = outcomes
+            .iter()
+            .map(|(end, probability)| probability * self.after_set(completed, end))
+            .sum();
+
+        self.matches.insert(key, probability);
+        probability
+    }
+
+    fn stage(&self, completed: &[SetState], set: &SetContext) -> MatchStage {
+        let won = |player| {
+            completed
+                .iter()
+                .filter(|set| set.winner() == Some(player))
+                .count() as u8
+        };
+        let lead = if self.rules.decided_by_total_games() {
+            completed.iter().fold(0i16, |lead, set| {
+                let (p1, p2) = set.games();
+                lead + i16::from(p1) - i16::from(p2)
+            })
+        } else {
+            0
+        };
+        MatchStage {
+            player1_sets: won(Player::Player1),
+            player2_sets: won(Player::Player2),
+            played: completed.len(),
+            lead,
+            is_final_set: set.is_final_set,
+            kind: set.kind,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::{replay_with, replay_with_context};
+    use crate::config::TiebreakRule;
+    use crate::rules::{CumulativeGamesRules, TiebreakOnlyRules};
+    use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+    const EPSILON: f64 = 1e-9;
+
+    fn ts(secs: u64) -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(1_000_000 + secs)
+    }
+
+    fn make_events(scorers: &[Player]) -> Vec<(Player, SystemTime)> {
+        scorers
+            .iter()
+            .enumerate()
+            .map(|(i, &p)| (p, ts(i as u64 * 30)))
+            .collect()
+    }
+
+    fn serve(player1: f64, player2: f64) -> ServeWinProbabilities {
+        ServeWinProbabilities { player1, player2 }
+    }
+
+    fn play(state: MatchState, scorers: &[Player]) -> MatchState {
+        scorers.iter().fold(state, |s, &p| s.score_point(p))
+    }
+
+    #[test]
+    fn test_even_players_are_even() {
+        let state = MatchState::new(MatchConfig::default());
+        let probabilities = win_probabilities(&state, &serve(0.6, 0.6)).unwrap();
+        assert!((probabilities.player1_match - 0.5).abs() < EPSILON);
+        assert!((probabilities.player1_set - 0.5).abs() < EPSILON);
+    }
+
+    #[test]
+    fn test_game_probability_matches_closed_form() {
+        // Holding serve from love: p^4 (1 + 4q + 10q^2) + 20 p^3 q^3 * p^2 / (p^2 + q^2)
+        let (p, q) = (0.6f64, 0.4f64);
+        let expected = p.powi(4) * (1.0 + 4.0 * q + 10.0 * q * q)
+            + 20.0 * p.powi(3) * q.powi(3) * p * p / (p * p + q * q);
+        let state = MatchState::new(MatchConfig::default());
+        let probabilities = win_probabilities(&state, &serve(p, 0.6)).unwrap();
+        assert!((probabilities.player1_game - expected).abs() < EPSILON);
+    }
+
+    #[test]
+    fn test_no_ad_deuce_is_a_single_point() {
+        let config = MatchConfig {
+            no_ad_scoring: true,
+            ..MatchConfig::default()
+        };
+        let deuce = [Player::Player1, Player::Player2].repeat(3);
+        let state = play(MatchState::new(config), &deuce);
+        let probabilities = win_probabilities(&state, &serve(0.7, 0.6)).unwrap();
+        assert!((probabilities.player1_game - 0.7).abs() < EPSILON);
+
+        let state = play(MatchState::new(MatchConfig::default()), &deuce);
+        let probabilities = win_probabilities(&state, &serve(0.7, 0.6)).unwrap();
+        assert!((probabilities.player1_game - 0.49 / 0.58).abs() < EPSILON);
+    }
+
+    #[test]
+    fn test_stronger_server_is_favoured_and_completed_is_certain() {
+        let state = MatchState::new(MatchConfig::default());
+        let probabilities = win_probabilities(&state, &serve(0.7, 0.6)).unwrap();
+        assert!(probabilities.player1_match > 0.5);
+        assert!(probabilities.player1_match < 1.0);
+
+        let won = play(state, &[Player::Player2; 48]);
+        assert_eq!(won.winner(), Some(Player::Player2));
+        assert_eq!(
+            win_probabilities(&won, &serve(0.7, 0.6))
+                .unwrap()
+                .player1_match,
+            0.0
+        );
+    }
+
+    #[test]
+    fn test_unreachable_states_are_rejected() {
+        let with_sets = |new_sets: Vec<SetState>| {
+            let mut state = MatchState::new(MatchConfig::default());
+            if let MatchState::Playing { sets, .. } = &mut state {
+                *sets = new_sets;
+            }
+            state
+        };
+        let serve = serve(0.7, 0.6);
+
+        assert_eq!(
+            win_probabilities(&with_sets(Vec::new()), &serve),
+            Err(InvariantError::InvalidSetSequence)
+        );
+        let finished_set_in_play = with_sets(vec![SetState::Completed {
+            winner: Player::Player1,
+            player1_games: 6,
+            player2_games: 0,
+            tiebreak_score: None,
+        }]);
+        assert!(win_probabilities(&finished_set_in_play, &serve).is_err());
+    }
+
+    #[test]
+    fn test_sudden_death_tiebreak() {
+        let config = MatchConfig {
+            sets_to_win: 1,
+            tiebreak_rule: TiebreakRule {
+                win_by: 1,
+                cap: None,
+            },
+            ..MatchConfig::default()
+        };
+        let mut scorers = Vec::new();
+        for _ in 0..6 {
+            scorers.extend([Player::Player1; 4]);
+            scorers.extend([Player::Player2; 4]);
+        }
+        scorers.extend([Player::Player1, Player::Player2].repeat(6));
+        let state = play(MatchState::new(config), &scorers);
+
+        // 6-6 in the tiebreak, point 13 served by Player1: one point decides
+        assert_eq!(state.serving_player(), Some(Player::Player1));
+        let probabilities = win_probabilities(&state, &serve(0.7, 0.6)).unwrap();
+        assert!((probabilities.player1_match - 0.7).abs() < EPSILON);
+        assert!((probabilities.player1_set - 0.7).abs() < EPSILON);
+    }
+
+    #[test]
+    fn test_advantage_final_set_matches_tiebreak_set_for_even_players() {
+        let config = MatchConfig {
+            final_set_tiebreak: false,
+            ..MatchConfig::default()
+        };
+        let state = MatchState::new(config);
+        let probabilities = win_probabilities(&state, &serve(0.65, 0.65)).unwrap();
+        assert!((probabilities.player1_match - 0.5).abs() < EPSILON);
+    }
+
+    #[test]
+    fn test_cumulative_games_overtime() {
+        // Player2 trails 15-19 in games after taking the fifth set, so
+        // overtime goes on until Player1 wins a game or Player2 wins four
+        let mut scorers = vec![Player::Player1; 40];
+        for _ in 0..3 {
+            scorers.extend([Player::Player1; 12]);
+            scorers.extend([Player::Player2; 20]);
+        }
+        let rules = CumulativeGamesRules::team_tennis();
+        let state = scorers.iter().fold(
+            MatchState::new_with(MatchConfig::team_tennis(), &rules),
+            |s, &p| s.score_point_with(p, &rules),
+        );
+        assert_eq!(state.total_games(), (19, 15));
+        assert_eq!(state.winner(), None);
+
+        let probabilities = win_probabilities_with(&state, &serve(0.6, 0.6), &rules).unwrap();
+        assert!(probabilities.player1_match > 0.9);
+        assert!(probabilities.player1_match < 1.0);
+    }
+
+    /// Each point's probability is the serve-weighted mean of the two
+    /// states it leads to, which only holds if the model and the state
+    /// machine agree on who serves every point.
+    fn assert_consistent(config: MatchConfig, scorers: &[Player], rules: &dyn ScoringRules) {
+        let serve = serve(0.64, 0.58);
+        let mut state = MatchState::new_with(config, rules);
+        for &scorer in scorers {
+            let Some(server) = state.serving_player() else {
+                break;
+            };
+            let point = serve.player1_wins_point(server);
+            let probabilities =
+                |state: &MatchState| win_probabilities_with(state, &serve, rules).unwrap();
+            let before = probabilities(&state).player1_match;
+            let won = probabilities(&state.score_point_with(Player::Player1, rules));
+            let lost = probabilities(&state.score_point_with(Player::Player2, rules));
+            let expected = point * won.player1_match + (1.0 - point) * lost.player1_match;
+            assert!(
+                (before - expected).abs() < EPSILON,
+                "{before} vs {expected}"
+            );
+            state = state.score_point_with(scorer, rules);
+        }
+    }
+
+    fn tiebreak_set(scorers: &mut Vec<Player>) {
+        for _ in 0..6 {
+            scorers.extend([Player::Player1; 4]);
+            scorers.extend([Player::Player2; 4]);
+        }
+        scorers.extend([Player::Player1, Player::Player2].repeat(5));
+        scorers.extend([Player::Player2; 2]);
+    }
+
+    #[test]
+    fn test_consistent_with_state_machine_serving() {
+        let mut scorers = Vec::new();
+        tiebreak_set(&mut scorers);
+        tiebreak_set(&mut scorers);
+        scorers.extend([Player::Player1, Player::Player2].repeat(6));
+
+        assert_consistent(MatchConfig::default(), &scorers, &StandardRules);
+        assert_consistent(
+            MatchConfig::padel(Player::Player2),
+            &scorers,
+            &StandardRules,
+        );
+        assert_consistent(MatchConfig::tiebreaks(7, 2), &scorers, &TiebreakOnlyRules);
+        // Doubles tiebreaks hand the first serve on from one to the next
+        let doubles_tiebreaks = MatchConfig {
+            sets_to_win: 3,
+            ..MatchConfig::padel(Player::Player2)
+        };
+        assert_consistent(doubles_tiebreaks, &scorers, &TiebreakOnlyRules);
+    }
+
+    #[test]
+    fn test_importance_grows_with_context() {
+        let config = MatchConfig {
+            sets_to_win: 1,
+            ..MatchConfig::default()
+        };
+        // Break point on Player1's serve at 0-0, then again at 5-5
+        let to_break_point = [
+            Player::Player1,
+            Player::Player1,
+            Player::Player2,
+            Player::Player2,
+            Player::Player2,
+        ];
+        let mut scorers = to_break_point.to_vec();
+        let early = scorers.len();
+        scorers.extend([Player::Player1; 3]);
+        for _ in 0..5 {
+            scorers.extend([Player::Player2; 4]);
+            scorers.extend([Player::Player1; 4]);
+        }
+        scorers.truncate(scorers.len() - 4);
+        scorers.extend(to_break_point);
+        let late = scorers.len();
+        scorers.push(Player::Player2);
+
+        let mut contexts = replay_with_context(&config, &make_events(&scorers));
+        annotate_importance(&config, &mut contexts, &serve(0.62, 0.62));
+
+        assert!(contexts[early].is_break_point && contexts[late].is_break_point);
+        let early_importance = contexts[early].importance.unwrap();
+        let late_importance = contexts[late].importance.unwrap();
+        assert!(early_importance > 0.0);
+        assert!(late_importance > 1.5 * early_importance);
+        assert!(contexts.iter().all(|p| p.importance.unwrap() >= 0.0));
+
+        let biggest = biggest_points(&contexts, 3);
+        assert_eq!(biggest.len(), 3);
+        assert_eq!(biggest[0].point_number, contexts[late].point_number);
+        assert!(biggest[1].importance >= biggest[2].importance);
+    }
+
+    #[test]
+    fn test_match_point_importance() {
+        let config = MatchConfig::tiebreaks(7, 1);
+        let scorers = [Player::Player1; 7];
+        let rules = TiebreakOnlyRules;
+        let start = MatchState::new_with(config.clone(), &rules);
+        let mut contexts = replay_with(&start, &make_events(&scorers), &rules, None);
+        annotate_importance_with(&config, &mut contexts, &serve(0.6, 0.6), &rules);
+        assert!(contexts[6].is_match_point);
+        // Losing at 6-0 still leaves Player1 a big favourite
+        assert!(contexts[6].importance < contexts[0].importance);
+        assert!(biggest_points(&contexts[..0], 5).is_empty());
+    }
+
+    #[test]
+    fn test_series_over_replay() {
+        let config = MatchConfig::default();
+        let mut scorers = vec![Player::Player1; 24];
+        scorers.extend([Player::Player2; 4]);
+        let contexts = replay_with_context(&config, &make_events(&scorers));
+        let series = win_probability_series(&config, &contexts, &serve(0.62, 0.62));
+
+        assert_eq!(series.len(), 28);
+        assert!((series[0].before.player1_match - 0.5).abs() < EPSILON);
+        assert_eq!(series[1].before, series[0].after);
+        // Player1 pulls ahead, then gives a little back
+        assert!(series[23].after.player1_match > series[0].after.player1_match);
+        assert!(series[27].after.player1_match < series[23].after.player1_match);
+    }
+}
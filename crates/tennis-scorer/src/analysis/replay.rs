@@ -2,33 +2,118 @@ use std::time::SystemTime;
 
 use crate::config::MatchConfig;
 use crate::game::GameState;
+use crate::history::MatchWithHistory;
 use crate::match_state::MatchState;
+use crate::rules::{ScoringRules, StandardRules};
 use crate::set::SetState;
 use crate::tiebreak::TiebreakState;
-use crate::types::{Player, Point};
+use crate::types::Player;
 
+use super::court::Court;
 use super::types::{GameScore, PointContext, ScoreSnapshot, SetScore};
 
+/// Replay timestamped points of a match recorded from its first point under
+/// standard rules. For a match picked up mid-way, replay from its
+/// `MatchState::from_score` state with [`replay_with`] to continue its set
+/// and game numbering.
 pub fn replay_with_context(
     config: &MatchConfig,
     events: &[(Player, SystemTime)],
 ) -> Vec<PointContext> {
-    let mut state = MatchState::new(config.clone());
+    replay(
+        &MatchState::new(config.clone()),
+        events,
+        &StandardRules,
+        None,
+    )
+}
+
+/// As [`replay_with_context`], for a timed match on which time was called
+/// after `points_before` points. Later points, the sudden-death point or
+/// tiebreak, are flagged `is_overtime` and leave the score as it stood.
+pub fn replay_with_time_called(
+    config: &MatchConfig,
+    events: &[(Player, SystemTime)],
+    points_before: usize,
+) -> Vec<PointContext> {
+    replay(
+        &MatchState::new(config.clone()),
+        events,
+        &StandardRules,
+        Some(points_before),
+    )
+}
+
+/// Replay timestamped points from `start` under `rules`, e.g. from
+/// `MatchState::new_with`, or from `MatchState::from_score` for a match
+/// picked up mid-way. A completed starting state yields no contexts. With
+/// `time_called`, points from that index on are treated as in
+/// [`replay_with_time_called`].
+pub fn replay_with(
+    start: &MatchState,
+    events: &[(Player, SystemTime)],
+    rules: &dyn ScoringRules,
+    time_called: Option<usize>,
+) -> Vec<PointContext> {
+    replay(start, events, rules, time_called)
+}
+
+/// Replay a recording from its initial state, under the rules it was scored
+/// with. Points claimed as bisques are flagged `is_bisque`, and points played
+/// after time was called `is_overtime`.
+pub fn replay_recording(recording: &MatchWithHistory) -> Vec<PointContext> {
+    let mut contexts = replay(
+        recording.initial_state(),
+        recording.point_events(),
+        recording.rules(),
+        recording.time_called().map(|called| called.points_before),
+    );
+    for &i in recording.bisque_points() {
+        if let Some(context) = contexts.get_mut(i) {
+            context.is_bisque = true;
+        }
+    }
+    contexts
+}
+
+fn replay(
+    start: &MatchState,
+    events: &[(Player, SystemTime)],
+    rules: &dyn ScoringRules,
+    time_called: Option<usize>,
+) -> Vec<PointContext> {
+    if start.winner().is_some() {
+        return Vec::new();
+    }
+    let mut state = start.clone();
     let mut contexts = Vec::with_capacity(events.len());
-    let mut total_completed_games: u32 = 0;
 
     for (i, (scorer, timestamp)) in events.iter().enumerate() {
-        let serving_player = determine_server(&state, config, total_completed_games);
-        let score_before = score_snapshot_from_state(&state);
+        let is_overtime = time_called.is_some_and(|points_before| i >= points_before);
+        let serving_player = state.serving_player().unwrap_or(Player::Player1);
+        let server_position = server_position(&state);
+        let score_before = score_snapshot_from_state(&state, rules);
         let (game_number_in_set, set_number) = current_position(&state);
         let is_tiebreak = is_in_tiebreak(&state);
 
-        let is_game_point = is_game_point_for_either(&state, is_tiebreak);
-        let is_break_point = is_break_point_state(&state, serving_player, is_tiebreak);
-        let is_set_point = is_set_point_state(&state, is_tiebreak);
-        let is_match_point = is_match_point_state(&state);
+        // Overtime points decide the match outside the regular scoring
+        let stakes = [Player::Player1, Player::Player2].map(|p| {
+            if is_overtime {
+                PointStakes::default()
+            } else {
+                point_stakes(&state, p, rules)
+            }
+        });
+        let is_game_point = stakes.iter().any(|s| s.game);
+        let is_break_point = match serving_player.opponent() {
+            Player::Player1 => stakes[0].game,
+            Player::Player2 => stakes[1].game,
+        };
+        let is_set_point = stakes.iter().any(|s| s.set);
+        let is_match_point = stakes.iter().any(|s| s.match_);
+        let is_golden_point = score_before.current_game.is_deuce && stakes.iter().all(|s| s.game);
 
-        contexts.push(PointContext {
+        let mut context = PointContext {
             point_number: (i + 1) as u32,
             scorer: *scorer,
             timestamp: *timestamp,
@@ -42,69 +127,43 @@ pub fn replay_with_context(
             set_number,
             is_tiebreak,
             point_end_type: None,
-        });
-
-        let old_games = count_games_in_current_set(&state);
-        state = state.score_point(*scorer);
-        let new_games = count_games_in_current_set(&state);
+            is_golden_point,
+            importance: None,
+            server_position,
+            returner_position: 0,
+            is_bisque: false,
+            is_overtime,
+        };
+        context.returner_position = returner_position(&state, Court::of(&context));
+        contexts.push(context);
 
-        if new_games > old_games || set_just_completed(&state, set_number) {
-            total_completed_games += 1;
+        if !is_overtime {
+            state = state.score_point_with(*scorer, rules);
         }
     }
 
     contexts
 }
 
-fn determine_server(
-    state: &MatchState,
-    config: &MatchConfig,
-    total_completed_games: u32,
-) -> Player {
-    if !config.serve_order.is_empty() {
-        // Doubles: use the state's current_server index into serve_order
-        let idx = state.current_server() as usize;
-        if idx < config.serve_order.len() {
-            config.serve_order[idx].0
-        } else {
-            Player::Player1
-        }
-    } else {
-        // Singles: game parity
-        if is_in_tiebreak(state) {
-            // During tiebreak, use tiebreak serving pattern
-            let tb_points = tiebreak_points_played(state);
-            let game_before_tb = total_completed_games;
-            let base_server = if game_before_tb.is_multiple_of(2) {
-                Player::Player1
-            } else {
-                Player::Player2
-            };
-            let offset = if tb_points == 0 {
-                0
-            } else {
-                (tb_points as usize).div_ceil(2)
-            };
-            if offset % 2 == 0 {
-                base_server
-            } else {
-                base_server.opponent()
-            }
-        } else if total_completed_games.is_multiple_of(2) {
-            Player::Player1
-        } else {
-            Player::Player2
-        }
+/// Doubles partners receive in one court each, so the court says which of
+/// them returns.
+fn returner_position(state: &MatchState, court: Court) -> u8 {
+    match state {
+        MatchState::Playing { config, .. } if !config.serve_order.is_empty() => match court {
+            Court::Deuce => 0,
+            Court::Ad => 1,
+        },
+        _ => 0,
     }
 }
 
-fn tiebreak_points_played(state: &MatchState) -> u8 {
+fn server_position(state: &MatchState) -> u8 {
     match state {
-        MatchState::Playing {
-            tiebreak_points_served,
-            ..
-        } => *tiebreak_points_served,
-        _ => 0,
+        MatchState::Playing { config, .. } => config
+            .serve_order
+            .get(state.current_server() as usize)
+            .map_or(0, |&(_, position)| position),
+        MatchState::Completed { .. } => 0,
     }
 }
 
@@ -127,32 +186,6 @@ fn is_in_tiebreak(state: &MatchState) -> bool {
     }
 }
 
-fn count_games_in_current_set(state: &MatchState) -> u8 {
-    match state {
-        MatchState::Playing { sets, .. } => match sets.last() {
-            Some(SetState::Playing {
-                player1_games,
-                player2_games,
-                ..
-            }) => player1_games + player2_games,
-            Some(SetState::Completed {
-                player1_games,
-                player2_games,
-                ..
-            }) => player1_games + player2_games,
-            None => 0,
-        },
-        _ => 0,
-    }
-}
-
-fn set_just_completed(state: &MatchState, prev_set_number: u32) -> bool {
-    match state {
-        MatchState::Playing { sets, .. } => sets.len() as u32 > prev_set_number,
-        MatchState::Completed { .. } => true,
-    }
-}
-
 fn current_position(state: &MatchState) -> (u32, u32) {
     match state {
         MatchState::Playing { sets, .. } => {
@@ -174,7 +207,7 @@ fn current_position(state: &MatchState) -> (u32, u32) {
     }
 }
 
-pub fn score_snapshot_from_state(state: &MatchState) -> ScoreSnapshot {
+pub fn score_snapshot_from_state(state: &MatchState, rules: &dyn ScoringRules) -> ScoreSnapshot {
     match state {
         MatchState::Playing {
             sets,
@@ -211,6 +244,7 @@ pub fn score_snapshot_from_state(state: &MatchState) -> ScoreSnapshot {
                 current_game,
                 player1_sets: *player1_sets,
                 player2_sets: *player2_sets,
+                total_games: rules.decided_by_total_games().then(|| state.total_games()),
             }
         }
         MatchState::Completed {
@@ -231,6 +265,7 @@ pub fn score_snapshot_from_state(state: &MatchState) -> ScoreSnapshot {
                 },
                 player1_sets: *player1_sets,
                 player2_sets: *player2_sets,
+                total_games: None,
             }
         }
     }
@@ -278,8 +313,8 @@ fn set_score_from_state(set: &SetState) -> SetScore {
 fn game_score_from_state(game: &GameState) -> GameScore {
     match game {
         GameState::Points { player1, player2 } => GameScore {
-            player1_points: point_to_string(*player1),
-            player2_points: point_to_string(*player2),
+            player1_points: player1.to_string(),
+            player2_points: player2.to_string(),
             is_deuce: false,
             advantage: None,
             deuce_count: 0,
@@ -334,207 +369,62 @@ fn game_score_from_tiebreak(tb: &TiebreakState) -> GameScore {
     }
 }
 
-fn point_to_string(p: Point) -> String {
-    match p {
-        Point::Love => "0".to_string(),
-        Point::Fifteen => "15".to_string(),
-        Point::Thirty => "30".to_string(),
-        Point::Forty => "40".to_string(),
-    }
+/// What winning the next point would decide for one player.
+#[derive(Default)]
+struct PointStakes {
+    game: bool,
+    set: bool,
+    match_: bool,
 }
 
-/// Check if either player is at game point (one point from winning the game)
-fn is_game_point_for_either(state: &MatchState, in_tiebreak: bool) -> bool {
-    match state {
-        MatchState::Playing { sets, config, .. } => match sets.last() {
-            Some(SetState::Playing {
-                current_game,
-                tiebreak,
-                ..
-            }) => {
-                if in_tiebreak {
-                    if let Some(TiebreakState::Playing {
-                        player1_points,
-                        player2_points,
-                        target_points,
-                    }) = tiebreak
-                    {
-                        let leader = (*player1_points).max(*player2_points);
-                        let trailer = (*player1_points).min(*player2_points);
-                        // Game point if leader >= target-1 and leader > trailer,
-                        // or both >= target-1 (one more point with 2-pt lead could win)
-                        leader >= target_points - 1
-                            && (leader > trailer || trailer >= target_points - 1)
-                    } else {
-                        false
-                    }
-                } else {
-                    match current_game {
-                        GameState::Points { player1, player2 } => {
-                            *player1 == Point::Forty || *player2 == Point::Forty
-                        }
-                        GameState::Deuce { .. } => {
-                            // At deuce, no one is at game point (need advantage first)
-                            // Unless no-ad scoring
-                            config.no_ad_scoring
-                        }
-                        GameState::Advantage { .. } => true,
-                        GameState::Completed(_) => false,
-                    }
-                }
-            }
-            _ => false,
-        },
-        _ => false,
+/// Score the point for `player` under `rules` and see what it decides, so
+/// game, set and match point flags follow whatever format is being played.
+fn point_stakes(state: &MatchState, player: Player, rules: &dyn ScoringRules) -> PointStakes {
+    let after = state.score_point_with(player, rules);
+    PointStakes {
+        game: games_won(&after, player) > games_won(state, player),
+        set: sets_won(&after, player) > sets_won(state, player),
+        match_: after.winner() == Some(player),
     }
 }
 
-/// Check if the returner could win the game (break point)
-fn is_break_point_state(state: &MatchState, serving_player: Player, in_tiebreak: bool) -> bool {
-    match state {
-        MatchState::Playing { sets, config, .. } => match sets.last() {
-            Some(SetState::Playing {
-                current_game,
-                tiebreak,
-                ..
-            }) => {
-                if in_tiebreak {
-                    if let Some(TiebreakState::Playing {
-                        player1_points,
-                        player2_points,
-                        target_points,
-                    }) = tiebreak
-                    {
-                        let (returner_pts, server_pts) = match serving_player {
-                            Player::Player1 => (*player2_points, *player1_points),
-                            Player::Player2 => (*player1_points, *player2_points),
-                        };
-                        // Returner at game point: returner >= target-1 and returner > server
-                        // Or at extended tiebreak: both >= target-1 and returner > server
-                        returner_pts >= target_points - 1 && returner_pts > server_pts
-                    } else {
-                        false
-                    }
-                } else {
-                    let returner = serving_player.opponent();
-                    match current_game {
-                        GameState::Points { player1, player2 } => {
-                            let returner_pts = match returner {
-                                Player::Player1 => *player1,
-                                Player::Player2 => *player2,
-                            };
-                            let server_pts = match serving_player {
-                                Player::Player1 => *player1,
-                                Player::Player2 => *player2,
-                            };
-                            returner_pts == Point::Forty && server_pts != Point::Forty
-                        }
-                        GameState::Deuce { .. } => {
-                            // At deuce with no-ad, both could win = break point for returner
-                            config.no_ad_scoring
-                        }
-                        GameState::Advantage { player, .. } => *player == returner,
-                        GameState::Completed(_) => false,
-                    }
-                }
-            }
-            _ => false,
-        },
-        _ => false,
+/// Games won by `player` across all sets; a tiebreak counts as a game.
+fn games_won(state: &MatchState, player: Player) -> u16 {
+    let (p1, p2) = state.total_games();
+    match player {
+        Player::Player1 => p1,
+        Player::Player2 => p2,
     }
 }
 
-/// Check if winning this game could win the set for either player
-fn is_set_point_state(state: &MatchState, in_tiebreak: bool) -> bool {
-    if !is_game_point_for_either(state, in_tiebreak) {
-        return false;
-    }
-
-    match state {
-        MatchState::Playing { sets, config, .. } => match sets.last() {
-            Some(SetState::Playing {
-                player1_games,
-                player2_games,
-                tiebreak,
-                ..
-            }) => {
-                if tiebreak.is_some() {
-                    // In a tiebreak, winning it always wins the set
-                    return true;
-                }
-                // Check if winning one more game would win the set
-                let p1_if_win = player1_games + 1;
-                let p2_if_win = player2_games + 1;
-
-                let player1_sets = match state {
-                    MatchState::Playing { player1_sets, .. } => *player1_sets,
-                    _ => 0,
-                };
-                let player2_sets = match state {
-                    MatchState::Playing { player2_sets, .. } => *player2_sets,
-                    _ => 0,
-                };
-                let is_final_set = player1_sets == config.sets_to_win - 1
-                    && player2_sets == config.sets_to_win - 1;
-
-                would_win_set(
-                    p1_if_win,
-                    *player2_games,
-                    is_final_set,
-                    config.final_set_tiebreak,
-                ) || would_win_set(
-                    *player1_games,
-                    p2_if_win,
-                    is_final_set,
-                    config.final_set_tiebreak,
-                )
-            }
-            _ => false,
-        },
-        _ => false,
-    }
-}
-
-fn would_win_set(
-    p1_games: u8,
-    p2_games: u8,
-    _is_final_set: bool,
-    _final_set_tiebreak: bool,
-) -> bool {
-    let leader = p1_games.max(p2_games);
-    let trailer = p1_games.min(p2_games);
-    let lead = leader - trailer;
-    (leader >= 6 && lead >= 2) || (leader == 7 && trailer == 6)
-}
-
-/// Check if winning this set would win the match
-fn is_match_point_state(state: &MatchState) -> bool {
-    match state {
-        MatchState::Playing { config, .. } => {
-            let in_tb = is_in_tiebreak(state);
-            if !is_game_point_for_either(state, in_tb) {
-                return false;
-            }
-            if !is_set_point_state(state, in_tb) {
-                return false;
-            }
-            let (p1_sets, p2_sets) = match state {
-                MatchState::Playing {
-                    player1_sets,
-                    player2_sets,
-                    ..
-                } => (*player1_sets, *player2_sets),
-                _ => (0, 0),
-            };
-            p1_sets == config.sets_to_win - 1 || p2_sets == config.sets_to_win - 1
+fn sets_won(state: &MatchState, player: Player) -> u8 {
+    let (p1, p2) = match state {
+        MatchState::Playing {
+            player1_sets,
+            player2_sets,
+            ..
         }
-        _ => false,
+        | MatchState::Completed {
+            player1_sets,
+            player2_sets,
+            ..
+        } => (*player1_sets, *player2_sets),
+    };
+    match player {
+        Player::Player1 => p1,
+        Player::Player2 => p2,
     }
 }
 
 #[cfg(test)]
+#[allow(clippy::needless_range_loop)]
 mod tests {
     use super::*;
+    use crate::analysis::compute_analysis;
+    use crate::config::{MatchConfig, TimeLimit, TimedDecider, TimedLeader};
+    use crate::rules::{CumulativeGamesRules, SetContext, TiebreakOnlyRules};
+    use crate::types::Point;
+    use std::sync::Arc;
     use std::time::Duration;
 
     fn ts(secs: u64) -> SystemTime {
@@ -722,6 +612,36 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_singles_tiebreak_serve_alternation() {
+        let config = MatchConfig::default();
+        let mut scorers = Vec::new();
+        for _ in 0..6 {
+            scorers.extend([Player::Player1; 4]);
+            scorers.extend([Player::Player2; 4]);
+        }
+        scorers.extend([Player::Player1; 7]);
+
+        let events = make_events(&scorers);
+        let contexts = replay_with_context(&config, &events);
+
+        // Player1 served game 1, so also serves the first tiebreak point;
+        // then each player serves two points in turn
+        let servers: Vec<Player> = contexts[48..].iter().map(|c| c.serving_player).collect();
+        assert_eq!(
+            servers,
+            vec![
+                Player::Player1,
+                Player::Player2,
+                Player::Player2,
+                Player::Player1,
+                Player::Player1,
+                Player::Player2,
+                Player::Player2,
+            ]
+        );
+    }
+
     #[test]
     fn test_doubles_serve_rotation() {
         use crate::config::MatchType;
@@ -793,4 +713,284 @@ mod tests {
             Some(Player::Player1)
         );
     }
+
+    #[test]
+    fn test_replay_from_starting_score_continues_numbering() {
+        use crate::game::GameState;
+        use crate::starting_score::{StartingPoints, StartingScore};
+
+        let score = StartingScore {
+            completed_sets: vec![(6, 4)],
+            games: (3, 2),
+            points: StartingPoints::Game(GameState::Points {
+                player1: Point::Thirty,
+                player2: Point::Fifteen,
+            }),
+            server: Player::Player2,
+            server_position: 0,
+        };
+        let start = MatchState::from_score(MatchConfig::default(), &score).unwrap();
+        // Player2 holds from 30-15 down, then Player1 holds to love
+        let events = make_events(&[
+            Player::Player2,
+            Player::Player2,
+            Player::Player2,
+            Player::Player1,
+            Player::Player1,
+            Player::Player1,
+            Player::Player1,
+        ]);
+        let contexts = replay_with(&start, &events, &StandardRules, None);
+
+        assert_eq!(contexts.len(), 7);
+        for c in &contexts[0..3] {
+            assert_eq!(c.set_number, 2);
+            assert_eq!(c.game_number_in_set, 6);
+            assert_eq!(c.serving_player, Player::Player2);
+        }
+        assert_eq!(contexts[0].score_before.current_game.player1_points, "30");
+        assert_eq!(contexts[0].score_before.sets[0].player1_games, 6);
+        // 30-40: break point for Player1
+        assert!(contexts[2].is_game_point);
+        for c in &contexts[3..7] {
+            assert_eq!(c.game_number_in_set, 7);
+            assert_eq!(c.serving_player, Player::Player1);
+        }
+    }
+
+    #[test]
+    fn test_replay_from_completed_state_is_empty() {
+        let start = MatchState::Completed {
+            winner: Player::Player1,
+            player1_sets: 2,
+            player2_sets: 0,
+            sets: vec![],
+            config: MatchConfig::default(),
+        };
+        let events = make_events(&[Player::Player1]);
+        assert!(replay_with(&start, &events, &StandardRules, None).is_empty());
+    }
+
+    #[test]
+    fn test_level_tiebreak_is_not_game_point() {
+        let config = MatchConfig::default();
+        let mut scorers = Vec::new();
+        for _ in 0..6 {
+            scorers.extend([Player::Player1; 4]);
+            scorers.extend([Player::Player2; 4]);
+        }
+        // 6-6 in the tiebreak
+        for _ in 0..6 {
+            scorers.push(Player::Player1);
+            scorers.push(Player::Player2);
+        }
+        scorers.push(Player::Player1);
+
+        let contexts = replay_with_context(&config, &make_events(&scorers));
+        let last = contexts.last().unwrap();
+        assert!(last.is_tiebreak);
+        assert!(!last.is_game_point);
+        assert!(!last.is_set_point);
+    }
+
+    /// One-set matches where the set goes to whoever first wins three games.
+    #[derive(Debug)]
+    struct FirstToThree;
+
+    impl ScoringRules for FirstToThree {
+        fn set_winner(
+            &self,
+            player1_games: u8,
+            player2_games: u8,
+            _set: &SetContext,
+        ) -> Option<Player> {
+            match (player1_games, player2_games) {
+                (3, _) => Some(Player::Player1),
+                (_, 3) => Some(Player::Player2),
+                _ => None,
+            }
+        }
+    }
+
+    #[test]
+    fn test_points_after_time_called_leave_the_score() {
+        let config = MatchConfig {
+            time_limit: Some(TimeLimit {
+                minutes: 45,
+                leader: TimedLeader::Games,
+                decider: TimedDecider::Tiebreak { points: 7 },
+            }),
+            ..MatchConfig::default()
+        };
+        // One game each, then time is called and Player1 wins the tiebreak
+        let mut scorers = vec![Player::Player1; 4];
+        scorers.extend([Player::Player2; 4]);
+        let recording =
+            MatchWithHistory::from_events(MatchState::new(config.clone()), &make_events(&scorers))
+                .unwrap()
+                .end_on_time_at(ts(2700))
+                .unwrap();
+        let recording = (0..7).fold(recording, |mwh, i| {
+            mwh.score_point_at(Player::Player1, ts(2700 + i)).unwrap()
+        });
+        assert_eq!(recording.current().winner(), Some(Player::Player1));
+
+        let contexts = replay_recording(&recording);
+        assert_eq!(contexts.len(), 15);
+        assert!(!contexts[7].is_overtime);
+        let overtime = &contexts[8..];
+        assert!(overtime.iter().all(|p| p.is_overtime && !p.is_game_point));
+        assert!(
+            overtime
+                .iter()
+                .all(|p| p.score_before == contexts[14].score_before)
+        );
+        assert_eq!(contexts[14].score_before.sets[0].player1_games, 1);
+        assert_eq!(contexts[14].score_before.current_game.player1_points, "0");
+
+        let events = recording.point_events();
+        assert_eq!(replay_with_time_called(&config, events, 8), contexts);
+        let analysis = compute_analysis(&contexts);
+        assert_eq!(analysis.player1.total_points.total_points, 8);
+    }
+
+    #[test]
+    fn test_flags_follow_custom_rules() {
+        let config = MatchConfig {
+            sets_to_win: 1,
+            ..MatchConfig::default()
+        };
+        let start = MatchState::new_with(config.clone(), &FirstToThree);
+        let recording = |scorers: &[Player]| {
+            MatchWithHistory::from_events_with(
+                start.clone(),
+                &make_events(scorers),
+                Arc::new(FirstToThree),
+            )
+            .unwrap()
+        };
+
+        // 2-0, 40-0: winning this game takes the set, and the match
+        let scorers = vec![Player::Player1; 12];
+        let contexts = replay_recording(&recording(&scorers));
+        assert!(contexts[11].is_set_point);
+        assert!(contexts[11].is_match_point);
+
+        // Standard rules need six games, so the same point is only game point
+        let contexts = replay_with_context(&config, &make_events(&scorers));
+        assert!(contexts[11].is_game_point);
+        assert!(!contexts[11].is_set_point);
+
+        // 2-1, Player2 at 40-0: winning only levels the set
+        let mut scorers = vec![Player::Player1; 8];
+        scorers.extend([Player::Player2; 8]);
+        let contexts = replay_recording(&recording(&scorers));
+        assert!(contexts[15].is_game_point);
+        assert!(!contexts[15].is_set_point);
+    }
+
+    #[test]
+    fn test_cumulative_games_flags_and_totals() {
+        let rules = CumulativeGamesRules::team_tennis();
+        let start = MatchState::new_with(MatchConfig::team_tennis(), &rules);
+        // Player1 wins the first set 5-0 and Player2 the next three; Player1
+        // then takes the fifth 5-0 but still trails 10-15, forcing overtime
+        let mut scorers = vec![Player::Player1; 20];
+        scorers.extend([Player::Player2; 60]);
+        scorers.extend([Player::Player1; 20]);
+        scorers.extend([Player::Player2; 4]);
+        let contexts = replay_with(&start, &make_events(&scorers), &rules, None);
+
+        let fifth_set_point = &contexts[99];
+        assert_eq!(fifth_set_point.set_number, 5);
+        assert_eq!(fifth_set_point.score_before.total_games, Some((9, 15)));
+        assert!(fifth_set_point.is_set_point);
+        assert!(!fifth_set_point.is_match_point);
+
+        // The leader's first overtime game ends the match
+        let last = contexts.last().unwrap();
+        assert_eq!(last.set_number, 6);
+        assert!(last.is_match_point);
+
+        // Without cumulative scoring the totals are not tracked
+        let contexts = replay_with_context(&MatchConfig::default(), &make_events(&scorers));
+        assert_eq!(contexts[0].score_before.total_games, None);
+    }
+
+    #[test]
+    fn test_tiebreak_only_numbers_each_tiebreak() {
+        let config = MatchConfig::tiebreaks(7, 2);
+        let mut scorers = vec![Player::Player1; 7];
+        scorers.extend([Player::Player2; 2]);
+        let contexts = replay_with(
+            &MatchState::new_with(config, &TiebreakOnlyRules),
+            &make_events(&scorers),
+            &TiebreakOnlyRules,
+            None,
+        );
+
+        assert!(contexts.iter().all(|c| c.is_tiebreak));
+        assert!(contexts.iter().all(|c| c.game_number_in_set == 1));
+        assert_eq!(contexts[6].set_number, 1);
+        assert!(contexts[6].is_set_point);
+        assert_eq!(contexts[7].set_number, 2);
+        // The second tiebreak is opened by the other player
+        assert_eq!(contexts[0].serving_player, Player::Player1);
+        assert_eq!(contexts[7].serving_player, Player::Player2);
+    }
+
+    #[test]
+    fn test_sudden_death_tiebreak_flags() {
+        let config = MatchConfig {
+            tiebreak_rule: crate::config::TiebreakRule {
+                win_by: 1,
+                cap: None,
+            },
+            ..MatchConfig::default()
+        };
+        let mut scorers = Vec::new();
+        for _ in 0..6 {
+            scorers.extend([Player::Player1; 4]);
+            scorers.extend([Player::Player2; 4]);
+        }
+        for _ in 0..6 {
+            scorers.push(Player::Player1);
+            scorers.push(Player::Player2);
+        }
+        scorers.push(Player::Player2);
+
+        // At 6-6 the next point takes the set
+        let contexts = replay_with_context(&config, &make_events(&scorers));
+        let last = contexts.last().unwrap();
+        assert!(last.is_tiebreak);
+        assert!(last.is_set_point);
+        assert!(!last.is_match_point);
+    }
+
+    #[test]
+    fn test_capped_match_tiebreak_flags() {
+        let config = MatchConfig {
+            match_tiebreak_points: Some(10),
+            match_tiebreak_rule: crate::config::TiebreakRule {
+                win_by: 2,
+                cap: Some(13),
+            },
+            ..MatchConfig::default()
+        };
+        let mut scorers = vec![Player::Player1; 24];
+        scorers.extend([Player::Player2; 24]);
+        for _ in 0..12 {
+            scorers.push(Player::Player1);
+            scorers.push(Player::Player2);
+        }
+        scorers.push(Player::Player1);
+
+        // 12-12 in the match tiebreak is match point for both sides
+        let contexts = replay_with_context(&config, &make_events(&scorers));
+        let last = contexts.last().unwrap();
+        assert_eq!(last.set_number, 3);
+        assert!(last.is_match_point);
+        // Earlier level scores past the target are not
+        assert!(!contexts[contexts.len() - 3].is_match_point);
+    }
 }
@@ -0,0 +1,233 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::types::Player;
+
+use super::games::{label, label_sides};
+use super::momentum::ends_game;
+use super::stats::rate;
+use super::types::{PointContext, point_rank};
+
+/// Points played at one game score.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScoreStateStats {
+    /// Game score before the point, server first as the umpire calls it,
+    /// e.g. "30-40" or "A-40". Deuce is "40-40".
+    pub score: String,
+    pub serving: bool,
+    pub points_played: u32,
+    pub points_won: u32,
+    pub win_rate: f64,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct GameOutcomeStats {
+    pub love_holds: u32,
+    /// Service games held after being 0-30 down.
+    pub holds_from_0_30: u32,
+    pub breaks_to_love: u32,
+    /// Return games won after being 40-0 down.
+    pub breaks_from_40_0: u32,
+    /// Sets won 6-0.
+    pub bagels: u32,
+    /// Sets won 6-1.
+    pub breadsticks: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PlayerScoreStates {
+    /// Serving scores first, then returning, each from 0-0 upwards.
+    pub by_score: Vec<ScoreStateStats>,
+    pub outcomes: GameOutcomeStats,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScoreStateAnalysis {
+    pub player1: PlayerScoreStates,
+    pub player2: PlayerScoreStates,
+}
+
+/// How each player does at each game score, on serve and on return, plus
+/// counts of notable game and set scores. Tiebreak points are left out of
+/// the per-score breakdown.
+pub fn compute_score_states(points: &[PointContext]) -> ScoreStateAnalysis {
+    ScoreStateAnalysis {
+        player1: player_score_states(points, Player::Player1),
+        player2: player_score_states(points, Player::Player2),
+    }
+}
+
+fn player_score_states(points: &[PointContext], player: Player) -> PlayerScoreStates {
+    // (returning, server rank, returner rank) -> (score, played, won)
+    let mut tally: BTreeMap<(bool, i8, i8), (String, u32, u32)> = BTreeMap::new();
+    for p in points.iter().filter(|p| !p.is_tiebreak) {
+        let game = &p.score_before.current_game;
+        let score = label(game, p.serving_player);
+        let (server, returner) = label_sides(game, p.serving_player);
+        let (server_rank, returner_rank) = (
+            point_rank(server).unwrap_or(0),
+            point_rank(returner).unwrap_or(0),
+        );
+        let entry = tally
+            .entry((p.serving_player != player, server_rank, returner_rank))
+            .or_insert((score, 0, 0));
+        entry.1 += 1;
+        if p.scorer == player {
+            entry.2 += 1;
+        }
+    }
+
+    let by_score = tally
+        .into_iter()
+        .map(
+            |((returning, _, _), (score, points_played, points_won))| ScoreStateStats {
+                score,
+                serving: !returning,
+                points_played,
+                points_won,
+                win_rate: rate(points_won, points_played),
+            },
+        )
+        .collect();
+
+    PlayerScoreStates {
+        by_score,
+        outcomes: game_outcomes(points, player),
+    }
+}
+
+fn game_outcomes(points: &[PointContext], player: Player) -> GameOutcomeStats {
+    let mut outcomes = GameOutcomeStats::default();
+    let mut set_scores: BTreeMap<u32, (u8, u8)> = BTreeMap::new();
+
+    let games = points.chunk_by(|a, b| {
+        (a.set_number, a.game_number_in_set) == (b.set_number, b.game_number_in_set)
+    });
+    for game in games {
+        let last = &game[game.len() - 1];
+        if !ends_game(last) {
+            continue;
+        }
+        let winner = last.scorer;
+        let server = game[0].serving_player;
+
+        if let Some(set) = last.score_before.sets.last() {
+            let games = match winner {
+                Player::Player1 => (set.player1_games + 1, set.player2_games),
+                Player::Player2 => (set.player1_games, set.player2_games + 1),
+            };
+            set_scores.insert(last.set_number, games);
+        }
+        if winner != player || game[0].is_tiebreak {
+            continue;
+        }
+
+        let passed = |score: &str| {
+            game.iter()
+                .any(|p| label(&p.score_before.current_game, server) == score)
+        };
+        let opponent_points = game.iter().filter(|p| p.scorer != player).count();
+        if server == player {
+            if opponent_points == 0 {
+                outcomes.love_holds += 1;
+            }
+            if passed("0-30") {
+                outcomes.holds_from_0_30 += 1;
+            }
+        } else {
+            if opponent_points == 0 {
+                outcomes.breaks_to_love += 1;
+            }
+            if passed("40-0") {
+                outcomes.breaks_from_40_0 += 1;
+            }
+        }
+    }
+
+    for (player1_games, player2_games) in set_scores.into_values() {
+        let (own, other) = match player {
+            Player::Player1 => (player1_games, player2_games),
+            Player::Player2 => (player2_games, player1_games),
+        };
+        match (own, other) {
+            (6, 0) => outcomes.bagels += 1,
+            (6, 1) => outcomes.breadsticks += 1,
+            _ => {}
+        }
+    }
+
+    outcomes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::replay::replay_with_context;
+    use crate::config::MatchConfig;
+    use std::time::{Duration, SystemTime};
+
+    fn ts(secs: u64) -> SystemTime {
+        SystemTime::UNIX_EPOCH + Duration::from_secs(1_000_000 + secs)
+    }
+
+    fn make_events(scorers: &[Player]) -> Vec<(Player, SystemTime)> {
+        scorers
+            .iter()
+            .enumerate()
+            .map(|(i, p)| (*p, ts(i as u64 * 30)))
+            .collect()
+    }
+
+    fn score_states(scorers: &[Player]) -> ScoreStateAnalysis {
+        let contexts = replay_with_context(&MatchConfig::default(), &make_events(scorers));
+        compute_score_states(&contexts)
+    }
+
+    fn at<'a>(states: &'a PlayerScoreStates, score: &str, serving: bool) -> &'a ScoreStateStats {
+        states
+            .by_score
+            .iter()
+            .find(|s| s.score == score && s.serving == serving)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_points_by_score() {
+        use Player::*;
+        // Player1 serves from 0-30 down to hold
+        let analysis = score_states(&[Player2, Player2, Player1, Player1, Player1, Player1]);
+
+        let p1 = &analysis.player1;
+        assert_eq!(p1.by_score[0].score, "0-0");
+        assert!(p1.by_score[0].serving);
+        assert_eq!(at(p1, "0-0", true).points_won, 0);
+        let thirty_thirty = at(p1, "30-30", true);
+        assert_eq!(thirty_thirty.points_played, 1);
+        assert_eq!(thirty_thirty.win_rate, 1.0);
+
+        let p2 = &analysis.player2;
+        assert!(p2.by_score.iter().all(|s| !s.serving));
+        assert_eq!(at(p2, "0-15", false).points_won, 1);
+        assert_eq!(at(p2, "40-30", false).points_won, 0);
+
+        assert_eq!(p1.outcomes.holds_from_0_30, 1);
+        assert_eq!(p1.outcomes.love_holds, 0);
+    }
+
+    #[test]
+    fn test_game_and_set_outcomes() {
+        // Player1 holds to love, breaks from 40-0 down, then wins every point
+        let mut scorers = vec![Player::Player1; 4];
+        scorers.extend([Player::Player2; 3]);
+        scorers.extend([Player::Player1; 5 + 16]);
+        let analysis = score_states(&scorers);
+        let outcomes = &analysis.player1.outcomes;
+
+        assert_eq!(outcomes.love_holds, 3);
+        assert_eq!(outcomes.breaks_to_love, 2);
+        assert_eq!(outcomes.breaks_from_40_0, 1);
+        assert_eq!(outcomes.bagels, 1);
+        assert_eq!(analysis.player2.outcomes, GameOutcomeStats::default());
+    }
+}
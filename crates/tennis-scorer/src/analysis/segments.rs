@@ -0,0 +1,223 @@
+use std::time::{Duration, SystemTime};
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::MatchConfig;
+
+use super::momentum::{MomentumData, compute_momentum};
+use super::pace::{PaceData, compute_pace, compute_pace_with_limit};
+use super::stats::{MatchAnalysis, compute_analysis};
+use super::types::PointContext;
+
+/// A part of a match to analyse on its own.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "segment", rename_all = "snake_case")]
+pub enum Segment {
+    Set {
+        set_number: u32,
+    },
+    /// Every tiebreak point in the match.
+    Tiebreaks,
+    /// Games `first_game..=last_game` of one set.
+    Games {
+        set_number: u32,
+        first_game: u32,
+        last_game: u32,
+    },
+    /// Points played from `start` up to but not including `end`.
+    TimeRange {
+        start: SystemTime,
+        end: SystemTime,
+    },
+    /// Points played in the final `duration` before the last point,
+    /// e.g. the last hour.
+    Last {
+        duration: Duration,
+    },
+}
+
+impl Segment {
+    /// The points of `points` that fall in this segment, in order.
+    pub fn points(&self, points: &[PointContext]) -> Vec<PointContext> {
+        match self {
+            Segment::Set { set_number } => filter_points(points, |p| p.set_number == *set_number),
+            Segment::Tiebreaks => filter_points(points, |p| p.is_tiebreak),
+            Segment::Games {
+                set_number,
+                first_game,
+                last_game,
+            } => filter_points(points, |p| {
+                p.set_number == *set_number
+                    && (*first_game..=*last_game).contains(&p.game_number_in_set)
+            }),
+            Segment::TimeRange { start, end } => {
+                filter_points(points, |p| (*start..*end).contains(&p.timestamp))
+            }
+            Segment::Last { duration } => {
+                let Some(last) = points.last() else {
+                    return Vec::new();
+                };
+                let start = last.timestamp.checked_sub(*duration);
+                filter_points(points, |p| start.is_none_or(|start| p.timestamp >= start))
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SegmentAnalysis {
+    pub segment: Segment,
+    pub points_played: u32,
+    pub analysis: MatchAnalysis,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SetAnalysis {
+    pub set_number: u32,
+    pub analysis: MatchAnalysis,
+}
+
+/// Whole-match stats alongside the per-set split, momentum and pace, all
+/// from one replay.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MatchReport {
+    pub analysis: MatchAnalysis,
+    pub per_set: Vec<SetAnalysis>,
+    pub momentum: MomentumData,
+    pub pace: PaceData,
+}
+
+/// The points matching `predicate`, in order.
+pub fn filter_points(
+    points: &[PointContext],
+    predicate: impl Fn(&PointContext) -> bool,
+) -> Vec<PointContext> {
+    points.iter().filter(|p| predicate(p)).cloned().collect()
+}
+
+/// Stats for each segment, computed as if its points were a match of
+/// their own.
+pub fn compute_segment_analysis(
+    points: &[PointContext],
+    segments: &[Segment],
+) -> Vec<SegmentAnalysis> {
+    segments
+        .iter()
+        .map(|segment| {
+            let segment_points = segment.points(points);
+            SegmentAnalysis {
+                segment: segment.clone(),
+                points_played: segment_points.len() as u32,
+                analysis: compute_analysis(&segment_points),
+            }
+        })
+        .collect()
+}
+
+/// Stats for each set played, in order.
+pub fn compute_set_analysis(points: &[PointContext]) -> Vec<SetAnalysis> {
+    points
+        .chunk_by(|a, b| a.set_number == b.set_number)
+        .map(|set| SetAnalysis {
+            set_number: set[0].set_number,
+            analysis: compute_analysis(set),
+        })
+        .collect()
+}
+
+/// `points` must come from replaying a match played under `config`.
+pub fn compute_report(config: &MatchConfig, points: &[PointContext]) -> MatchReport {
+    MatchReport {
+        analysis: compute_analysis(points),
+        per_set: compute_set_analysis(points),
+        momentum: compute_momentum(points),
+        pace: match config.time_limit {
+            Some(limit) => compute_pace_with_limit(points, limit.duration()),
+            None => compute_pace(points),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::replay::replay_with_context;
+    use crate::types::Player;
+
+    fn ts(secs: u64) -> SystemTime {
+        SystemTime::UNIX_EPOCH + Duration::from_secs(1_000_000 + secs)
+    }
+
+    fn make_events(scorers: &[Player]) -> Vec<(Player, SystemTime)> {
+        scorers
+            .iter()
+            .enumerate()
+            .map(|(i, p)| (*p, ts(i as u64 * 30)))
+            .collect()
+    }
+
+    fn first_set_then_one_game() -> Vec<PointContext> {
+        // Player1 wins the first set to love, then Player2 breaks
+        let mut scorers = vec![Player::Player1; 24];
+        scorers.extend([Player::Player2; 4]);
+        replay_with_context(&MatchConfig::default(), &make_events(&scorers))
+    }
+
+    #[test]
+    fn test_segments() {
+        let points = first_set_then_one_game();
+
+        let set2 = Segment::Set { set_number: 2 }.points(&points);
+        assert_eq!(set2.len(), 4);
+        assert!(set2.iter().all(|p| p.scorer == Player::Player2));
+
+        let games = Segment::Games {
+            set_number: 1,
+            first_game: 2,
+            last_game: 3,
+        };
+        assert_eq!(games.points(&points).len(), 8);
+        assert_eq!(games.points(&points)[0].point_number, 5);
+
+        let window = Segment::TimeRange {
+            start: ts(0),
+            end: ts(60),
+        };
+        assert_eq!(window.points(&points).len(), 2);
+
+        let last = Segment::Last {
+            duration: Duration::from_secs(90),
+        };
+        assert_eq!(last.points(&points).len(), 4);
+        assert!(Segment::Tiebreaks.points(&points).is_empty());
+        assert!(last.points(&[]).is_empty());
+
+        let analysis = compute_segment_analysis(&points, &[last, Segment::Tiebreaks]);
+        assert_eq!(analysis[0].points_played, 4);
+        assert_eq!(analysis[0].analysis.player2.service.return_games_won, 1);
+        assert_eq!(analysis[1].points_played, 0);
+    }
+
+    #[test]
+    fn test_report_splits_sets() {
+        let points = first_set_then_one_game();
+        let report = compute_report(&MatchConfig::default(), &points);
+
+        assert_eq!(report.per_set.len(), 2);
+        assert_eq!(report.per_set[0].set_number, 1);
+        assert_eq!(
+            report.per_set[0].analysis.player1.total_points.points_won,
+            24
+        );
+        assert_eq!(
+            report.per_set[1].analysis.player1.total_points.points_won,
+            0
+        );
+        assert_eq!(report.analysis, compute_analysis(&points));
+        assert_eq!(report.momentum.per_set_basic.len(), 2);
+        assert_eq!(report.pace.total_duration_seconds, 27.0 * 30.0);
+
+        let predicate = filter_points(&points, |p| p.serving_player == Player::Player2);
+        assert_eq!(predicate.len(), 4 * 3);
+    }
+}
@@ -2,7 +2,9 @@ use serde::{Deserialize, Serialize};
 
 use crate::types::Player;
 
-use super::types::PointContext;
+use super::comeback::{ComebackStats, compute_comeback};
+use super::court::{CourtStats, compute_court_stats};
+use super::types::{PointContext, before_time_called};
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct BreakPointStats {
@@ -35,6 +37,14 @@ pub struct DeuceStats {
     pub deuce_game_win_rate: f64,
     pub total_deuce_count: u32,
     pub average_deuces_per_deuce_game: f64,
+    /// Deuce games settled by a deciding point (no-ad, golden or star
+    /// point) rather than from advantage. Included in `deuce_games_count`.
+    #[serde(default)]
+    pub golden_point_games: u32,
+    #[serde(default)]
+    pub golden_points_won: u32,
+    #[serde(default)]
+    pub golden_point_win_rate: f64,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -92,6 +102,10 @@ pub struct PlayerStats {
     pub clutch: ClutchStats,
     pub tiebreak: TiebreakStats,
     pub total_points: TotalPointsStats,
+    #[serde(default)]
+    pub comeback: ComebackStats,
+    #[serde(default)]
+    pub court: CourtStats,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -100,7 +114,10 @@ pub struct MatchAnalysis {
     pub player2: PlayerStats,
 }
 
+/// Stats for both players. Points played after time was called are left
+/// out, as they do not change the score.
 pub fn compute_analysis(points: &[PointContext]) -> MatchAnalysis {
+    let points = before_time_called(points);
     MatchAnalysis {
         player1: compute_player_stats(points, Player::Player1),
         player2: compute_player_stats(points, Player::Player2),
@@ -117,6 +134,8 @@ fn compute_player_stats(points: &[PointContext], player: Player) -> PlayerStats
         clutch: compute_clutch(points, player),
         tiebreak: compute_tiebreak_stats(points, player),
         total_points: compute_total_points(points, player),
+        comeback: compute_comeback(points, player),
+        court: compute_court_stats(points, player),
     }
 }
 
@@ -169,7 +188,9 @@ pub fn compute_service_stats(points: &[PointContext], player: Player) -> Service
         std::collections::BTreeMap::new();
 
     for p in points {
-        if p.serving_player == player {
+        if p.is_bisque {
+            // Scored without a rally, so neither served nor returned
+        } else if p.serving_player == player {
             service_points_total += 1;
             if p.scorer == player {
                 service_points_won += 1;
@@ -265,6 +286,8 @@ pub fn compute_deuce_stats(points: &[PointContext], player: Player) -> DeuceStat
     // Track games that reach deuce and their outcomes
     let mut deuce_games: std::collections::BTreeMap<(u32, u32), (u8, Option<Player>)> =
         std::collections::BTreeMap::new();
+    let mut golden_points: std::collections::BTreeMap<(u32, u32), Player> =
+        std::collections::BTreeMap::new();
 
     for p in points {
         let key = (p.set_number, p.game_number_in_set);
@@ -282,6 +305,9 @@ pub fn compute_deuce_stats(points: &[PointContext], player: Player) -> DeuceStat
             // Track who eventually wins (last point scorer in deuce state)
             entry.1 = Some(p.scorer);
         }
+        if p.is_golden_point {
+            golden_points.insert(key, p.scorer);
+        }
     }
 
     let deuce_games_count = deuce_games.len() as u32;
@@ -294,6 +320,8 @@ pub fn compute_deuce_stats(points: &[PointContext], player: Player) -> DeuceStat
             deuce_games_won += 1;
         }
     }
+    let golden_point_games = golden_points.len() as u32;
+    let golden_points_won = golden_points.values().filter(|&&w| w == player).count() as u32;
 
     DeuceStats {
         deuce_games_count,
@@ -309,6 +337,13 @@ pub fn compute_deuce_stats(points: &[PointContext], player: Player) -> DeuceStat
         } else {
             0.0
         },
+        golden_point_games,
+        golden_points_won,
+        golden_point_win_rate: if golden_point_games > 0 {
+            golden_points_won as f64 / golden_point_games as f64
+        } else {
+            0.0
+        },
     }
 }
 
@@ -510,6 +545,9 @@ pub fn compute_clutch(points: &[PointContext], player: Player) -> ClutchStats {
     }
 }
 
+/// Tiebreaks played and won by `player`, each set's tiebreak (or each
+/// tiebreak of a tiebreak-only match) counting once. A tiebreak still in
+/// progress at the last point is left out.
 pub fn compute_tiebreak_stats(points: &[PointContext], player: Player) -> TiebreakStats {
     // Group tiebreak points by set
     let mut tiebreak_sets: std::collections::BTreeMap<u32, Vec<&PointContext>> =
@@ -526,11 +564,9 @@ pub fn compute_tiebreak_stats(points: &[PointContext], player: Player) -> Tiebre
     let mut total_margin = 0f64;
 
     for tb_points in tiebreak_sets.values() {
-        if tb_points.is_empty() {
+        let Some(last) = tb_points.last() else {
             continue;
-        }
-        played += 1;
-        let last = tb_points.last().unwrap();
+        };
         let gs = &last.score_before.current_game;
         let p1: u8 = gs.player1_points.parse().unwrap_or(0);
         let p2: u8 = gs.player2_points.parse().unwrap_or(0);
@@ -541,6 +577,11 @@ pub fn compute_tiebreak_stats(points: &[PointContext], player: Player) -> Tiebre
         } else {
             (p2 + 1, p1)
         };
+        // Only a converted game point can have ended the tiebreak
+        if !last.is_game_point || winner_pts <= loser_pts {
+            continue;
+        }
+        played += 1;
         total_margin += (winner_pts as i32 - loser_pts as i32).unsigned_abs() as f64;
 
         if last.scorer == player {
@@ -571,7 +612,7 @@ pub fn compute_total_points(points: &[PointContext], player: Player) -> TotalPoi
     }
 }
 
-fn rate(numerator: u32, denominator: u32) -> f64 {
+pub(super) fn rate(numerator: u32, denominator: u32) -> f64 {
     if denominator > 0 {
         numerator as f64 / denominator as f64
     } else {
@@ -582,8 +623,11 @@ fn rate(numerator: u32, denominator: u32) -> f64 {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::analysis::replay::replay_with_context;
-    use crate::config::MatchConfig;
+    use crate::analysis::replay::{replay_recording, replay_with, replay_with_context};
+    use crate::config::{Handicap, MatchConfig};
+    use crate::history::MatchWithHistory;
+    use crate::match_state::MatchState;
+    use crate::rules::TiebreakOnlyRules;
     use std::time::{Duration, SystemTime};
 
     fn ts(secs: u64) -> SystemTime {
@@ -645,6 +689,31 @@ mod tests {
         assert_eq!(analysis.player1.service.service_points_won, 24); // 6 service games * 4 pts
     }
 
+    #[test]
+    fn test_bisques_excluded_from_service_stats() {
+        let config = MatchConfig {
+            handicap: Handicap {
+                player2_bisques: 1,
+                ..Default::default()
+            },
+            ..MatchConfig::default()
+        };
+        // Player1 serves: wins one point, then Player2 claims a bisque
+        let recording = MatchWithHistory::new(MatchState::new(config))
+            .score_point_at(Player::Player1, ts(0))
+            .unwrap()
+            .claim_bisque(Player::Player2)
+            .unwrap();
+        let contexts = replay_recording(&recording);
+        assert!(contexts[1].is_bisque);
+
+        let analysis = compute_analysis(&contexts);
+        assert_eq!(analysis.player1.service.service_points_total, 1);
+        assert_eq!(analysis.player2.service.return_points_total, 1);
+        assert_eq!(analysis.player2.service.return_points_won, 0);
+        assert_eq!(analysis.player2.total_points.points_won, 1);
+    }
+
     #[test]
     fn test_streaks_dominant_match() {
         let config = MatchConfig::default();
@@ -700,6 +769,67 @@ mod tests {
         assert_eq!(deuce.deuce_games_won, 1);
     }
 
+    #[test]
+    fn test_deuce_stats_separate_star_point_games() {
+        let config = MatchConfig::padel(Player::Player1);
+        let mut scorers = vec![
+            Player::Player1,
+            Player::Player1,
+            Player::Player1,
+            Player::Player2,
+            Player::Player2,
+            Player::Player2, // Deuce 1
+            Player::Player1,
+            Player::Player2, // Deuce 2
+            Player::Player2,
+            Player::Player1, // Deuce 3: star point
+            Player::Player2, // P2 wins the star point
+        ];
+        // Second game decided from advantage at the first deuce
+        scorers.extend([
+            Player::Player1,
+            Player::Player1,
+            Player::Player1,
+            Player::Player2,
+            Player::Player2,
+            Player::Player2,
+            Player::Player1,
+            Player::Player1,
+        ]);
+        let contexts = replay_with_context(&config, &make_events(&scorers));
+        assert!(contexts[10].is_golden_point);
+        assert_eq!(contexts.iter().filter(|c| c.is_golden_point).count(), 1);
+
+        let p1 = compute_deuce_stats(&contexts, Player::Player1);
+        let p2 = compute_deuce_stats(&contexts, Player::Player2);
+        assert_eq!(p1.deuce_games_count, 2);
+        assert_eq!(p1.golden_point_games, 1);
+        assert_eq!(p1.golden_points_won, 0);
+        assert_eq!(p2.golden_points_won, 1);
+        assert_eq!(p2.golden_point_win_rate, 1.0);
+    }
+
+    #[test]
+    fn test_no_ad_deuce_is_golden_point() {
+        let config = MatchConfig {
+            no_ad_scoring: true,
+            ..MatchConfig::default()
+        };
+        let events = make_events(&[
+            Player::Player1,
+            Player::Player1,
+            Player::Player1,
+            Player::Player2,
+            Player::Player2,
+            Player::Player2,
+            Player::Player1,
+        ]);
+        let contexts = replay_with_context(&config, &events);
+        let deuce = compute_deuce_stats(&contexts, Player::Player1);
+        assert_eq!(deuce.golden_point_games, 1);
+        assert_eq!(deuce.golden_points_won, 1);
+    }
+
     #[test]
     fn test_empty_match_analysis() {
         let contexts: Vec<PointContext> = vec![];
@@ -736,4 +866,25 @@ mod tests {
         assert_eq!(tb.tiebreaks_won, 1);
         assert_eq!(tb.tiebreak_win_rate, 1.0);
     }
+
+    #[test]
+    fn test_tiebreak_only_stats_count_each_tiebreak() {
+        let config = MatchConfig::tiebreaks(7, 2);
+        // P1 takes the first 7-0, P2 the second 7-3, and the third stops at 2-1
+        let mut scorers = vec![Player::Player1; 7];
+        scorers.extend([Player::Player1; 3]);
+        scorers.extend([Player::Player2; 7]);
+        scorers.extend([Player::Player1, Player::Player1, Player::Player2]);
+        let contexts = replay_with(
+            &MatchState::new_with(config, &TiebreakOnlyRules),
+            &make_events(&scorers),
+            &TiebreakOnlyRules,
+            None,
+        );
+
+        let tb = compute_tiebreak_stats(&contexts, Player::Player1);
+        assert_eq!(tb.tiebreaks_played, 2);
+        assert_eq!(tb.tiebreaks_won, 1);
+        assert_eq!(tb.average_tiebreak_margin, 5.5);
+    }
 }
@@ -0,0 +1,299 @@
+use serde::{Deserialize, Serialize};
+
+use crate::types::Player;
+
+use super::momentum::{MomentumData, ends_game};
+use super::types::{PointContext, ScoreSnapshot};
+
+/// A stretch of the match in which one player kept gaining momentum.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MomentumPhase {
+    pub start_point: u32,
+    pub end_point: u32,
+    /// `None` while neither player has swung the line by the threshold.
+    pub leader: Option<Player>,
+    /// Change in the momentum line over the phase.
+    pub swing: f64,
+}
+
+/// A moment worth annotating on a momentum chart.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TurningPoint {
+    pub point_number: u32,
+    pub player: Player,
+    /// Score before the point.
+    pub score: ScoreSnapshot,
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TurningPointData {
+    pub phases: Vec<MomentumPhase>,
+    pub turning_points: Vec<TurningPoint>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct TurningPointOptions {
+    /// How far the momentum line must come back from its extreme before a
+    /// new phase starts, in the line's own units.
+    pub min_swing: f64,
+    /// Shortest run of games reported as a turning point.
+    pub min_game_run: u32,
+}
+
+impl Default for TurningPointOptions {
+    fn default() -> Self {
+        Self {
+            min_swing: 4.0,
+            min_game_run: 3,
+        }
+    }
+}
+
+/// Split the match into momentum phases and mark its turning points:
+/// phase changes, break-backs and runs of games.
+///
+/// Phases follow the model line of `momentum` when there is one, otherwise
+/// the basic line, which must be computed over the same `points`.
+pub fn compute_turning_points(
+    points: &[PointContext],
+    momentum: &MomentumData,
+    options: &TurningPointOptions,
+) -> TurningPointData {
+    let line = if momentum.model.is_empty() {
+        &momentum.basic
+    } else {
+        &momentum.model
+    };
+    let phases = momentum_phases(points, line, options.min_swing);
+
+    let mut turning_points: Vec<TurningPoint> = phases
+        .iter()
+        .skip(1)
+        .filter_map(|phase| {
+            let player = phase.leader?;
+            let phase_points = points
+                .iter()
+                .filter(|p| (phase.start_point..=phase.end_point).contains(&p.point_number));
+            let won = phase_points.clone().filter(|p| p.scorer == player).count();
+            let start = points
+                .iter()
+                .find(|p| p.point_number == phase.start_point)?;
+            Some(TurningPoint {
+                point_number: phase.start_point,
+                player,
+                score: start.score_before.clone(),
+                reason: format!("won {won} of the next {} points", phase_points.count()),
+            })
+        })
+        .collect();
+    turning_points.extend(game_turning_points(points, options.min_game_run));
+    turning_points.sort_by_key(|t| t.point_number);
+
+    TurningPointData {
+        phases,
+        turning_points,
+    }
+}
+
+/// Zigzag segmentation: a phase ends at the line's extreme once the line
+/// has come back from it by `min_swing`.
+fn momentum_phases(points: &[PointContext], line: &[f64], min_swing: f64) -> Vec<MomentumPhase> {
+    let len = points.len().min(line.len());
+    if len == 0 {
+        return Vec::new();
+    }
+    let value_before = |i: usize| if i == 0 { 0.0 } else { line[i - 1] };
+    let mut phases = Vec::new();
+    let mut start = 0;
+    let mut leader: Option<Player> = None;
+    let mut extreme = 0;
+
+    for (i, &value) in line.iter().enumerate().take(len) {
+        let from_start = value - value_before(start);
+        match leader {
+            None => {
+                if from_start >= min_swing {
+                    leader = Some(Player::Player1);
+                } else if from_start <= -min_swing {
+                    leader = Some(Player::Player2);
+                }
+                extreme = i;
+            }
+            Some(player) => {
+                let gain = match player {
+                    Player::Player1 => value - line[extreme],
+                    Player::Player2 => line[extreme] - value,
+                };
+                if gain > 0.0 {
+                    extreme = i;
+                } else if -gain >= min_swing {
+                    phases.push(MomentumPhase {
+                        start_point: points[start].point_number,
+                        end_point: points[extreme].point_number,
+                        leader,
+                        swing: line[extreme] - value_before(start),
+                    });
+                    start = extreme + 1;
+                    leader = Some(player.opponent());
+                    extreme = i;
+                }
+            }
+        }
+    }
+
+    phases.push(MomentumPhase {
+        start_point: points[start].point_number,
+        end_point: points[len - 1].point_number,
+        leader,
+        swing: line[len - 1] - value_before(start),
+    });
+    phases
+}
+
+/// Break-backs and runs of at least `min_game_run` games, each marked on
+/// the point that completed it.
+fn game_turning_points(points: &[PointContext], min_game_run: u32) -> Vec<TurningPoint> {
+    let mut turning_points = Vec::new();
+    let mut set_number = 0;
+    let mut breaks = [0u32; 2];
+    let mut run: Option<(Player, u32, &PointContext)> = None;
+
+    let end_run = |run: Option<(Player, u32, &PointContext)>, out: &mut Vec<TurningPoint>| {
+        if let Some((player, games, last)) = run
+            && games >= min_game_run.max(1)
+        {
+            out.push(TurningPoint {
+                point_number: last.point_number,
+                player,
+                score: last.score_before.clone(),
+                reason: format!("won {games} straight games"),
+            });
+        }
+    };
+
+    for p in points.iter().filter(|p| ends_game(p)) {
+        let winner = p.scorer;
+        if p.set_number != set_number {
+            set_number = p.set_number;
+            breaks = [0, 0];
+        }
+
+        if winner != p.serving_player && !p.is_tiebreak {
+            let (mine, theirs) = match winner {
+                Player::Player1 => (0, 1),
+                Player::Player2 => (1, 0),
+            };
+            if breaks[theirs] > breaks[mine]
+                && let Some(set) = p.score_before.sets.last()
+            {
+                let (own, other) = match winner {
+                    Player::Player1 => (set.player1_games + 1, set.player2_games),
+                    Player::Player2 => (set.player2_games + 1, set.player1_games),
+                };
+                turning_points.push(TurningPoint {
+                    point_number: p.point_number,
+                    player: winner,
+                    score: p.score_before.clone(),
+                    reason: format!("broke back to {own}-{other}"),
+                });
+            }
+            breaks[mine] += 1;
+        }
+
+        run = match run {
+            Some((player, games, _)) if player == winner => Some((player, games + 1, p)),
+            previous => {
+                end_run(previous, &mut turning_points);
+                Some((winner, 1, p))
+            }
+        };
+    }
+    end_run(run, &mut turning_points);
+
+    turning_points
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::momentum::compute_momentum;
+    use crate::analysis::replay::replay_with_context;
+    use crate::config::MatchConfig;
+    use std::time::{Duration, SystemTime};
+
+    fn ts(secs: u64) -> SystemTime {
+        SystemTime::UNIX_EPOCH + Duration::from_secs(1_000_000 + secs)
+    }
+
+    fn make_events(scorers: &[Player]) -> Vec<(Player, SystemTime)> {
+        scorers
+            .iter()
+            .enumerate()
+            .map(|(i, p)| (*p, ts(i as u64 * 30)))
+            .collect()
+    }
+
+    fn turning_points(scorers: &[Player]) -> TurningPointData {
+        let contexts = replay_with_context(&MatchConfig::default(), &make_events(scorers));
+        let momentum = compute_momentum(&contexts);
+        compute_turning_points(&contexts, &momentum, &TurningPointOptions::default())
+    }
+
+    #[test]
+    fn test_empty() {
+        let data = turning_points(&[]);
+        assert!(data.phases.is_empty());
+        assert!(data.turning_points.is_empty());
+    }
+
+    #[test]
+    fn test_phases_follow_swings() {
+        let mut scorers = vec![Player::Player1; 6];
+        scorers.extend([Player::Player2; 5]);
+        scorers.push(Player::Player1);
+        let data = turning_points(&scorers);
+
+        assert_eq!(data.phases.len(), 2);
+        assert_eq!(data.phases[0].start_point, 1);
+        assert_eq!(data.phases[0].end_point, 6);
+        assert_eq!(data.phases[0].leader, Some(Player::Player1));
+        assert_eq!(data.phases[0].swing, 6.0);
+        assert_eq!(data.phases[1].start_point, 7);
+        assert_eq!(data.phases[1].leader, Some(Player::Player2));
+        assert_eq!(data.phases[1].swing, -4.0);
+
+        let shift = &data.turning_points[0];
+        assert_eq!(shift.point_number, 7);
+        assert_eq!(shift.player, Player::Player2);
+        assert_eq!(shift.reason, "won 5 of the next 6 points");
+    }
+
+    #[test]
+    fn test_break_back_and_game_run() {
+        // Player2 holds, breaks, then Player1 breaks back and holds for 3-2
+        let mut scorers = vec![Player::Player1; 4];
+        for _ in 0..2 {
+            scorers.extend([Player::Player2; 4]);
+        }
+        scorers.extend([Player::Player1; 12]);
+        let data = turning_points(&scorers);
+
+        let reasons: Vec<(u32, &str)> = data
+            .turning_points
+            .iter()
+            .map(|t| (t.point_number, t.reason.as_str()))
+            .collect();
+        assert!(reasons.contains(&(16, "broke back to 2-2")));
+        assert!(reasons.contains(&(24, "won 3 straight games")));
+
+        let break_back = data
+            .turning_points
+            .iter()
+            .find(|t| t.reason.starts_with("broke back"))
+            .unwrap();
+        assert_eq!(break_back.player, Player::Player1);
+        assert_eq!(break_back.score.sets[0].player1_games, 1);
+        assert_eq!(break_back.score.current_game.player1_points, "40");
+    }
+}
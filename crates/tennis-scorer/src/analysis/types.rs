@@ -2,7 +2,7 @@ use std::time::SystemTime;
 
 use serde::{Deserialize, Serialize};
 
-use crate::types::Player;
+use crate::types::{Player, Point};
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum PointEndType {
@@ -38,6 +38,9 @@ pub struct ScoreSnapshot {
     pub current_game: GameScore,
     pub player1_sets: u8,
     pub player2_sets: u8,
+    /// Running (player1, player2) game totals in a cumulative-games match.
+    #[serde(default)]
+    pub total_games: Option<(u16, u16)>,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -56,4 +59,67 @@ pub struct PointContext {
     pub is_tiebreak: bool,
     #[serde(default)]
     pub point_end_type: Option<PointEndType>,
+    /// A deciding point at deuce (no-ad, or padel's golden/star point):
+    /// whoever wins it wins the game.
+    #[serde(default)]
+    pub is_golden_point: bool,
+    /// Player1's match-win probability if they win the point minus if they
+    /// lose it. Set by `annotate_importance`.
+    #[serde(default)]
+    pub importance: Option<f64>,
+    /// Which member of the serving team served, as in the `serve_order`
+    /// entries. Always 0 without a serve order.
+    #[serde(default)]
+    pub server_position: u8,
+    /// Which member of the receiving team received: 0 for the one in the
+    /// deuce court, 1 for the one in the ad court, as partners keep their
+    /// sides through a set. Always 0 without a serve order.
+    #[serde(default)]
+    pub returner_position: u8,
+    /// Claimed from a handicap bisque rather than played. It counts
+    /// towards the score but not the serve and return stats.
+    #[serde(default)]
+    pub is_bisque: bool,
+    /// Played after time was called on a timed match, in the sudden-death
+    /// point or tiebreak. The score before it is the score at time, and it
+    /// is left out of the match stats.
+    #[serde(default)]
+    pub is_overtime: bool,
+}
+
+/// The point a game-score label names, from "-30" and "-15" (owed in
+/// handicap play) through "0" to "40". `None` for anything else, such as a
+/// tiebreak count or advantage.
+pub(super) fn parse_point(label: &str) -> Option<Point> {
+    POINTS.into_iter().find(|point| point.to_string() == label)
+}
+
+/// How far a game-score label stands from love, in points: -2 and -1 for
+/// owed handicap points, 0 to 3 for love to 40, and 4 for advantage ("A",
+/// as game-log labels write it).
+pub(super) fn point_rank(label: &str) -> Option<i8> {
+    if label == "A" {
+        return Some(4);
+    }
+    let point = parse_point(label)?;
+    POINTS.iter().position(|&p| p == point).map(|i| i as i8 - 2)
+}
+
+const POINTS: [Point; 6] = [
+    Point::OweThirty,
+    Point::OweFifteen,
+    Point::Love,
+    Point::Fifteen,
+    Point::Thirty,
+    Point::Forty,
+];
+
+/// The points scored normally, up to any called on time: overtime points
+/// always come last.
+pub(super) fn before_time_called(points: &[PointContext]) -> &[PointContext] {
+    let end = points
+        .iter()
+        .position(|p| p.is_overtime)
+        .unwrap_or(points.len());
+    &points[..end]
 }
@@ -0,0 +1,48 @@
+use std::time::SystemTime;
+
+/// Source of the timestamps recorded for scored points.
+///
+/// `MatchWithHistory::score_point` uses [`SystemClock`]; tests and importers
+/// can pass their own clock to `score_point_with_clock` instead of sleeping.
+pub trait Clock {
+    fn now(&self) -> SystemTime;
+}
+
+/// Clock backed by the system wall clock.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> SystemTime {
+        SystemTime::now()
+    }
+}
+
+impl<F> Clock for F
+where
+    F: Fn() -> SystemTime,
+{
+    fn now(&self) -> SystemTime {
+        self()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_system_clock_is_current() {
+        let before = SystemTime::now();
+        let now = SystemClock.now();
+        assert!(now >= before);
+    }
+
+    #[test]
+    fn test_closure_clock() {
+        let fixed = SystemTime::UNIX_EPOCH + Duration::from_secs(42);
+        let clock = move || fixed;
+        assert_eq!(clock.now(), fixed);
+    }
+}
@@ -1,4 +1,7 @@
-use crate::types::Player;
+use std::time::Duration;
+
+use crate::game::GameState;
+use crate::types::{Player, Point};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
@@ -8,6 +11,125 @@ pub enum MatchType {
     Doubles,
 }
 
+/// Club handicap settings: points each player starts every game with, and
+/// bisques (free points a player may claim at any time).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct Handicap {
+    /// Points received at the start of each game: 1 is +15, 2 is +30, 3 is
+    /// +40; -1 and -2 owe 15 and 30.
+    pub player1_points: i8,
+    pub player2_points: i8,
+    pub player1_bisques: u8,
+    pub player2_bisques: u8,
+}
+
+impl Handicap {
+    /// The score every regular game starts at. Tiebreaks are not
+    /// handicapped.
+    pub fn starting_game(&self) -> GameState {
+        GameState::starting_at(
+            handicap_point(self.player1_points),
+            handicap_point(self.player2_points),
+        )
+    }
+
+    pub fn bisques(&self, player: Player) -> u8 {
+        match player {
+            Player::Player1 => self.player1_bisques,
+            Player::Player2 => self.player2_bisques,
+        }
+    }
+}
+
+fn handicap_point(points: i8) -> Point {
+    match points {
+        i8::MIN..=-2 => Point::OweThirty,
+        -1 => Point::OweFifteen,
+        0 => Point::Love,
+        1 => Point::Fifteen,
+        2 => Point::Thirty,
+        3..=i8::MAX => Point::Forty,
+    }
+}
+
+/// How a tiebreak is won once a side reaches its target.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TiebreakRule {
+    /// Lead needed to win: 2 normally, 1 for sudden death at the target.
+    pub win_by: u8,
+    /// When set, the first side to this many points wins whatever the
+    /// margin, e.g. 13 for sudden death at 12-12 in a match tiebreak to 10.
+    #[serde(default)]
+    pub cap: Option<u8>,
+}
+
+impl Default for TiebreakRule {
+    fn default() -> Self {
+        Self {
+            win_by: 2,
+            cap: None,
+        }
+    }
+}
+
+impl TiebreakRule {
+    /// Winner of a tiebreak to `target_points` at these points, if it is
+    /// over.
+    pub fn winner(
+        &self,
+        player1_points: u8,
+        player2_points: u8,
+        target_points: u8,
+    ) -> Option<Player> {
+        let leader = if player1_points > player2_points {
+            Player::Player1
+        } else {
+            Player::Player2
+        };
+        let top = player1_points.max(player2_points);
+        let capped = self.cap.is_some_and(|cap| top >= cap);
+        if capped
+            || (top >= target_points && player1_points.abs_diff(player2_points) >= self.win_by)
+        {
+            Some(leader)
+        } else {
+            None
+        }
+    }
+}
+
+/// How the leader is decided when a timed match runs out of time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TimedLeader {
+    /// Most games won across the whole match.
+    Games,
+    /// Most sets won, then most games in the unfinished set.
+    SetsThenGames,
+}
+
+/// What is played when the score is level at the end of a timed match.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TimedDecider {
+    /// One point decides the match.
+    SuddenDeathPoint,
+    /// A tiebreak to this many points, won by two.
+    Tiebreak { points: u8 },
+}
+
+/// A match played until its slot ends (see `MatchWithHistory::end_on_time`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TimeLimit {
+    pub minutes: u16,
+    pub leader: TimedLeader,
+    pub decider: TimedDecider,
+}
+
+impl TimeLimit {
+    pub fn duration(&self) -> Duration {
+        Duration::from_secs(u64::from(self.minutes) * 60)
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct MatchConfig {
     pub sets_to_win: u8,
@@ -18,6 +140,26 @@ pub struct MatchConfig {
     pub match_type: MatchType,
     #[serde(default)]
     pub serve_order: Vec<(Player, u8)>,
+    /// When set, the deciding set is replaced by a single match tiebreak
+    /// played to this many points (commonly 10).
+    #[serde(default)]
+    pub match_tiebreak_points: Option<u8>,
+    /// When set, the point played at this deuce decides the game: 3 is
+    /// padel's star point (two advantages, then a deciding point).
+    /// `no_ad_scoring` is the same as 1.
+    #[serde(default)]
+    pub golden_point_deuce: Option<u8>,
+    #[serde(default)]
+    pub handicap: Handicap,
+    #[serde(default)]
+    pub time_limit: Option<TimeLimit>,
+    /// How set tiebreaks (including those of a tiebreak-only match) are
+    /// won.
+    #[serde(default)]
+    pub tiebreak_rule: TiebreakRule,
+    /// How a match tiebreak or supertiebreak is won.
+    #[serde(default)]
+    pub match_tiebreak_rule: TiebreakRule,
 }
 
 impl Default for MatchConfig {
@@ -29,6 +171,61 @@ impl Default for MatchConfig {
             no_ad_scoring: false,
             match_type: MatchType::Singles,
             serve_order: Vec::new(),
+            match_tiebreak_points: None,
+            golden_point_deuce: None,
+            handicap: Handicap::default(),
+            time_limit: None,
+            tiebreak_rule: TiebreakRule::default(),
+            match_tiebreak_rule: TiebreakRule::default(),
+        }
+    }
+}
+
+impl MatchConfig {
+    /// Padel: doubles, best of three sets with tiebreaks to 7, and the star
+    /// point at the third deuce. `first_server_team` serves the first game.
+    pub fn padel(first_server_team: Player) -> Self {
+        let other = first_server_team.opponent();
+        Self {
+            match_type: MatchType::Doubles,
+            serve_order: vec![
+                (first_server_team, 0),
+                (other, 0),
+                (first_server_team, 1),
+                (other, 1),
+            ],
+            golden_point_deuce: Some(3),
+            ..Self::default()
+        }
+    }
+
+    /// Team tennis: no-ad scoring and tiebreaks to five. Play it under
+    /// `CumulativeGamesRules::team_tennis`, which sets the five sets to five
+    /// games, extended play and the supertiebreak.
+    pub fn team_tennis() -> Self {
+        Self {
+            tiebreak_points: 5,
+            no_ad_scoring: true,
+            ..Self::default()
+        }
+    }
+
+    /// A series of stand-alone tiebreaks to `points`, best of
+    /// `2 * tiebreaks_to_win - 1`. Play it under `TiebreakOnlyRules`.
+    pub fn tiebreaks(points: u8, tiebreaks_to_win: u8) -> Self {
+        Self {
+            sets_to_win: tiebreaks_to_win,
+            tiebreak_points: points,
+            ..Self::default()
+        }
+    }
+
+    /// The deuce whose point decides the game, if any.
+    pub fn deciding_deuce(&self) -> Option<u8> {
+        if self.no_ad_scoring {
+            Some(1)
+        } else {
+            self.golden_point_deuce
         }
     }
 }
@@ -79,5 +276,43 @@ mod tests {
         let config: MatchConfig = serde_json::from_str(json).unwrap();
         assert_eq!(config.match_type, MatchType::Singles);
         assert!(config.serve_order.is_empty());
+        assert_eq!(config.match_tiebreak_points, None);
+    }
+
+    #[test]
+    fn test_padel_config() {
+        let config = MatchConfig::padel(Player::Player2);
+        assert_eq!(config.match_type, MatchType::Doubles);
+        assert_eq!(config.serve_order[0], (Player::Player2, 0));
+        assert_eq!(config.deciding_deuce(), Some(3));
+        assert_eq!(
+            MatchConfig {
+                no_ad_scoring: true,
+                ..MatchConfig::default()
+            }
+            .deciding_deuce(),
+            Some(1)
+        );
+        assert_eq!(MatchConfig::default().deciding_deuce(), None);
+    }
+
+    #[test]
+    fn test_tiebreak_rule_margin_and_cap() {
+        let standard = TiebreakRule::default();
+        assert_eq!(standard.winner(7, 6, 7), None);
+        assert_eq!(standard.winner(9, 7, 7), Some(Player::Player1));
+
+        let sudden_death = TiebreakRule {
+            win_by: 1,
+            cap: None,
+        };
+        assert_eq!(sudden_death.winner(6, 7, 7), Some(Player::Player2));
+
+        let capped = TiebreakRule {
+            win_by: 2,
+            cap: Some(13),
+        };
+        assert_eq!(capped.winner(12, 11, 10), None);
+        assert_eq!(capped.winner(13, 12, 10), Some(Player::Player1));
     }
 }
@@ -1,3 +1,4 @@
+use crate::invariant::InvariantError;
 use crate::types::{Player, Point};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -16,7 +17,24 @@ impl GameState {
         }
     }
 
+    /// A game starting from the given points, e.g. a handicap start.
+    /// 40-40 is deuce.
+    pub fn starting_at(player1: Point, player2: Point) -> Self {
+        if player1 == Point::Forty && player2 == Point::Forty {
+            GameState::Deuce { count: 1 }
+        } else {
+            GameState::Points { player1, player2 }
+        }
+    }
+
     pub fn score_point(&self, scorer: Player, no_ad: bool) -> GameState {
+        self.score_point_deciding_at(scorer, no_ad.then_some(1))
+    }
+
+    /// Score a point in a game where the point played at the
+    /// `deciding_deuce`th deuce decides it: 1 is no-ad's golden point, 3 is
+    /// padel's star point, `None` plays advantages indefinitely.
+    pub fn score_point_deciding_at(&self, scorer: Player, deciding_deuce: Option<u8>) -> GameState {
         match self {
             GameState::Completed(_) => self.clone(),
 
@@ -28,7 +46,7 @@ impl GameState {
 
                 if scorer_points == Point::Forty {
                     if opponent_points == Point::Forty {
-                        if no_ad {
+                        if deciding_deuce.is_some_and(|n| n <= 1) {
                             GameState::Completed(scorer)
                         } else {
                             // First deuce, count = 1
@@ -58,7 +76,7 @@ impl GameState {
             }
 
             GameState::Deuce { count } => {
-                if no_ad {
+                if deciding_deuce.is_some_and(|n| *count >= n) {
                     GameState::Completed(scorer)
                 } else {
                     GameState::Advantage {
@@ -91,6 +109,35 @@ impl GameState {
         }
     }
 
+    /// Check the score is one `score_point` can produce: 40-40 is always
+    /// `Deuce`, deuce counts start at 1, and no-ad games never reach a
+    /// second deuce or an advantage.
+    pub fn validate(&self, no_ad: bool) -> Result<(), InvariantError> {
+        self.validate_deciding_at(no_ad.then_some(1))
+    }
+
+    /// Like [`GameState::validate`], for a game decided at the
+    /// `deciding_deuce`th deuce: no later deuce or advantage is reachable.
+    pub fn validate_deciding_at(&self, deciding_deuce: Option<u8>) -> Result<(), InvariantError> {
+        let valid = match self {
+            GameState::Points { player1, player2 } => {
+                !(*player1 == Point::Forty && *player2 == Point::Forty)
+            }
+            GameState::Deuce { count } => {
+                *count >= 1 && deciding_deuce.is_none_or(|n| *count <= n.max(1))
+            }
+            GameState::Advantage { deuce_count, .. } => {
+                *deuce_count >= 1 && deciding_deuce.is_none_or(|n| *deuce_count < n)
+            }
+            GameState::Completed(_) => true,
+        };
+        if valid {
+            Ok(())
+        } else {
+            Err(InvariantError::InvalidGame(self.clone()))
+        }
+    }
+
     pub fn deuce_count(&self) -> u8 {
         match self {
             GameState::Deuce { count } => *count,
@@ -241,4 +288,71 @@ mod tests {
         let game = game.score_point(Player::Player1, false);
         assert_eq!(game, GameState::Completed(Player::Player1));
     }
+
+    #[test]
+    fn test_validate() {
+        assert!(GameState::new().validate(false).is_ok());
+        assert!(GameState::Deuce { count: 3 }.validate(false).is_ok());
+        assert!(GameState::Deuce { count: 1 }.validate(true).is_ok());
+
+        let forty_all = GameState::Points {
+            player1: Point::Forty,
+            player2: Point::Forty,
+        };
+        assert_eq!(
+            forty_all.validate(false),
+            Err(InvariantError::InvalidGame(forty_all.clone()))
+        );
+        assert!(GameState::Deuce { count: 0 }.validate(false).is_err());
+        assert!(GameState::Deuce { count: 2 }.validate(true).is_err());
+        let advantage = GameState::Advantage {
+            player: Player::Player1,
+            deuce_count: 1,
+        };
+        assert!(advantage.validate(false).is_ok());
+        assert!(advantage.validate(true).is_err());
+    }
+
+    #[test]
+    fn test_star_point_after_third_deuce() {
+        let deuce = GameState::Deuce { count: 2 };
+        // Advantages are still played at the second deuce
+        assert_eq!(
+            deuce.score_point_deciding_at(Player::Player1, Some(3)),
+            GameState::Advantage {
+                player: Player::Player1,
+                deuce_count: 2
+            }
+        );
+        let star = GameState::Advantage {
+            player: Player::Player1,
+            deuce_count: 2,
+        }
+        .score_point_deciding_at(Player::Player2, Some(3));
+        assert_eq!(star, GameState::Deuce { count: 3 });
+        assert_eq!(
+            star.score_point_deciding_at(Player::Player2, Some(3)),
+            GameState::Completed(Player::Player2)
+        );
+    }
+
+    #[test]
+    fn test_validate_deciding_deuce() {
+        assert!(
+            GameState::Deuce { count: 3 }
+                .validate_deciding_at(Some(3))
+                .is_ok()
+        );
+        assert!(
+            GameState::Deuce { count: 4 }
+                .validate_deciding_at(Some(3))
+                .is_err()
+        );
+        let advantage = GameState::Advantage {
+            player: Player::Player1,
+            deuce_count: 3,
+        };
+        assert!(advantage.validate_deciding_at(Some(3)).is_err());
+        assert!(advantage.validate_deciding_at(None).is_ok());
+    }
 }
@@ -1,17 +1,210 @@
-use std::time::SystemTime;
+use std::fmt;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
 
+use crate::clock::{Clock, SystemClock};
+use crate::config::{MatchConfig, TimedDecider, TimedLeader};
 use crate::match_state::MatchState;
+use crate::rules::{ScoringRules, SetContext, SetKind, StandardRules};
+use crate::set::SetState;
+use crate::tiebreak::TiebreakState;
 use crate::types::Player;
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TimestampError {
+    /// The timestamp is earlier than the previously recorded point.
+    NotMonotonic {
+        previous: SystemTime,
+        given: SystemTime,
+    },
+}
+
+impl fmt::Display for TimestampError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TimestampError::NotMonotonic { previous, given } => write!(
+                f,
+                "timestamp {given:?} is earlier than the previous point at {previous:?}"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for TimestampError {}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BisqueError {
+    /// The player has already claimed every bisque the handicap gives them.
+    NoneRemaining(Player),
+    /// The match is over.
+    MatchOver,
+}
+
+impl fmt::Display for BisqueError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BisqueError::NoneRemaining(player) => write!(f, "{player:?} has no bisques left"),
+            BisqueError::MatchOver => write!(f, "the match is over"),
+        }
+    }
+}
+
+impl std::error::Error for BisqueError {}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EndOnTimeError {
+    /// The match config has no time limit.
+    NoTimeLimit,
+    /// The match is already over, or time has already been called.
+    AlreadyEnded,
+}
+
+impl fmt::Display for EndOnTimeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EndOnTimeError::NoTimeLimit => write!(f, "the match has no time limit"),
+            EndOnTimeError::AlreadyEnded => write!(f, "the match has already ended"),
+        }
+    }
+}
+
+impl std::error::Error for EndOnTimeError {}
+
+/// Why a match finished.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompletionReason {
+    /// Played to the end under the match rules.
+    Played,
+    /// Decided by `MatchWithHistory::end_on_time`, on the score or in a
+    /// sudden-death point or tiebreak.
+    TimeLimit,
+}
+
+/// Time called on a timed match (see `MatchWithHistory::end_on_time`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimeCalled {
+    pub at: SystemTime,
+    /// Points recorded before time was called. Any later points were played
+    /// in the sudden-death point or tiebreak and leave the score as it stood.
+    pub points_before: usize,
+}
+
+/// How and when a finished match ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Completion {
+    pub winner: Player,
+    pub reason: CompletionReason,
+    pub ended_at: SystemTime,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StitchError {
+    /// The two recordings were scored under different configs.
+    ConfigMismatch,
+    /// The two recordings were scored under different `ScoringRules`.
+    RulesMismatch,
+    /// The continuation does not start from the score the first recording
+    /// reached. Both scores are in standard notation.
+    ScoreMismatch {
+        recorded: String,
+        continuation: String,
+    },
+    /// The continuation's points replayed onto the first recording end at a
+    /// different score than the continuation itself.
+    ReplayMismatch {
+        merged: String,
+        continuation: String,
+    },
+    /// A continuation point is earlier than the first recording's last point.
+    Timestamp(TimestampError),
+    /// Time was called on the continuation, but cannot be on the merged
+    /// recording.
+    EndOnTime(EndOnTimeError),
+    /// A bisque claimed on the continuation cannot be claimed on the merged
+    /// recording.
+    Bisque(BisqueError),
+}
+
+impl fmt::Display for StitchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StitchError::ConfigMismatch => {
+                write!(f, "recordings were scored under different configs")
+            }
+            StitchError::RulesMismatch => {
+                write!(f, "recordings were scored under different rules")
+            }
+            StitchError::ScoreMismatch {
+                recorded,
+                continuation,
+            } => write!(
+                f,
+                "recording ends at {recorded} but continuation starts at {continuation}"
+            ),
+            StitchError::ReplayMismatch {
+                merged,
+                continuation,
+            } => write!(
+                f,
+                "merged replay ends at {merged} but continuation ends at {continuation}"
+            ),
+            StitchError::Timestamp(e) => write!(f, "{e}"),
+            StitchError::EndOnTime(e) => write!(f, "{e}"),
+            StitchError::Bisque(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+impl std::error::Error for StitchError {}
+
+impl From<TimestampError> for StitchError {
+    fn from(e: TimestampError) -> Self {
+        StitchError::Timestamp(e)
+    }
+}
+
+impl From<EndOnTimeError> for StitchError {
+    fn from(e: EndOnTimeError) -> Self {
+        StitchError::EndOnTime(e)
+    }
+}
+
+impl From<BisqueError> for StitchError {
+    fn from(e: BisqueError) -> Self {
+        StitchError::Bisque(e)
+    }
+}
+
+/// A score that advances one rally at a time, so that [`ScoreHistory`] can
+/// record it with undo and timestamps.
+pub trait Scoring: Clone {
+    /// The score after `scorer` wins the next point or rally.
+    fn score_point(&self, scorer: Player) -> Self;
+
+    /// The winner, once the contest is over.
+    fn winner(&self) -> Option<Player>;
+}
+
+impl Scoring for MatchState {
+    fn score_point(&self, scorer: Player) -> Self {
+        MatchState::score_point(self, scorer)
+    }
+
+    fn winner(&self) -> Option<Player> {
+        MatchState::winner(self)
+    }
+}
+
+/// Undo history and per-point timestamps for any [`Scoring`] state.
 #[derive(Debug, Clone)]
-pub struct MatchWithHistory {
-    current: MatchState,
-    history: Vec<MatchState>,
+pub struct ScoreHistory<S> {
+    current: S,
+    history: Vec<S>,
     point_events: Vec<(Player, SystemTime)>,
 }
 
-impl MatchWithHistory {
-    pub fn new(state: MatchState) -> Self {
+impl<S: Scoring> ScoreHistory<S> {
+    pub fn new(state: S) -> Self {
         Self {
             current: state,
             history: Vec::new(),
@@ -19,28 +212,82 @@ impl MatchWithHistory {
         }
     }
 
-    pub fn score_point(&self, scorer: Player) -> MatchWithHistory {
+    /// Rebuild a recording by replaying timestamped points from `start`.
+    pub fn from_events(start: S, events: &[(Player, SystemTime)]) -> Result<Self, TimestampError> {
+        events
+            .iter()
+            .try_fold(Self::new(start), |recording, &(scorer, timestamp)| {
+                recording.score_point_at(scorer, timestamp)
+            })
+    }
+
+    pub fn score_point(&self, scorer: Player) -> Self {
+        self.score_point_with_clock(scorer, &SystemClock)
+    }
+
+    /// Score a point stamped with the clock's current time.
+    ///
+    /// A clock reading earlier than the last recorded point (e.g. the wall
+    /// clock was adjusted) is clamped to that point's timestamp.
+    pub fn score_point_with_clock(&self, scorer: Player, clock: &dyn Clock) -> Self {
+        let timestamp = self.clamp(clock.now());
+        self.record(scorer, timestamp, |state| state.score_point(scorer))
+    }
+
+    /// Score a point that happened at an explicit time, e.g. when
+    /// back-filling a match or flushing points queued by the UI.
+    ///
+    /// Timestamps must be non-decreasing across the recorded points.
+    pub fn score_point_at(
+        &self,
+        scorer: Player,
+        timestamp: SystemTime,
+    ) -> Result<Self, TimestampError> {
+        self.check_timestamp(timestamp)?;
+        Ok(self.record(scorer, timestamp, |state| state.score_point(scorer)))
+    }
+
+    fn clamp(&self, now: SystemTime) -> SystemTime {
+        match self.last_timestamp() {
+            Some(last) if now < last => last,
+            _ => now,
+        }
+    }
+
+    fn check_timestamp(&self, timestamp: SystemTime) -> Result<(), TimestampError> {
+        match self.last_timestamp() {
+            Some(previous) if timestamp < previous => Err(TimestampError::NotMonotonic {
+                previous,
+                given: timestamp,
+            }),
+            _ => Ok(()),
+        }
+    }
+
+    /// Push the state `next` produces from the current one. Points after the
+    /// winner is decided are ignored.
+    fn record(&self, scorer: Player, timestamp: SystemTime, next: impl FnOnce(&S) -> S) -> Self {
         if self.current.winner().is_some() {
             return self.clone();
         }
 
-        let new_state = self.current.score_point(scorer);
+        let new_state = next(&self.current);
         let mut new_history = self.history.clone();
         new_history.push(self.current.clone());
 
         let mut new_point_events = self.point_events.clone();
-        new_point_events.push((scorer, SystemTime::now()));
+        new_point_events.push((scorer, timestamp));
 
         debug_assert_eq!(new_history.len(), new_point_events.len());
 
-        MatchWithHistory {
+        Self {
             current: new_state,
             history: new_history,
             point_events: new_point_events,
         }
     }
 
-    pub fn undo(&self) -> MatchWithHistory {
+    pub fn undo(&self) -> Self {
         if self.history.is_empty() {
             return self.clone();
         }
@@ -53,14 +300,14 @@ impl MatchWithHistory {
 
         debug_assert_eq!(new_history.len(), new_point_events.len());
 
-        MatchWithHistory {
+        Self {
             current: previous_state,
             history: new_history,
             point_events: new_point_events,
         }
     }
 
-    pub fn current(&self) -> &MatchState {
+    pub fn current(&self) -> &S {
         &self.current
     }
 
@@ -75,12 +322,553 @@ impl MatchWithHistory {
     pub fn point_events(&self) -> &[(Player, SystemTime)] {
         &self.point_events
     }
+
+    /// Timestamp of the most recently recorded point, if any.
+    pub fn last_timestamp(&self) -> Option<SystemTime> {
+        self.point_events.last().map(|(_, timestamp)| *timestamp)
+    }
+
+    /// The state before the first recorded point.
+    pub fn initial_state(&self) -> &S {
+        self.history.first().unwrap_or(&self.current)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct MatchWithHistory {
+    recording: ScoreHistory<MatchState>,
+    rules: Arc<dyn ScoringRules>,
+    /// Indices into the point events of points claimed as bisques.
+    bisques: Vec<usize>,
+    time_up: Option<TimeUp>,
+}
+
+/// Time called on a timed match.
+#[derive(Debug, Clone)]
+struct TimeUp {
+    at: SystemTime,
+    /// Points recorded before time was called; later ones are played in the
+    /// sudden-death point or tiebreak.
+    points_before: usize,
+    /// The finished match, once it is decided.
+    result: Option<MatchState>,
+}
+
+impl MatchWithHistory {
+    pub fn new(state: MatchState) -> Self {
+        Self::with_rules(state, Arc::new(StandardRules))
+    }
+
+    /// Track a match scored under a format other than standard tennis.
+    pub fn with_rules(state: MatchState, rules: Arc<dyn ScoringRules>) -> Self {
+        Self {
+            recording: ScoreHistory::new(state),
+            rules,
+            bisques: Vec::new(),
+            time_up: None,
+        }
+    }
+
+    /// Rebuild a recording by replaying timestamped points from `start`.
+    pub fn from_events(
+        start: MatchState,
+        events: &[(Player, SystemTime)],
+    ) -> Result<MatchWithHistory, TimestampError> {
+        Self::from_events_with(start, events, Arc::new(StandardRules))
+    }
+
+    /// As [`from_events`](Self::from_events), for a match scored under `rules`.
+    pub fn from_events_with(
+        start: MatchState,
+        events: &[(Player, SystemTime)],
+        rules: Arc<dyn ScoringRules>,
+    ) -> Result<MatchWithHistory, TimestampError> {
+        events.iter().try_fold(
+            MatchWithHistory::with_rules(start, rules),
+            |mwh, &(scorer, timestamp)| mwh.score_point_at(scorer, timestamp),
+        )
+    }
+
+    pub fn score_point(&self, scorer: Player) -> MatchWithHistory {
+        self.score_point_with_clock(scorer, &SystemClock)
+    }
+
+    /// Score a point stamped with the clock's current time.
+    ///
+    /// A clock reading earlier than the last recorded point (e.g. the wall
+    /// clock was adjusted) is clamped to that point's timestamp.
+    pub fn score_point_with_clock(&self, scorer: Player, clock: &dyn Clock) -> MatchWithHistory {
+        let timestamp = self.recording.clamp(clock.now());
+        self.record_point(scorer, timestamp)
+    }
+
+    /// Score a point that happened at an explicit time, e.g. when
+    /// back-filling a match or flushing points queued by the UI.
+    ///
+    /// Timestamps must be non-decreasing across the recorded points.
+    pub fn score_point_at(
+        &self,
+        scorer: Player,
+        timestamp: SystemTime,
+    ) -> Result<MatchWithHistory, TimestampError> {
+        self.recording.check_timestamp(timestamp)?;
+        Ok(self.record_point(scorer, timestamp))
+    }
+
+    fn record_point(&self, scorer: Player, timestamp: SystemTime) -> MatchWithHistory {
+        let Some(time_up) = &self.time_up else {
+            let rules = self.rules.as_ref();
+            return MatchWithHistory {
+                recording: self.recording.record(scorer, timestamp, |state| {
+                    state.score_point_with(scorer, rules)
+                }),
+                rules: Arc::clone(&self.rules),
+                bisques: self.bisques.clone(),
+                time_up: None,
+            };
+        };
+        if time_up.result.is_some() {
+            return self.clone();
+        }
+
+        // Overtime points are recorded without changing the score at time
+        let mut next = self.clone();
+        next.recording = self.recording.record(scorer, timestamp, Clone::clone);
+        next.time_up = Some(TimeUp {
+            result: next.overtime_result(time_up.points_before),
+            ..time_up.clone()
+        });
+        next
+    }
+
+    /// Award `player` a free point from their handicap bisques, stamped with
+    /// the current time. It is scored like a point won, can be undone like
+    /// one, and is listed in [`bisque_points`](Self::bisque_points).
+    pub fn claim_bisque(&self, player: Player) -> Result<MatchWithHistory, BisqueError> {
+        self.claim_bisque_with_clock(player, &SystemClock)
+    }
+
+    pub fn claim_bisque_with_clock(
+        &self,
+        player: Player,
+        clock: &dyn Clock,
+    ) -> Result<MatchWithHistory, BisqueError> {
+        if self.current().winner().is_some() {
+            return Err(BisqueError::MatchOver);
+        }
+        if self.bisques_left(player) == 0 {
+            return Err(BisqueError::NoneRemaining(player));
+        }
+
+        let timestamp = self.recording.clamp(clock.now());
+        let mut claimed = self.record_point(player, timestamp);
+        claimed.bisques.push(self.history_len());
+        Ok(claimed)
+    }
+
+    /// Bisques `player` may still claim.
+    pub fn bisques_left(&self, player: Player) -> u8 {
+        let MatchState::Playing { config, .. } = self.current() else {
+            return 0;
+        };
+        let events = self.point_events();
+        let claimed = self
+            .bisques
+            .iter()
+            .filter(|&&i| events[i].0 == player)
+            .count();
+        config
+            .handicap
+            .bisques(player)
+            .saturating_sub(claimed as u8)
+    }
+
+    /// Indices into [`point_events`](Self::point_events) of points claimed
+    /// as bisques rather than played.
+    pub fn bisque_points(&self) -> &[usize] {
+        &self.bisques
+    }
+
+    pub fn undo(&self) -> MatchWithHistory {
+        // Calling time is undone once every overtime point has been
+        if let Some(time_up) = &self.time_up
+            && self.history_len() == time_up.points_before
+        {
+            return MatchWithHistory {
+                time_up: None,
+                ..self.clone()
+            };
+        }
+
+        let recording = self.recording.undo();
+        let mut bisques = self.bisques.clone();
+        if bisques.last() == Some(&recording.history_len()) {
+            bisques.pop();
+        }
+        let time_up = self.time_up.as_ref().map(|time_up| TimeUp {
+            result: None,
+            ..time_up.clone()
+        });
+        MatchWithHistory {
+            recording,
+            rules: Arc::clone(&self.rules),
+            bisques,
+            time_up,
+        }
+    }
+
+    /// Call time on a timed match, at the clock's current time.
+    ///
+    /// The leader under the config's `TimeLimit` wins at once. If the score
+    /// is level the match goes on to the configured sudden-death point or
+    /// tiebreak: further points are recorded as usual but leave
+    /// [`current`](Self::current) at the score when time was called until
+    /// the decider is won (see [`overtime_score`](Self::overtime_score)).
+    /// Undo steps back through the decider and then reopens the match.
+    pub fn end_on_time(&self) -> Result<MatchWithHistory, EndOnTimeError> {
+        self.end_on_time_with_clock(&SystemClock)
+    }
+
+    pub fn end_on_time_with_clock(
+        &self,
+        clock: &dyn Clock,
+    ) -> Result<MatchWithHistory, EndOnTimeError> {
+        self.end_on_time_at(clock.now())
+    }
+
+    /// Call time at an explicit time, e.g. when rebuilding a stored match
+    /// from its [`time_called`](Self::time_called) marker. A time earlier
+    /// than the last recorded point is clamped to it.
+    pub fn end_on_time_at(&self, at: SystemTime) -> Result<MatchWithHistory, EndOnTimeError> {
+        if self.time_up.is_some() || self.current().winner().is_some() {
+            return Err(EndOnTimeError::AlreadyEnded);
+        }
+        let state = self.current();
+        let time_limit = match state {
+            MatchState::Playing { config, .. } => config.time_limit,
+            MatchState::Completed { .. } => None,
+        }
+        .ok_or(EndOnTimeError::NoTimeLimit)?;
+
+        let result = time_leader(state, time_limit.leader).map(|winner| completed(state, winner));
+        Ok(MatchWithHistory {
+            time_up: Some(TimeUp {
+                at: self.recording.clamp(at),
+                points_before: self.history_len(),
+                result,
+            }),
+            ..self.clone()
+        })
+    }
+
+    /// When time was called, if it has been.
+    pub fn time_called(&self) -> Option<TimeCalled> {
+        self.time_up.as_ref().map(|time_up| TimeCalled {
+            at: time_up.at,
+            points_before: time_up.points_before,
+        })
+    }
+
+    /// Points each side has won in the sudden-death point or tiebreak
+    /// played after time was called on a level score.
+    pub fn overtime_score(&self) -> Option<(u8, u8)> {
+        let time_up = self.time_up.as_ref()?;
+        let points = &self.point_events()[time_up.points_before..];
+        if time_up.result.is_some() && points.is_empty() {
+            return None;
+        }
+        let won = |player| points.iter().filter(|(p, _)| *p == player).count() as u8;
+        Some((won(Player::Player1), won(Player::Player2)))
+    }
+
+    fn overtime_result(&self, points_before: usize) -> Option<MatchState> {
+        let state = self.recording.current();
+        let MatchState::Playing { config, .. } = state else {
+            return None;
+        };
+        let decider = config.time_limit?.decider;
+        let points = &self.point_events()[points_before..];
+        // The decider settles the match, so it is won as a deciding-set
+        // tiebreak under these rules
+        let set = SetContext {
+            config,
+            is_final_set: true,
+            kind: SetKind::Regular,
+        };
+        let winner = match decider {
+            TimedDecider::SuddenDeathPoint => points.first().map(|(p, _)| *p),
+            TimedDecider::Tiebreak { points: target } => points
+                .iter()
+                .fold(TiebreakState::new(target), |tb, (p, _)| {
+                    tb.score_point_with(*p, self.rules(), &set)
+                })
+                .winner(),
+        }?;
+        Some(completed(state, winner))
+    }
+
+    /// Time left before a timed match's slot ends, counted from the first
+    /// recorded point. `None` without a time limit or before the first point.
+    pub fn time_left_with_clock(&self, clock: &dyn Clock) -> Option<Duration> {
+        let MatchState::Playing { config, .. } = self.initial_state() else {
+            return None;
+        };
+        let limit = config.time_limit?.duration();
+        let started = self.point_events().first()?.1;
+        let elapsed = clock.now().duration_since(started).unwrap_or_default();
+        Some(limit.saturating_sub(elapsed))
+    }
+
+    /// Who won, why, and when, once the match is over.
+    pub fn completion(&self) -> Option<Completion> {
+        let winner = self.current().winner()?;
+        match &self.time_up {
+            Some(time_up) => Some(Completion {
+                winner,
+                reason: CompletionReason::TimeLimit,
+                ended_at: if self.history_len() > time_up.points_before {
+                    self.last_timestamp()?
+                } else {
+                    time_up.at
+                },
+            }),
+            None => Some(Completion {
+                winner,
+                reason: CompletionReason::Played,
+                ended_at: self.last_timestamp()?,
+            }),
+        }
+    }
+
+    pub fn current(&self) -> &MatchState {
+        self.time_up
+            .as_ref()
+            .and_then(|time_up| time_up.result.as_ref())
+            .unwrap_or(self.recording.current())
+    }
+
+    pub fn history_len(&self) -> usize {
+        self.recording.history_len()
+    }
+
+    pub fn can_undo(&self) -> bool {
+        self.recording.can_undo() || self.time_up.is_some()
+    }
+
+    pub fn point_events(&self) -> &[(Player, SystemTime)] {
+        self.recording.point_events()
+    }
+
+    /// Timestamp of the most recently recorded point, if any.
+    pub fn last_timestamp(&self) -> Option<SystemTime> {
+        self.recording.last_timestamp()
+    }
+
+    /// The rules points are scored under.
+    pub fn rules(&self) -> &dyn ScoringRules {
+        self.rules.as_ref()
+    }
+
+    /// The state before the first recorded point.
+    pub fn initial_state(&self) -> &MatchState {
+        self.recording.initial_state()
+    }
+
+    /// Join a continuation recorded from the score this recording reached,
+    /// e.g. a match finished on the phone after the watch battery died.
+    ///
+    /// The continuation must be scored under the same `ScoringRules` and
+    /// config (its serve order may differ, as `MatchState::from_score`
+    /// fills one in), start from the same sets, games, points and server,
+    /// and have no point earlier than this recording's last. Its points are
+    /// replayed onto this recording, bisques as bisques, and the replay must
+    /// end at the continuation's own score.
+    ///
+    /// Rules are compared by their `Debug` output, which for the rules in
+    /// this crate names the type and every setting.
+    pub fn stitch(&self, continuation: &MatchWithHistory) -> Result<MatchWithHistory, StitchError> {
+        if format!("{:?}", self.rules()) != format!("{:?}", continuation.rules()) {
+            return Err(StitchError::RulesMismatch);
+        }
+        let start = continuation.initial_state();
+        if let (MatchState::Playing { config: a, .. }, MatchState::Playing { config: b, .. }) =
+            (self.current(), start)
+            && !same_rules(a, b)
+        {
+            return Err(StitchError::ConfigMismatch);
+        }
+        if !same_position(self.current(), start) {
+            return Err(StitchError::ScoreMismatch {
+                recorded: self.current().to_string(),
+                continuation: start.to_string(),
+            });
+        }
+
+        let time_called = continuation.time_called();
+        let mut merged = self.clone();
+        for (i, &(scorer, timestamp)) in continuation.point_events().iter().enumerate() {
+            if let Some(called) = time_called
+                && called.points_before == i
+            {
+                merged = merged.end_on_time_at(called.at)?;
+            }
+            merged = if continuation.bisques.contains(&i) {
+                merged.recording.check_timestamp(timestamp)?;
+                merged.claim_bisque_with_clock(scorer, &|| timestamp)?
+            } else {
+                merged.score_point_at(scorer, timestamp)?
+            };
+        }
+        if let Some(called) = time_called
+            && called.points_before == continuation.history_len()
+        {
+            merged = merged.end_on_time_at(called.at)?;
+        }
+        if !same_position(merged.current(), continuation.current()) {
+            return Err(StitchError::ReplayMismatch {
+                merged: merged.current().to_string(),
+                continuation: continuation.current().to_string(),
+            });
+        }
+        Ok(merged)
+    }
+}
+
+/// The leader when time is called, ignoring points in the current game.
+fn time_leader(state: &MatchState, leader: TimedLeader) -> Option<Player> {
+    let MatchState::Playing {
+        sets,
+        player1_sets,
+        player2_sets,
+        ..
+    } = state
+    else {
+        return state.winner();
+    };
+    let games = |set: &SetState| match set {
+        SetState::Playing {
+            player1_games,
+            player2_games,
+            ..
+        }
+        | SetState::Completed {
+            player1_games,
+            player2_games,
+            ..
+        } => (u32::from(*player1_games), u32::from(*player2_games)),
+    };
+    let (player1, player2) = match leader {
+        TimedLeader::Games => sets
+            .iter()
+            .map(games)
+            .fold((0, 0), |(a, b), (c, d)| (a + c, b + d)),
+        TimedLeader::SetsThenGames if player1_sets != player2_sets => {
+            (u32::from(*player1_sets), u32::from(*player2_sets))
+        }
+        TimedLeader::SetsThenGames => sets.last().map(games).unwrap_or_default(),
+    };
+    match player1.cmp(&player2) {
+        std::cmp::Ordering::Greater => Some(Player::Player1),
+        std::cmp::Ordering::Less => Some(Player::Player2),
+        std::cmp::Ordering::Equal => None,
+    }
+}
+
+/// `state` ended early with `winner`; the last set is left as it stood.
+fn completed(state: &MatchState, winner: Player) -> MatchState {
+    match state {
+        MatchState::Playing {
+            sets,
+            player1_sets,
+            player2_sets,
+            config,
+            ..
+        } => MatchState::Completed {
+            winner,
+            player1_sets: *player1_sets,
+            player2_sets: *player2_sets,
+            sets: sets.clone(),
+            config: config.clone(),
+        },
+        MatchState::Completed { .. } => state.clone(),
+    }
+}
+
+fn same_rules(a: &MatchConfig, b: &MatchConfig) -> bool {
+    MatchConfig {
+        serve_order: Vec::new(),
+        ..a.clone()
+    } == MatchConfig {
+        serve_order: Vec::new(),
+        ..b.clone()
+    }
+}
+
+/// Whether two states show the same score and server, ignoring how they
+/// were reached (deuce counts, tiebreak scores of finished sets, serve
+/// rotation bookkeeping).
+fn same_position(a: &MatchState, b: &MatchState) -> bool {
+    match (a, b) {
+        (MatchState::Playing { sets: a_sets, .. }, MatchState::Playing { sets: b_sets, .. }) => {
+            same_sets(a_sets, b_sets) && server(a) == server(b)
+        }
+        (
+            MatchState::Completed {
+                winner: a_winner,
+                sets: a_sets,
+                ..
+            },
+            MatchState::Completed {
+                winner: b_winner,
+                sets: b_sets,
+                ..
+            },
+        ) => a_winner == b_winner && same_sets(a_sets, b_sets),
+        _ => false,
+    }
+}
+
+fn same_sets(a: &[SetState], b: &[SetState]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).all(|(a, b)| set_key(a) == set_key(b))
+}
+
+fn set_key(set: &SetState) -> (u8, u8, Option<String>) {
+    match set {
+        SetState::Playing {
+            player1_games,
+            player2_games,
+            current_game,
+            tiebreak,
+        } => {
+            let points = match tiebreak {
+                Some(tb) => tb.to_string(),
+                None => current_game.to_string(),
+            };
+            (*player1_games, *player2_games, Some(points))
+        }
+        SetState::Completed {
+            player1_games,
+            player2_games,
+            ..
+        } => (*player1_games, *player2_games, None),
+    }
+}
+
+/// The serving player and their position in the serve order.
+fn server(state: &MatchState) -> Option<(Player, u8)> {
+    match state {
+        MatchState::Playing { config, .. } if !config.serve_order.is_empty() => {
+            Some(config.serve_order[state.current_server() as usize])
+        }
+        _ => state.serving_player().map(|player| (player, 0)),
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::config::MatchConfig;
+    use crate::config::TiebreakRule;
+    use crate::starting_score::StartingScore;
     use std::time::Duration;
 
     #[test]
@@ -145,6 +933,7 @@ mod tests {
             player1_sets: 2,
             player2_sets: 0,
             sets: vec![],
+            config: MatchConfig::default(),
         };
         let mwh = MatchWithHistory::new(state);
         let initial_len = mwh.history_len();
@@ -200,17 +989,20 @@ mod tests {
         assert_eq!(mwh.point_events().len(), 0);
     }
 
+    fn ts(secs: u64) -> SystemTime {
+        SystemTime::UNIX_EPOCH + Duration::from_secs(1_000_000 + secs)
+    }
+
     #[test]
     fn test_rescore_after_undo_gets_new_timestamp() {
         let state = MatchState::new(MatchConfig::default());
         let mwh = MatchWithHistory::new(state);
 
-        let mwh = mwh.score_point(Player::Player1);
+        let mwh = mwh.score_point_with_clock(Player::Player1, &|| ts(0));
         let original_timestamp = mwh.point_events()[0].1;
 
         let mwh = mwh.undo();
-        std::thread::sleep(Duration::from_millis(1));
-        let mwh = mwh.score_point(Player::Player2);
+        let mwh = mwh.score_point_with_clock(Player::Player2, &|| ts(5));
 
         let new_timestamp = mwh.point_events()[0].1;
         assert!(new_timestamp > original_timestamp);
@@ -224,10 +1016,447 @@ mod tests {
             player1_sets: 2,
             player2_sets: 0,
             sets: vec![],
+            config: MatchConfig::default(),
         };
         let mwh = MatchWithHistory::new(state);
 
         let mwh = mwh.score_point(Player::Player2);
         assert_eq!(mwh.point_events().len(), 0);
     }
+
+    #[test]
+    fn test_score_point_at_records_given_timestamp() {
+        let mwh = MatchWithHistory::new(MatchState::new(MatchConfig::default()));
+
+        let mwh = mwh.score_point_at(Player::Player1, ts(10)).unwrap();
+        let mwh = mwh.score_point_at(Player::Player2, ts(40)).unwrap();
+
+        assert_eq!(
+            mwh.point_events(),
+            &[(Player::Player1, ts(10)), (Player::Player2, ts(40))]
+        );
+        assert_eq!(mwh.last_timestamp(), Some(ts(40)));
+    }
+
+    #[test]
+    fn test_score_point_at_allows_equal_timestamps() {
+        let mwh = MatchWithHistory::new(MatchState::new(MatchConfig::default()));
+
+        let mwh = mwh.score_point_at(Player::Player1, ts(10)).unwrap();
+        let mwh = mwh.score_point_at(Player::Player1, ts(10)).unwrap();
+        assert_eq!(mwh.history_len(), 2);
+    }
+
+    #[test]
+    fn test_score_point_at_rejects_earlier_timestamp() {
+        let mwh = MatchWithHistory::new(MatchState::new(MatchConfig::default()));
+        let mwh = mwh.score_point_at(Player::Player1, ts(30)).unwrap();
+
+        let err = mwh.score_point_at(Player::Player2, ts(20)).unwrap_err();
+        assert_eq!(
+            err,
+            TimestampError::NotMonotonic {
+                previous: ts(30),
+                given: ts(20),
+            }
+        );
+        assert_eq!(mwh.history_len(), 1);
+    }
+
+    #[test]
+    fn test_score_point_at_after_undo_checks_remaining_points() {
+        let mwh = MatchWithHistory::new(MatchState::new(MatchConfig::default()));
+        let mwh = mwh.score_point_at(Player::Player1, ts(10)).unwrap();
+        let mwh = mwh.score_point_at(Player::Player1, ts(50)).unwrap();
+
+        // Once the later point is undone, an earlier correction is valid
+        let mwh = mwh.undo();
+        let mwh = mwh.score_point_at(Player::Player2, ts(20)).unwrap();
+        assert_eq!(mwh.point_events()[1], (Player::Player2, ts(20)));
+    }
+
+    #[test]
+    fn test_clock_going_backwards_is_clamped() {
+        let mwh = MatchWithHistory::new(MatchState::new(MatchConfig::default()));
+        let mwh = mwh.score_point_with_clock(Player::Player1, &|| ts(100));
+        let mwh = mwh.score_point_with_clock(Player::Player2, &|| ts(90));
+
+        assert_eq!(mwh.point_events()[1], (Player::Player2, ts(100)));
+    }
+
+    /// Partial recording: Player1 holds, then Player2 leads 0-30.
+    fn watch_recording() -> MatchWithHistory {
+        let events: Vec<(Player, SystemTime)> = [Player::Player1; 4]
+            .into_iter()
+            .chain([Player::Player2; 2])
+            .enumerate()
+            .map(|(i, p)| (p, ts(i as u64 * 30)))
+            .collect();
+        MatchWithHistory::from_events(MatchState::new(MatchConfig::default()), &events).unwrap()
+    }
+
+    /// Continuation scored from the recording's final position.
+    fn phone_recording(
+        recorded: &MatchWithHistory,
+        config: MatchConfig,
+        events: &[(Player, SystemTime)],
+    ) -> MatchWithHistory {
+        let score = StartingScore::from_state(recorded.current()).unwrap();
+        let start = MatchState::from_score(config, &score).unwrap();
+        MatchWithHistory::from_events(start, events).unwrap()
+    }
+
+    #[test]
+    fn test_from_events_replays_points() {
+        let mwh = watch_recording();
+        assert_eq!(mwh.history_len(), 6);
+        assert_eq!(mwh.last_timestamp(), Some(ts(150)));
+        assert_eq!(mwh.current().to_string(), "1-0 0-30*");
+        assert_eq!(
+            mwh.initial_state(),
+            &MatchState::new(MatchConfig::default())
+        );
+    }
+
+    #[test]
+    fn test_stitch_merges_timelines() {
+        let watch = watch_recording();
+        let phone = phone_recording(
+            &watch,
+            MatchConfig::default(),
+            &[(Player::Player2, ts(600)), (Player::Player2, ts(630))],
+        );
+
+        let merged = watch.stitch(&phone).unwrap();
+        assert_eq!(merged.history_len(), 8);
+        assert_eq!(merged.initial_state(), watch.initial_state());
+        assert_eq!(merged.current().to_string(), "1-1 *0-0");
+        assert_eq!(merged.point_events()[6], (Player::Player2, ts(600)));
+
+        // The merged timeline undoes across the join
+        assert_eq!(merged.undo().undo().current(), watch.current());
+    }
+
+    #[test]
+    fn test_stitch_rejects_wrong_starting_score() {
+        let watch = watch_recording();
+        let phone = MatchWithHistory::new(MatchState::new(MatchConfig::default()))
+            .score_point_at(Player::Player1, ts(600))
+            .unwrap();
+
+        let err = watch.stitch(&phone).unwrap_err();
+        assert_eq!(
+            err,
+            StitchError::ScoreMismatch {
+                recorded: "1-0 0-30*".to_string(),
+                continuation: "0-0 *0-0".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_stitch_rejects_different_rules() {
+        let watch = watch_recording();
+        let config = MatchConfig {
+            no_ad_scoring: true,
+            ..MatchConfig::default()
+        };
+        let phone = phone_recording(&watch, config, &[]);
+
+        assert_eq!(
+            watch.stitch(&phone).unwrap_err(),
+            StitchError::ConfigMismatch
+        );
+    }
+
+    #[test]
+    fn test_stitch_rejects_overlapping_timestamps() {
+        let watch = watch_recording();
+        let phone = phone_recording(
+            &watch,
+            MatchConfig::default(),
+            &[(Player::Player1, ts(100))],
+        );
+
+        assert_eq!(
+            watch.stitch(&phone).unwrap_err(),
+            StitchError::Timestamp(TimestampError::NotMonotonic {
+                previous: ts(150),
+                given: ts(100),
+            })
+        );
+    }
+
+    #[test]
+    fn test_stitch_rejects_different_scoring_rules() {
+        let watch = watch_recording();
+        let score = StartingScore::from_state(watch.current()).unwrap();
+        let phone = MatchWithHistory::with_rules(
+            MatchState::from_score(MatchConfig::default(), &score).unwrap(),
+            Arc::new(crate::rules::CumulativeGamesRules::team_tennis()),
+        );
+
+        assert_eq!(
+            watch.stitch(&phone).unwrap_err(),
+            StitchError::RulesMismatch
+        );
+    }
+
+    #[test]
+    fn test_stitch_replays_bisques_as_bisques() {
+        let watch = MatchWithHistory::new(MatchState::new(handicap_config()))
+            .score_point_at(Player::Player1, ts(0))
+            .unwrap()
+            .claim_bisque_with_clock(Player::Player2, &|| ts(30))
+            .unwrap();
+        let phone = phone_recording(&watch, handicap_config(), &[])
+            .claim_bisque_with_clock(Player::Player2, &|| ts(600))
+            .unwrap()
+            .score_point_at(Player::Player1, ts(630))
+            .unwrap();
+
+        let merged = watch.stitch(&phone).unwrap();
+        assert_eq!(merged.bisque_points(), [1, 2]);
+        assert_eq!(merged.bisques_left(Player::Player2), 0);
+        assert_eq!(merged.current().to_string(), "0-0 *30-30");
+
+        // Each recording granted both bisques, but the match has only two
+        let greedy = phone_recording(&merged, handicap_config(), &[])
+            .claim_bisque_with_clock(Player::Player2, &|| ts(700))
+            .unwrap();
+        assert_eq!(
+            merged.stitch(&greedy).unwrap_err(),
+            StitchError::Bisque(BisqueError::NoneRemaining(Player::Player2))
+        );
+    }
+
+    fn handicap_config() -> MatchConfig {
+        MatchConfig {
+            handicap: crate::config::Handicap {
+                player2_bisques: 2,
+                ..Default::default()
+            },
+            ..MatchConfig::default()
+        }
+    }
+
+    #[test]
+    fn test_claim_bisque_scores_a_point() {
+        let mwh = MatchWithHistory::new(MatchState::new(handicap_config()));
+        let mwh = mwh.score_point(Player::Player1);
+        let mwh = mwh.claim_bisque(Player::Player2).unwrap();
+
+        assert_eq!(mwh.current().to_string(), "0-0 *15-15");
+        assert_eq!(mwh.point_events().len(), 2);
+        assert_eq!(mwh.bisque_points(), &[1]);
+        assert_eq!(mwh.bisques_left(Player::Player2), 1);
+    }
+
+    #[test]
+    fn test_claim_bisque_limited_by_handicap() {
+        let mwh = MatchWithHistory::new(MatchState::new(handicap_config()));
+        assert_eq!(
+            mwh.claim_bisque(Player::Player1).unwrap_err(),
+            BisqueError::NoneRemaining(Player::Player1)
+        );
+        let mwh = mwh
+            .claim_bisque(Player::Player2)
+            .unwrap()
+            .claim_bisque(Player::Player2)
+            .unwrap();
+        assert_eq!(
+            mwh.claim_bisque(Player::Player2).unwrap_err(),
+            BisqueError::NoneRemaining(Player::Player2)
+        );
+    }
+
+    #[test]
+    fn test_undo_bisque_returns_it() {
+        let mwh = MatchWithHistory::new(MatchState::new(handicap_config()))
+            .claim_bisque(Player::Player2)
+            .unwrap()
+            .score_point(Player::Player1);
+
+        // Undoing a played point keeps the bisque
+        let mwh = mwh.undo();
+        assert_eq!(mwh.bisque_points(), &[0]);
+
+        let mwh = mwh.undo();
+        assert!(mwh.bisque_points().is_empty());
+        assert_eq!(mwh.bisques_left(Player::Player2), 2);
+        assert_eq!(mwh.history_len(), 0);
+    }
+
+    fn timed_config(leader: TimedLeader, decider: TimedDecider) -> MatchConfig {
+        MatchConfig {
+            time_limit: Some(crate::config::TimeLimit {
+                minutes: 45,
+                leader,
+                decider,
+            }),
+            ..MatchConfig::default()
+        }
+    }
+
+    fn play_games(mwh: MatchWithHistory, winners: &[Player]) -> MatchWithHistory {
+        winners.iter().fold(mwh, |mwh, &winner| {
+            (0..4).fold(mwh, |mwh, _| mwh.score_point_with_clock(winner, &|| ts(0)))
+        })
+    }
+
+    #[test]
+    fn test_end_on_time_leader_wins() {
+        let config = timed_config(TimedLeader::Games, TimedDecider::SuddenDeathPoint);
+        let mwh = MatchWithHistory::new(MatchState::new(config));
+        let mwh = play_games(mwh, &[Player::Player1, Player::Player2, Player::Player2]);
+        let mwh = mwh.score_point_with_clock(Player::Player1, &|| ts(0));
+
+        let ended = mwh.end_on_time_with_clock(&|| ts(2700)).unwrap();
+        assert_eq!(ended.current().winner(), Some(Player::Player2));
+        assert_eq!(
+            ended.completion(),
+            Some(Completion {
+                winner: Player::Player2,
+                reason: CompletionReason::TimeLimit,
+                ended_at: ts(2700),
+            })
+        );
+        // Points after the result are ignored
+        assert_eq!(ended.score_point(Player::Player1).history_len(), 13);
+
+        let reopened = ended.undo();
+        assert_eq!(reopened.current().winner(), None);
+        assert_eq!(reopened.history_len(), 13);
+    }
+
+    #[test]
+    fn test_end_on_time_sudden_death() {
+        let config = timed_config(TimedLeader::Games, TimedDecider::SuddenDeathPoint);
+        let mwh = MatchWithHistory::new(MatchState::new(config));
+        let mwh = play_games(mwh, &[Player::Player1, Player::Player2]);
+
+        let overtime = mwh.end_on_time().unwrap();
+        assert_eq!(overtime.current().winner(), None);
+        assert_eq!(overtime.overtime_score(), Some((0, 0)));
+        assert_eq!(
+            overtime.end_on_time().unwrap_err(),
+            EndOnTimeError::AlreadyEnded
+        );
+
+        let decided = overtime.score_point(Player::Player1);
+        assert_eq!(decided.current().winner(), Some(Player::Player1));
+        assert_eq!(
+            decided.completion().map(|c| c.reason),
+            Some(CompletionReason::TimeLimit)
+        );
+        assert_eq!(decided.point_events().len(), 9);
+
+        let undone = decided.undo();
+        assert_eq!(undone.current().winner(), None);
+        assert_eq!(undone.overtime_score(), Some((0, 0)));
+        assert_eq!(undone.undo().overtime_score(), None);
+    }
+
+    #[test]
+    fn test_end_on_time_tiebreak_decider() {
+        let config = timed_config(
+            TimedLeader::SetsThenGames,
+            TimedDecider::Tiebreak { points: 7 },
+        );
+        let mwh = MatchWithHistory::new(MatchState::new(config));
+        let mwh = play_games(mwh, &[Player::Player2; 6]);
+        let mwh = play_games(mwh, &[Player::Player1; 6]);
+        let mwh = play_games(mwh, &[Player::Player1, Player::Player2]);
+
+        let mut mwh = mwh.end_on_time().unwrap();
+        for _ in 0..6 {
+            mwh = mwh.score_point(Player::Player2);
+        }
+        assert_eq!(mwh.overtime_score(), Some((0, 6)));
+        assert_eq!(mwh.current().winner(), None);
+        let mwh = mwh.score_point(Player::Player2);
+        assert_eq!(mwh.current().winner(), Some(Player::Player2));
+    }
+
+    #[test]
+    fn test_end_on_time_sudden_death_tiebreak_decider() {
+        let config = MatchConfig {
+            tiebreak_rule: TiebreakRule {
+                win_by: 1,
+                cap: None,
+            },
+            ..timed_config(TimedLeader::Games, TimedDecider::Tiebreak { points: 7 })
+        };
+        let mwh = MatchWithHistory::new(MatchState::new(config));
+        let mwh = play_games(mwh, &[Player::Player1, Player::Player2]);
+
+        let mut mwh = mwh.end_on_time().unwrap();
+        for _ in 0..6 {
+            mwh = mwh.score_point(Player::Player1);
+            mwh = mwh.score_point(Player::Player2);
+        }
+        assert_eq!(mwh.overtime_score(), Some((6, 6)));
+        // Win by one: the next point takes it at 7-6
+        let mwh = mwh.score_point(Player::Player2);
+        assert_eq!(mwh.current().winner(), Some(Player::Player2));
+    }
+
+    #[test]
+    fn test_stitch_carries_time_called() {
+        let config = timed_config(TimedLeader::Games, TimedDecider::SuddenDeathPoint);
+        let watch = play_games(
+            MatchWithHistory::new(MatchState::new(config.clone())),
+            &[Player::Player1],
+        );
+        let phone = phone_recording(&watch, config, &[]);
+        let phone = play_games(phone, &[Player::Player2])
+            .end_on_time_at(ts(2700))
+            .unwrap()
+            .score_point_at(Player::Player2, ts(2710))
+            .unwrap();
+
+        let merged = watch.stitch(&phone).unwrap();
+        assert_eq!(
+            merged.time_called(),
+            Some(TimeCalled {
+                at: ts(2700),
+                points_before: 8,
+            })
+        );
+        assert_eq!(merged.current().winner(), Some(Player::Player2));
+        assert_eq!(merged.history_len(), 9);
+    }
+
+    #[test]
+    fn test_end_on_time_needs_time_limit() {
+        let mwh = MatchWithHistory::new(MatchState::new(MatchConfig::default()));
+        assert_eq!(mwh.end_on_time().unwrap_err(), EndOnTimeError::NoTimeLimit);
+    }
+
+    #[test]
+    fn test_time_left_and_played_completion() {
+        let config = timed_config(TimedLeader::Games, TimedDecider::SuddenDeathPoint);
+        let mwh = MatchWithHistory::new(MatchState::new(config))
+            .score_point_at(Player::Player1, ts(0))
+            .unwrap();
+        assert_eq!(
+            mwh.time_left_with_clock(&|| ts(600)),
+            Some(Duration::from_secs(2100))
+        );
+        assert_eq!(mwh.time_left_with_clock(&|| ts(3000)), Some(Duration::ZERO));
+        assert_eq!(mwh.completion(), None);
+
+        let mut played = MatchWithHistory::new(MatchState::new(MatchConfig::default()));
+        for i in 0..48 {
+            played = played.score_point_at(Player::Player1, ts(i)).unwrap();
+        }
+        assert_eq!(
+            played.completion(),
+            Some(Completion {
+                winner: Player::Player1,
+                reason: CompletionReason::Played,
+                ended_at: ts(47),
+            })
+        );
+    }
 }
@@ -0,0 +1,90 @@
+use std::fmt;
+
+use crate::game::GameState;
+use crate::tiebreak::TiebreakState;
+
+/// A structural invariant broken by a hand-built or deserialised state.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InvariantError {
+    /// The config cannot describe a match (e.g. zero sets to win).
+    InvalidConfig,
+    /// A game score the game state machine never produces, or a finished
+    /// game left as the current game.
+    InvalidGame(GameState),
+    /// A tiebreak that is already decided, played to the wrong target, or
+    /// otherwise unreachable.
+    InvalidTiebreak(TiebreakState),
+    /// An unfinished set whose games already decide it.
+    SetAlreadyDecided {
+        player1_games: u8,
+        player2_games: u8,
+    },
+    /// A completed set whose final score cannot occur.
+    InvalidSetResult {
+        player1_games: u8,
+        player2_games: u8,
+    },
+    /// A set is in a tiebreak at a score where none is played, or is missing
+    /// one where it should be.
+    TiebreakMismatch {
+        player1_games: u8,
+        player2_games: u8,
+    },
+    /// An unfinished set before the current one, a finished current set, or
+    /// no sets at all.
+    InvalidSetSequence,
+    /// The set counts disagree with the winners of the completed sets.
+    SetCountMismatch { player1_sets: u8, player2_sets: u8 },
+    /// A match still in play although one side has won enough sets, or a
+    /// completed match whose winner did not win more sets.
+    InvalidMatchResult,
+    /// Serve rotation indices outside the serve order, or tiebreak points
+    /// served that disagree with the tiebreak score.
+    InvalidServeTracking,
+}
+
+impl fmt::Display for InvariantError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InvariantError::InvalidConfig => write!(f, "config cannot describe a match"),
+            InvariantError::InvalidGame(game) => write!(f, "invalid game state {game:?}"),
+            InvariantError::InvalidTiebreak(tb) => write!(f, "invalid tiebreak state {tb:?}"),
+            InvariantError::SetAlreadyDecided {
+                player1_games,
+                player2_games,
+            } => write!(
+                f,
+                "set at {player1_games}-{player2_games} should already be over"
+            ),
+            InvariantError::InvalidSetResult {
+                player1_games,
+                player2_games,
+            } => write!(f, "a set cannot finish {player1_games}-{player2_games}"),
+            InvariantError::TiebreakMismatch {
+                player1_games,
+                player2_games,
+            } => write!(
+                f,
+                "tiebreak state does not match games {player1_games}-{player2_games}"
+            ),
+            InvariantError::InvalidSetSequence => {
+                write!(f, "only the last set may be unfinished")
+            }
+            InvariantError::SetCountMismatch {
+                player1_sets,
+                player2_sets,
+            } => write!(
+                f,
+                "set count {player1_sets}-{player2_sets} does not match the completed sets"
+            ),
+            InvariantError::InvalidMatchResult => {
+                write!(f, "match result does not match the sets won")
+            }
+            InvariantError::InvalidServeTracking => {
+                write!(f, "serve tracking is inconsistent with the serve order")
+            }
+        }
+    }
+}
+
+impl std::error::Error for InvariantError {}
@@ -1,21 +1,49 @@
 pub mod analysis;
+mod clock;
 mod config;
 mod game;
 mod history;
+mod invariant;
 mod match_state;
+mod notation;
+mod pickleball;
+mod rally;
+mod rules;
 mod set;
+mod simulation;
+mod starting_score;
 mod tiebreak;
 mod types;
 
-pub use config::{MatchConfig, MatchType};
+pub use clock::{Clock, SystemClock};
+pub use config::{
+    Handicap, MatchConfig, MatchType, TiebreakRule, TimeLimit, TimedDecider, TimedLeader,
+};
 pub use game::GameState;
-pub use history::MatchWithHistory;
+pub use history::{
+    BisqueError, Completion, CompletionReason, EndOnTimeError, MatchWithHistory, ScoreHistory,
+    Scoring, StitchError, TimeCalled, TimestampError,
+};
+pub use invariant::InvariantError;
 pub use match_state::MatchState;
+pub use notation::{ParseScoreError, ScoreDisplay, ScoreLine, SetLine};
+pub use pickleball::{PickleballConfig, PickleballGame, PickleballState, PickleballWithHistory};
+pub use rally::{RallyConfig, RallyGame, RallyState, RallyWithHistory, ServeRule};
+pub use rules::{
+    CumulativeGamesRules, ScoringRules, SetContext, SetKind, StandardRules, TiebreakOnlyRules,
+};
 pub use set::SetState;
+pub use simulation::{
+    MAX_SIMULATED_GAME_POINTS, MAX_SIMULATED_POINTS, PlayerModel, PointTiming, ScoreLineCount,
+    SimulatedMatch, SimulationConfig, SimulationError, SimulationSummary, simulate_many,
+    simulate_many_with, simulate_match, simulate_match_with,
+};
+pub use starting_score::{StartingPoints, StartingScore, StartingScoreError};
 pub use tiebreak::TiebreakState;
 pub use types::{Player, Point};
 
 #[cfg(test)]
+#[allow(clippy::collapsible_if)]
 mod integration_tests {
     use super::*;
 
@@ -1,5 +1,10 @@
-use crate::config::MatchConfig;
+use crate::config::{MatchConfig, MatchType};
+use crate::game::GameState;
+use crate::invariant::InvariantError;
+use crate::rules::{ScoringRules, SetContext, StandardRules};
 use crate::set::SetState;
+use crate::starting_score::{StartingPoints, StartingScore, StartingScoreError};
+use crate::tiebreak::TiebreakState;
 use crate::types::Player;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -18,13 +23,20 @@ pub enum MatchState {
         player1_sets: u8,
         player2_sets: u8,
         sets: Vec<SetState>,
+        config: MatchConfig,
     },
 }
 
 impl MatchState {
     pub fn new(config: MatchConfig) -> Self {
+        Self::new_with(config, &StandardRules)
+    }
+
+    /// A new match whose first set is laid out by `rules`.
+    pub fn new_with(config: MatchConfig, rules: &dyn ScoringRules) -> Self {
+        let first_set = SetState::new_with(rules, &rules.set_context(&config, &[]));
         MatchState::Playing {
-            sets: vec![SetState::new()],
+            sets: vec![first_set],
             player1_sets: 0,
             player2_sets: 0,
             config,
@@ -34,7 +46,250 @@ impl MatchState {
         }
     }
 
+    /// Build a match already in progress, e.g. second set, 3-2, 30-15 with
+    /// Player2 serving. Positions that cannot be reached under `config` are
+    /// rejected.
+    ///
+    /// In singles the resulting config records an explicit two-entry
+    /// `serve_order` starting with whoever served the first game, so the
+    /// server is tracked the same way as in doubles.
+    pub fn from_score(
+        config: MatchConfig,
+        score: &StartingScore,
+    ) -> Result<MatchState, StartingScoreError> {
+        Self::from_score_with(config, score, &StandardRules)
+    }
+
+    /// As [`from_score`](Self::from_score), checking the position against
+    /// `rules`.
+    pub fn from_score_with(
+        config: MatchConfig,
+        score: &StartingScore,
+        rules: &dyn ScoringRules,
+    ) -> Result<MatchState, StartingScoreError> {
+        let mut sets = Vec::with_capacity(score.completed_sets.len() + 1);
+        let mut player1_sets = 0u8;
+        let mut player2_sets = 0u8;
+        let mut games_played = 0usize;
+
+        for (i, &(p1_games, p2_games)) in score.completed_sets.iter().enumerate() {
+            let set_context = rules.set_context(&config, &sets);
+            let set = Self::play_set_to(rules, &set_context, p1_games, p2_games);
+            let winner = match set {
+                SetState::Completed {
+                    winner,
+                    player1_games,
+                    player2_games,
+                    ..
+                } if player1_games == p1_games && player2_games == p2_games => winner,
+                _ => {
+                    return Err(StartingScoreError::InvalidSetScore {
+                        set_number: i + 1,
+                        player1_games: p1_games,
+                        player2_games: p2_games,
+                    });
+                }
+            };
+            match winner {
+                Player::Player1 => player1_sets += 1,
+                Player::Player2 => player2_sets += 1,
+            }
+            games_played += (p1_games + p2_games) as usize;
+            // Only the games are known, not how a tiebreak finished
+            sets.push(SetState::Completed {
+                winner,
+                player1_games: p1_games,
+                player2_games: p2_games,
+                tiebreak_score: None,
+            });
+            if rules.match_winner(&config, &sets).is_some() {
+                return Err(StartingScoreError::MatchAlreadyDecided);
+            }
+        }
+
+        let (p1_games, p2_games) = score.games;
+        let set_context = rules.set_context(&config, &sets);
+        let in_tiebreak = match Self::play_set_to(rules, &set_context, p1_games, p2_games) {
+            SetState::Playing {
+                player1_games,
+                player2_games,
+                tiebreak,
+                ..
+            } if player1_games == p1_games && player2_games == p2_games => tiebreak.is_some(),
+            _ => {
+                return Err(StartingScoreError::InvalidGameCount {
+                    player1_games: p1_games,
+                    player2_games: p2_games,
+                });
+            }
+        };
+        games_played += (p1_games + p2_games) as usize;
+
+        let (current_game, tiebreak, tiebreak_points_served) = match &score.points {
+            StartingPoints::Game(game) => {
+                if in_tiebreak
+                    || game.winner().is_some()
+                    || rules.validate_game(game, &set_context).is_err()
+                {
+                    return Err(StartingScoreError::InvalidGameScore);
+                }
+                (game.clone(), None, 0u8)
+            }
+            StartingPoints::Tiebreak { player1, player2 } => {
+                let tb = Self::play_tiebreak_to(rules, &set_context, *player1, *player2)
+                    .filter(|_| in_tiebreak)
+                    .ok_or(StartingScoreError::InvalidTiebreakScore {
+                        player1_points: *player1,
+                        player2_points: *player2,
+                    })?;
+                (GameState::new(), Some(tb), player1.saturating_add(*player2))
+            }
+        };
+        sets.push(SetState::Playing {
+            player1_games: p1_games,
+            player2_games: p2_games,
+            current_game,
+            tiebreak,
+        });
+
+        let mut config = config;
+        if config.serve_order.is_empty() {
+            // Whoever serves now also served every game of the same parity
+            let first_server = if games_played.is_multiple_of(2) {
+                score.server
+            } else {
+                score.server.opponent()
+            };
+            config.serve_order = vec![(first_server, 0), (first_server.opponent(), 0)];
+        }
+        let server_index = config
+            .serve_order
+            .iter()
+            .position(|&(player, position)| {
+                player == score.server
+                    && (config.match_type == MatchType::Singles
+                        || position == score.server_position)
+            })
+            .ok_or(StartingScoreError::UnknownServer {
+                player: score.server,
+                position: score.server_position,
+            })?;
+
+        let serve_len = config.serve_order.len();
+        let (serve_rotation_index, tiebreak_serve_index) = if in_tiebreak {
+            let offset = Self::tiebreak_server_offset(tiebreak_points_served) % serve_len;
+            let first_tb_server = (server_index + serve_len - offset) % serve_len;
+            (first_tb_server, first_tb_server)
+        } else {
+            (server_index, 0)
+        };
+
+        Ok(MatchState::Playing {
+            sets,
+            player1_sets,
+            player2_sets,
+            config,
+            serve_rotation_index,
+            tiebreak_serve_index,
+            tiebreak_points_served,
+        })
+    }
+
+    /// Total games won by (player1, player2) across `sets`, tiebreaks
+    /// counting as a game.
+    fn games_in(sets: &[SetState]) -> (u16, u16) {
+        sets.iter().fold((0, 0), |(p1, p2), set| {
+            let (g1, g2) = set.games();
+            (p1 + u16::from(g1), p2 + u16::from(g2))
+        })
+    }
+
+    /// Play a fresh set towards the given games, alternating games until the
+    /// trailer's count is reached so that neither side wins early. The result
+    /// only matches the requested games if that score is reachable.
+    fn play_set_to(
+        rules: &dyn ScoringRules,
+        set_context: &SetContext,
+        p1_games: u8,
+        p2_games: u8,
+    ) -> SetState {
+        let win_game = |set: SetState, player: Player| {
+            let start = Self::set_game_total(&set);
+            let mut set = set;
+            // A tiebreak can run long, but never past 255 points a side
+            for _ in 0..512 {
+                if set.winner().is_some() || Self::set_game_total(&set) > start {
+                    break;
+                }
+                set = set.score_point_with(player, rules, set_context);
+            }
+            set
+        };
+
+        let mut set = SetState::new_with(rules, set_context);
+        for _ in 0..p1_games.min(p2_games) {
+            set = win_game(set, Player::Player1);
+            set = win_game(set, Player::Player2);
+        }
+        let (leader, extra) = if p1_games > p2_games {
+            (Player::Player1, p1_games - p2_games)
+        } else {
+            (Player::Player2, p2_games - p1_games)
+        };
+        for _ in 0..extra {
+            set = win_game(set, leader);
+        }
+        set
+    }
+
+    /// Play a fresh tiebreak to the given points, or `None` if the tiebreak
+    /// would already be over.
+    fn play_tiebreak_to(
+        rules: &dyn ScoringRules,
+        set_context: &SetContext,
+        p1_points: u8,
+        p2_points: u8,
+    ) -> Option<TiebreakState> {
+        let mut tb = TiebreakState::new(rules.tiebreak_target(set_context));
+        let mut play = |player| tb = tb.score_point_with(player, rules, set_context);
+        for _ in 0..p1_points.min(p2_points) {
+            play(Player::Player1);
+            play(Player::Player2);
+        }
+        for _ in 0..p1_points.saturating_sub(p2_points) {
+            play(Player::Player1);
+        }
+        for _ in 0..p2_points.saturating_sub(p1_points) {
+            play(Player::Player2);
+        }
+        match tb {
+            TiebreakState::Playing {
+                player1_points,
+                player2_points,
+                ..
+            } if player1_points == p1_points && player2_points == p2_points => Some(tb),
+            _ => None,
+        }
+    }
+
     pub fn score_point(&self, scorer: Player) -> MatchState {
+        self.score_point_with(scorer, &StandardRules)
+    }
+
+    /// Score a point, with `rules` deciding when games, sets and the match
+    /// are won.
+    pub fn score_point_with(&self, scorer: Player, rules: &dyn ScoringRules) -> MatchState {
+        let next = self.apply_point(scorer, rules);
+        // Scoring a valid state must never produce an invalid one
+        debug_assert!(
+            self.validate_with(rules).is_err() || next.validate_with(rules).is_ok(),
+            "score_point produced an invalid state: {:?}",
+            next.validate_with(rules)
+        );
+        next
+    }
+
+    fn apply_point(&self, scorer: Player, rules: &dyn ScoringRules) -> MatchState {
         match self {
             MatchState::Completed { .. } => self.clone(),
 
@@ -50,19 +305,12 @@ impl MatchState {
                 let current_set_index = sets.len() - 1;
                 let current_set = &sets[current_set_index];
 
-                let is_final_set = *player1_sets == config.sets_to_win - 1
-                    && *player2_sets == config.sets_to_win - 1;
+                let set_context = rules.set_context(config, &sets[..current_set_index]);
 
                 // Detect if we are currently in a tiebreak before scoring
                 let was_in_tiebreak = Self::set_is_in_tiebreak(current_set);
 
-                let new_set = current_set.score_point(
-                    scorer,
-                    config.no_ad_scoring,
-                    config.tiebreak_points,
-                    is_final_set,
-                    config.final_set_tiebreak,
-                );
+                let new_set = current_set.score_point_with(scorer, rules, &set_context);
 
                 let mut new_sets = sets.clone();
                 new_sets[current_set_index] = new_set.clone();
@@ -99,8 +347,13 @@ impl MatchState {
                         // Compute who was serving the last point
                         let last_server_offset = Self::tiebreak_server_offset(new_pts - 1);
                         let last_server = (*tiebreak_serve_index + last_server_offset) % serve_len;
-                        // After tiebreak, next server = next after the last tiebreak server
-                        let new_rotation = (last_server + 1) % serve_len;
+                        // The rules pick who opens the next set
+                        let new_rotation = rules.next_set_first_server(
+                            *tiebreak_serve_index,
+                            last_server,
+                            serve_len,
+                            &set_context,
+                        );
                         (new_rotation, 0, 0)
                     } else if game_just_completed {
                         // Regular game completed, advance rotation
@@ -129,22 +382,24 @@ impl MatchState {
                         Player::Player2 => (*player1_sets, player2_sets + 1),
                     };
 
-                    if new_p1_sets >= config.sets_to_win {
+                    if let Some(winner) = rules.match_winner(config, &new_sets) {
                         MatchState::Completed {
-                            winner: Player::Player1,
-                            player1_sets: new_p1_sets,
-                            player2_sets: new_p2_sets,
-                            sets: new_sets,
-                        }
-                    } else if new_p2_sets >= config.sets_to_win {
-                        MatchState::Completed {
-                            winner: Player::Player2,
+                            winner,
                             player1_sets: new_p1_sets,
                             player2_sets: new_p2_sets,
                             sets: new_sets,
+                            config: config.clone(),
                         }
                     } else {
-                        new_sets.push(SetState::new());
+                        let next_set =
+                            SetState::new_with(rules, &rules.set_context(config, &new_sets));
+                        // A match tiebreak starts with the next server in rotation
+                        let new_tb_serve_idx = if Self::set_is_in_tiebreak(&next_set) {
+                            new_serve_idx
+                        } else {
+                            new_tb_serve_idx
+                        };
+                        new_sets.push(next_set);
                         MatchState::Playing {
                             sets: new_sets,
                             player1_sets: new_p1_sets,
@@ -170,6 +425,123 @@ impl MatchState {
         }
     }
 
+    /// Check every structural invariant of the state against its config,
+    /// e.g. before using a state that was built by hand or imported.
+    ///
+    /// A completed match is only checked for its set counts and winner: the
+    /// winner must have won the last set and more sets, or be the winner the
+    /// rules give for its sets (e.g. on total games). Its last set may be
+    /// unfinished (a retirement).
+    pub fn validate(&self) -> Result<(), InvariantError> {
+        self.validate_with(&StandardRules)
+    }
+
+    /// As [`validate`](Self::validate), under `rules`.
+    pub fn validate_with(&self, rules: &dyn ScoringRules) -> Result<(), InvariantError> {
+        match self {
+            MatchState::Playing {
+                sets,
+                player1_sets,
+                player2_sets,
+                config,
+                serve_rotation_index,
+                tiebreak_serve_index,
+                tiebreak_points_served,
+            } => {
+                rules.validate_config(config)?;
+                let (current, completed) = sets
+                    .split_last()
+                    .ok_or(InvariantError::InvalidSetSequence)?;
+                let (mut p1_sets, mut p2_sets) = (0u8, 0u8);
+                for (i, set) in completed.iter().enumerate() {
+                    set.validate_with(rules, &rules.set_context(config, &completed[..i]))?;
+                    match set.winner() {
+                        Some(Player::Player1) => p1_sets += 1,
+                        Some(Player::Player2) => p2_sets += 1,
+                        None => return Err(InvariantError::InvalidSetSequence),
+                    }
+                    if rules.match_winner(config, &completed[..=i]).is_some() {
+                        return Err(InvariantError::InvalidMatchResult);
+                    }
+                }
+                if current.winner().is_some() {
+                    return Err(InvariantError::InvalidSetSequence);
+                }
+                current.validate_with(rules, &rules.set_context(config, completed))?;
+                if (p1_sets, p2_sets) != (*player1_sets, *player2_sets) {
+                    return Err(InvariantError::SetCountMismatch {
+                        player1_sets: *player1_sets,
+                        player2_sets: *player2_sets,
+                    });
+                }
+
+                let serve_len = config.serve_order.len();
+                let serve_ok = if serve_len == 0 {
+                    (
+                        *serve_rotation_index,
+                        *tiebreak_serve_index,
+                        *tiebreak_points_served,
+                    ) == (0, 0, 0)
+                } else {
+                    let tiebreak_points = match current {
+                        SetState::Playing {
+                            tiebreak: Some(tb), ..
+                        } => tb.points().map_or(0, |(p1, p2)| p1 + p2),
+                        _ => 0,
+                    };
+                    *serve_rotation_index < serve_len
+                        && *tiebreak_serve_index < serve_len
+                        && *tiebreak_points_served == tiebreak_points
+                };
+                if serve_ok {
+                    Ok(())
+                } else {
+                    Err(InvariantError::InvalidServeTracking)
+                }
+            }
+            MatchState::Completed {
+                winner,
+                player1_sets,
+                player2_sets,
+                sets,
+                config,
+            } => {
+                let (last, earlier) = sets
+                    .split_last()
+                    .ok_or(InvariantError::InvalidSetSequence)?;
+                if earlier.iter().any(|set| set.winner().is_none()) {
+                    return Err(InvariantError::InvalidSetSequence);
+                }
+                let count = |player| {
+                    sets.iter()
+                        .filter(|set| set.winner() == Some(player))
+                        .count()
+                };
+                let (p1_sets, p2_sets) = (count(Player::Player1), count(Player::Player2));
+                if (p1_sets, p2_sets) != (*player1_sets as usize, *player2_sets as usize) {
+                    return Err(InvariantError::SetCountMismatch {
+                        player1_sets: *player1_sets,
+                        player2_sets: *player2_sets,
+                    });
+                }
+                let (won, lost) = match winner {
+                    Player::Player1 => (p1_sets, p2_sets),
+                    Player::Player2 => (p2_sets, p1_sets),
+                };
+                // After a retirement the winner may be behind on sets
+                let retired = last.winner().is_none();
+                // Formats not decided on sets (e.g. cumulative games) go to
+                // whoever their rules say won
+                let decided = rules.match_winner(config, sets) == Some(*winner);
+                if retired || decided || (won > lost && last.winner() == Some(*winner)) {
+                    Ok(())
+                } else {
+                    Err(InvariantError::InvalidMatchResult)
+                }
+            }
+        }
+    }
+
     /// Check if a set is currently in a tiebreak
     fn set_is_in_tiebreak(set: &SetState) -> bool {
         matches!(
@@ -203,6 +575,16 @@ impl MatchState {
         }
     }
 
+    /// Games won by (player1, player2) across the whole match, a tiebreak
+    /// counting as a game. This decides a cumulative-games match.
+    pub fn total_games(&self) -> (u16, u16) {
+        match self {
+            MatchState::Playing { sets, .. } | MatchState::Completed { sets, .. } => {
+                Self::games_in(sets)
+            }
+        }
+    }
+
     pub fn winner(&self) -> Option<Player> {
         match self {
             MatchState::Completed { winner, .. } => Some(*winner),
@@ -212,10 +594,7 @@ impl MatchState {
 
     pub fn config(&self) -> &MatchConfig {
         match self {
-            MatchState::Playing { config, .. } => config,
-            MatchState::Completed { .. } => {
-                panic!("Cannot get config from completed match")
-            }
+            MatchState::Playing { config, .. } | MatchState::Completed { config, .. } => config,
         }
     }
 
@@ -248,6 +627,45 @@ impl MatchState {
         }
     }
 
+    /// The player (or doubles team) serving the next point, or `None` once
+    /// the match is over.
+    ///
+    /// Without a serve order, Player1 serves the first game and service
+    /// alternates every game, with tiebreak points served in the usual
+    /// 1-2-2 pattern.
+    pub fn serving_player(&self) -> Option<Player> {
+        match self {
+            MatchState::Playing { config, sets, .. } => {
+                if !config.serve_order.is_empty() {
+                    return Some(config.serve_order[self.current_server() as usize].0);
+                }
+                let games_played: usize = sets
+                    .iter()
+                    .map(|set| Self::set_game_total(set) as usize)
+                    .sum();
+                let base_server = if games_played.is_multiple_of(2) {
+                    Player::Player1
+                } else {
+                    Player::Player2
+                };
+                // Serve tracking only runs with a serve order, so count the
+                // tiebreak points played from the tiebreak itself
+                let tiebreak_points_played = match sets.last() {
+                    Some(SetState::Playing {
+                        tiebreak: Some(tb), ..
+                    }) => tb.points().map_or(0, |(p1, p2)| p1 + p2),
+                    _ => 0,
+                };
+                if Self::tiebreak_server_offset(tiebreak_points_played) % 2 == 1 {
+                    Some(base_server.opponent())
+                } else {
+                    Some(base_server)
+                }
+            }
+            MatchState::Completed { .. } => None,
+        }
+    }
+
     /// Given the number of tiebreak points already played, returns the
     /// server offset from the first tiebreak server.
     /// Pattern: first server serves 1 point, then each subsequent serves 2.
@@ -261,7 +679,7 @@ impl MatchState {
     /// N=4: server 2 (offset 2) -- third server, 2 of 2
     /// N=5: server 3 (offset 3)
     /// Formula: if N == 0 { 0 } else { (N + 1) / 2 }
-    fn tiebreak_server_offset(points_played: u8) -> usize {
+    pub(crate) fn tiebreak_server_offset(points_played: u8) -> usize {
         if points_played == 0 {
             0
         } else {
@@ -273,12 +691,17 @@ impl MatchState {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::game::GameState;
+    use crate::rules::{CumulativeGamesRules, TiebreakOnlyRules};
+    use crate::types::Point;
 
     fn score_game(state: MatchState, winner: Player) -> MatchState {
+        score_game_with(state, winner, &StandardRules)
+    }
+
+    fn score_game_with(state: MatchState, winner: Player, rules: &dyn ScoringRules) -> MatchState {
         let mut s = state;
         for _ in 0..4 {
-            s = s.score_point(winner);
+            s = s.score_point_with(winner, rules);
             if s.winner().is_some() {
                 return s;
             }
@@ -363,6 +786,7 @@ mod tests {
             player1_sets: 2,
             player2_sets: 0,
             sets: vec![],
+            config: MatchConfig::default(),
         };
         let state = state.score_point(Player::Player2);
 
@@ -389,11 +813,13 @@ mod tests {
                     winner: Player::Player1,
                     player1_games: 6,
                     player2_games: 4,
+                    tiebreak_score: None,
                 },
                 SetState::Completed {
                     winner: Player::Player2,
                     player1_games: 4,
                     player2_games: 6,
+                    tiebreak_score: None,
                 },
                 SetState::Playing {
                     player1_games: 6,
@@ -588,4 +1014,639 @@ mod tests {
         let state = score_set(state, Player::Player1);
         assert_eq!(state.current_server(), 0);
     }
+
+    #[test]
+    fn test_final_set_without_tiebreak_needs_two_game_lead() {
+        let config = MatchConfig {
+            final_set_tiebreak: false,
+            ..MatchConfig::default()
+        };
+        let mut state = MatchState::new(config);
+        state = score_set(state, Player::Player1);
+        state = score_set(state, Player::Player2);
+        for _ in 0..6 {
+            state = score_game(state, Player::Player1);
+            state = score_game(state, Player::Player2);
+        }
+
+        // 7-6 in an advantage final set is not enough
+        state = score_game(state, Player::Player1);
+        assert!(state.winner().is_none());
+
+        state = score_game(state, Player::Player1);
+        assert_eq!(state.winner(), Some(Player::Player1));
+    }
+
+    fn starting_score(
+        completed_sets: Vec<(u8, u8)>,
+        games: (u8, u8),
+        points: StartingPoints,
+        server: Player,
+    ) -> StartingScore {
+        StartingScore {
+            completed_sets,
+            games,
+            points,
+            server,
+            server_position: 0,
+        }
+    }
+
+    fn serving_player(state: &MatchState) -> Player {
+        state.config().serve_order[state.current_server() as usize].0
+    }
+
+    #[test]
+    fn test_from_score_mid_second_set() {
+        let score = starting_score(
+            vec![(6, 4)],
+            (3, 2),
+            StartingPoints::Game(GameState::Points {
+                player1: Point::Thirty,
+                player2: Point::Fifteen,
+            }),
+            Player::Player2,
+        );
+        let state = MatchState::from_score(MatchConfig::default(), &score).unwrap();
+
+        if let MatchState::Playing {
+            sets,
+            player1_sets,
+            player2_sets,
+            ..
+        } = &state
+        {
+            assert_eq!((*player1_sets, *player2_sets), (1, 0));
+            assert_eq!(sets.len(), 2);
+            assert_eq!(sets[0].winner(), Some(Player::Player1));
+            assert!(matches!(
+                sets[1],
+                SetState::Playing {
+                    player1_games: 3,
+                    player2_games: 2,
+                    ..
+                }
+            ));
+        } else {
+            panic!("Expected Playing");
+        }
+        assert_eq!(serving_player(&state), Player::Player2);
+
+        // Player2 holds, so Player1 serves next
+        let state = state.score_point(Player::Player2);
+        let state = state.score_point(Player::Player2);
+        let state = state.score_point(Player::Player2);
+        assert_eq!(serving_player(&state), Player::Player1);
+    }
+
+    #[test]
+    fn test_from_score_love_matches_new_match_scoring() {
+        let score = starting_score(
+            vec![],
+            (0, 0),
+            StartingPoints::Game(GameState::new()),
+            Player::Player1,
+        );
+        let from_score = MatchState::from_score(MatchConfig::default(), &score).unwrap();
+        let from_score = score_set(from_score, Player::Player1);
+        let played = score_set(MatchState::new(MatchConfig::default()), Player::Player1);
+
+        if let (MatchState::Playing { sets: a, .. }, MatchState::Playing { sets: b, .. }) =
+            (&from_score, &played)
+        {
+            assert_eq!(a, b);
+        } else {
+            panic!("Expected Playing");
+        }
+    }
+
+    #[test]
+    fn test_from_score_in_tiebreak() {
+        let score = starting_score(
+            vec![],
+            (6, 6),
+            StartingPoints::Tiebreak {
+                player1: 3,
+                player2: 2,
+            },
+            Player::Player2,
+        );
+        let state = MatchState::from_score(MatchConfig::default(), &score).unwrap();
+        assert_eq!(serving_player(&state), Player::Player2);
+
+        // Player2 serves 2 points in a row (5th and 6th of the tiebreak)
+        let state = state.score_point(Player::Player1);
+        assert_eq!(serving_player(&state), Player::Player2);
+        let state = state.score_point(Player::Player1);
+        assert_eq!(serving_player(&state), Player::Player1);
+
+        // Player1 completes the tiebreak 7-2
+        let state = state.score_point(Player::Player1);
+        let state = state.score_point(Player::Player1);
+        if let MatchState::Playing { player1_sets, .. } = &state {
+            assert_eq!(*player1_sets, 1);
+        } else {
+            panic!("Expected Playing");
+        }
+    }
+
+    #[test]
+    fn test_from_score_doubles_server_position() {
+        let score = StartingScore {
+            completed_sets: vec![],
+            games: (1, 1),
+            points: StartingPoints::Game(GameState::new()),
+            server: Player::Player1,
+            server_position: 1,
+        };
+        let state = MatchState::from_score(doubles_config(), &score).unwrap();
+        assert_eq!(state.current_server(), 2);
+
+        let missing = StartingScore {
+            server_position: 2,
+            ..score
+        };
+        assert_eq!(
+            MatchState::from_score(doubles_config(), &missing),
+            Err(StartingScoreError::UnknownServer {
+                player: Player::Player1,
+                position: 2,
+            })
+        );
+    }
+
+    #[test]
+    fn test_from_score_rejects_impossible_sets() {
+        let config = MatchConfig::default();
+        let love = StartingPoints::Game(GameState::new());
+
+        for set in [(9, 2), (6, 5), (8, 6), (7, 7)] {
+            let score = starting_score(vec![set], (0, 0), love.clone(), Player::Player1);
+            assert_eq!(
+                MatchState::from_score(config.clone(), &score),
+                Err(StartingScoreError::InvalidSetScore {
+                    set_number: 1,
+                    player1_games: set.0,
+                    player2_games: set.1,
+                })
+            );
+        }
+
+        let score = starting_score(vec![(6, 0), (6, 0)], (0, 0), love.clone(), Player::Player1);
+        assert_eq!(
+            MatchState::from_score(config.clone(), &score),
+            Err(StartingScoreError::MatchAlreadyDecided)
+        );
+
+        let score = starting_score(vec![], (7, 3), love, Player::Player1);
+        assert_eq!(
+            MatchState::from_score(config, &score),
+            Err(StartingScoreError::InvalidGameCount {
+                player1_games: 7,
+                player2_games: 3,
+            })
+        );
+    }
+
+    #[test]
+    fn test_from_score_final_set_without_tiebreak() {
+        let config = MatchConfig {
+            final_set_tiebreak: false,
+            ..MatchConfig::default()
+        };
+        let score = starting_score(
+            vec![(6, 4), (4, 6)],
+            (7, 6),
+            StartingPoints::Game(GameState::new()),
+            Player::Player1,
+        );
+        assert!(MatchState::from_score(config.clone(), &score).is_ok());
+
+        // The same score in a non-deciding set would have gone to a tiebreak
+        let score = starting_score(
+            vec![(6, 4)],
+            (7, 6),
+            StartingPoints::Game(GameState::new()),
+            Player::Player1,
+        );
+        assert!(MatchState::from_score(config, &score).is_err());
+    }
+
+    #[test]
+    fn test_from_score_rejects_impossible_points() {
+        let config = MatchConfig::default();
+        let forty_all = StartingPoints::Game(GameState::Points {
+            player1: Point::Forty,
+            player2: Point::Forty,
+        });
+        let score = starting_score(vec![], (2, 1), forty_all, Player::Player1);
+        assert_eq!(
+            MatchState::from_score(config.clone(), &score),
+            Err(StartingScoreError::InvalidGameScore)
+        );
+
+        // A regular game cannot be in progress at 6-6
+        let score = starting_score(
+            vec![],
+            (6, 6),
+            StartingPoints::Game(GameState::new()),
+            Player::Player1,
+        );
+        assert_eq!(
+            MatchState::from_score(config.clone(), &score),
+            Err(StartingScoreError::InvalidGameScore)
+        );
+
+        // Tiebreak points outside a tiebreak, and a tiebreak already won
+        for (games, points) in [((5, 5), (1, 0)), ((6, 6), (7, 3))] {
+            let score = starting_score(
+                vec![],
+                games,
+                StartingPoints::Tiebreak {
+                    player1: points.0,
+                    player2: points.1,
+                },
+                Player::Player1,
+            );
+            assert_eq!(
+                MatchState::from_score(config.clone(), &score),
+                Err(StartingScoreError::InvalidTiebreakScore {
+                    player1_points: points.0,
+                    player2_points: points.1,
+                })
+            );
+        }
+
+        // Advantage cannot occur with no-ad scoring
+        let no_ad = MatchConfig {
+            no_ad_scoring: true,
+            ..MatchConfig::default()
+        };
+        let score = starting_score(
+            vec![],
+            (0, 0),
+            StartingPoints::Game(GameState::Advantage {
+                player: Player::Player1,
+                deuce_count: 1,
+            }),
+            Player::Player1,
+        );
+        assert_eq!(
+            MatchState::from_score(no_ad, &score),
+            Err(StartingScoreError::InvalidGameScore)
+        );
+    }
+
+    fn won_set(winner: Player) -> SetState {
+        let (player1_games, player2_games) = match winner {
+            Player::Player1 => (6, 0),
+            Player::Player2 => (0, 6),
+        };
+        SetState::Completed {
+            winner,
+            player1_games,
+            player2_games,
+            tiebreak_score: None,
+        }
+    }
+
+    fn playing_match(sets: Vec<SetState>, player1_sets: u8, player2_sets: u8) -> MatchState {
+        MatchState::Playing {
+            sets,
+            player1_sets,
+            player2_sets,
+            config: MatchConfig::default(),
+            serve_rotation_index: 0,
+            tiebreak_serve_index: 0,
+            tiebreak_points_served: 0,
+        }
+    }
+
+    #[test]
+    fn test_validate_states_reached_by_play() {
+        let config = MatchConfig {
+            match_type: MatchType::Doubles,
+            serve_order: vec![
+                (Player::Player1, 0),
+                (Player::Player2, 0),
+                (Player::Player1, 1),
+                (Player::Player2, 1),
+            ],
+            match_tiebreak_points: Some(10),
+            ..MatchConfig::default()
+        };
+        let mut state = MatchState::new(config);
+        // 7-6, 0-6, then into the match tiebreak
+        for _ in 0..5 {
+            state = score_game(state, Player::Player1);
+            state = score_game(state, Player::Player2);
+        }
+        state = score_game(state, Player::Player1);
+        state = score_game(state, Player::Player2);
+        for _ in 0..7 {
+            state = state.score_point(Player::Player1);
+            assert_eq!(state.validate(), Ok(()));
+        }
+        for _ in 0..6 {
+            state = score_game(state, Player::Player2);
+        }
+        for _ in 0..5 {
+            state = state.score_point(Player::Player2);
+            assert_eq!(state.validate(), Ok(()));
+        }
+        for _ in 0..10 {
+            state = state.score_point(Player::Player1);
+        }
+        assert_eq!(state.winner(), Some(Player::Player1));
+        assert_eq!(state.validate(), Ok(()));
+    }
+
+    #[test]
+    fn test_validate_set_count_mismatch() {
+        let state = playing_match(vec![SetState::new()], 1, 0);
+        assert_eq!(
+            state.validate(),
+            Err(InvariantError::SetCountMismatch {
+                player1_sets: 1,
+                player2_sets: 0,
+            })
+        );
+    }
+
+    #[test]
+    fn test_validate_set_order() {
+        let unfinished_first = playing_match(vec![SetState::new(), SetState::new()], 0, 0);
+        assert_eq!(
+            unfinished_first.validate(),
+            Err(InvariantError::InvalidSetSequence)
+        );
+
+        let finished_current = playing_match(vec![won_set(Player::Player1)], 1, 0);
+        assert_eq!(
+            finished_current.validate(),
+            Err(InvariantError::InvalidSetSequence)
+        );
+
+        let decided = playing_match(
+            vec![
+                won_set(Player::Player1),
+                won_set(Player::Player1),
+                SetState::new(),
+            ],
+            2,
+            0,
+        );
+        assert_eq!(decided.validate(), Err(InvariantError::InvalidMatchResult));
+    }
+
+    #[test]
+    fn test_validate_impossible_playing_set() {
+        let state = playing_match(
+            vec![SetState::Playing {
+                player1_games: 9,
+                player2_games: 2,
+                current_game: GameState::new(),
+                tiebreak: None,
+            }],
+            0,
+            0,
+        );
+        assert_eq!(
+            state.validate(),
+            Err(InvariantError::SetAlreadyDecided {
+                player1_games: 9,
+                player2_games: 2,
+            })
+        );
+    }
+
+    #[test]
+    fn test_validate_serve_tracking() {
+        let mut state = MatchState::new(MatchConfig {
+            serve_order: vec![(Player::Player1, 0), (Player::Player2, 0)],
+            ..MatchConfig::default()
+        });
+        if let MatchState::Playing {
+            serve_rotation_index,
+            ..
+        } = &mut state
+        {
+            *serve_rotation_index = 5;
+        }
+        assert_eq!(state.validate(), Err(InvariantError::InvalidServeTracking));
+    }
+
+    #[test]
+    fn test_validate_completed_match() {
+        let completed = |winner| MatchState::Completed {
+            winner,
+            player1_sets: 2,
+            player2_sets: 0,
+            sets: vec![won_set(Player::Player1), won_set(Player::Player1)],
+            config: MatchConfig::default(),
+        };
+        assert_eq!(completed(Player::Player1).validate(), Ok(()));
+        assert_eq!(
+            completed(Player::Player2).validate(),
+            Err(InvariantError::InvalidMatchResult)
+        );
+
+        // Player2 retired while ahead on sets
+        let retired = MatchState::Completed {
+            winner: Player::Player1,
+            player1_sets: 0,
+            player2_sets: 1,
+            sets: vec![won_set(Player::Player2), SetState::new()],
+            config: MatchConfig::default(),
+        };
+        assert_eq!(retired.validate(), Ok(()));
+    }
+
+    fn play_sets(state: MatchState, sets: &[(u8, u8)], rules: &dyn ScoringRules) -> MatchState {
+        let mut state = state;
+        for &(p1_games, p2_games) in sets {
+            // The loser's games come first so the winner never leads early
+            let (winner, loser) = if p1_games > p2_games {
+                (Player::Player1, Player::Player2)
+            } else {
+                (Player::Player2, Player::Player1)
+            };
+            for _ in 0..p1_games.min(p2_games) {
+                state = score_game_with(state, loser, rules);
+            }
+            for _ in 0..p1_games.max(p2_games) {
+                state = score_game_with(state, winner, rules);
+            }
+        }
+        state
+    }
+
+    fn current_set(state: &MatchState) -> &SetState {
+        match state {
+            MatchState::Playing { sets, .. } | MatchState::Completed { sets, .. } => {
+                sets.last().unwrap()
+            }
+        }
+    }
+
+    #[test]
+    fn test_cumulative_games_decided_by_total() {
+        let rules = CumulativeGamesRules {
+            overtime: false,
+            ..CumulativeGamesRules::team_tennis()
+        };
+        let state = play_sets(
+            MatchState::new_with(MatchConfig::team_tennis(), &rules),
+            &[(5, 0), (5, 0), (3, 5), (3, 5), (3, 5)],
+            &rules,
+        );
+
+        // Player1 won two sets to three but 19 games to 15
+        assert_eq!(state.winner(), Some(Player::Player1));
+        assert_eq!(state.total_games(), (19, 15));
+        assert_eq!(state.validate_with(&rules), Ok(()));
+    }
+
+    #[test]
+    fn test_cumulative_games_plays_every_set() {
+        let rules = CumulativeGamesRules::team_tennis();
+        let state = play_sets(
+            MatchState::new_with(MatchConfig::team_tennis(), &rules),
+            &[(5, 0), (5, 0), (5, 0)],
+            &rules,
+        );
+        match &state {
+            MatchState::Playing { sets, .. } => assert_eq!(sets.len(), 4),
+            _ => panic!("all five sets are played"),
+        }
+        assert_eq!(state.validate_with(&rules), Ok(()));
+    }
+
+    #[test]
+    fn test_cumulative_games_tiebreak_one_game_short() {
+        let rules = CumulativeGamesRules::team_tennis();
+        let state = play_sets(
+            MatchState::new_with(MatchConfig::team_tennis(), &rules),
+            &[(4, 4)],
+            &rules,
+        );
+        match current_set(&state) {
+            SetState::Playing {
+                tiebreak: Some(tb), ..
+            } => assert_eq!(*tb, TiebreakState::new(5)),
+            other => panic!("expected a tiebreak at 4-4, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_cumulative_overtime_ends_when_leader_wins_a_game() {
+        let rules = CumulativeGamesRules::team_tennis();
+        let state = play_sets(
+            MatchState::new_with(MatchConfig::team_tennis(), &rules),
+            &[(5, 0), (5, 0), (3, 5), (3, 5), (3, 5)],
+            &rules,
+        );
+        // Player2 trails 15-19 but won the last set, so play goes on
+        assert_eq!(state.winner(), None);
+        assert_eq!(state.validate_with(&rules), Ok(()));
+
+        let state = score_game_with(state, Player::Player2, &rules);
+        let state = score_game_with(state, Player::Player1, &rules);
+        assert_eq!(state.winner(), Some(Player::Player1));
+        assert_eq!(state.total_games(), (20, 16));
+        assert_eq!(state.validate_with(&rules), Ok(()));
+    }
+
+    #[test]
+    fn test_cumulative_overtime_levels_into_supertiebreak() {
+        let rules = CumulativeGamesRules::team_tennis();
+        let state = play_sets(
+            MatchState::new_with(MatchConfig::team_tennis(), &rules),
+            &[(5, 0), (5, 2), (3, 5), (3, 5), (3, 5)],
+            &rules,
+        );
+        let mut state = (0..2).fold(state, |s, _| score_game_with(s, Player::Player2, &rules));
+        assert_eq!(state.total_games(), (19, 19));
+        match current_set(&state) {
+            SetState::Playing {
+                player1_games: 0,
+                player2_games: 0,
+                tiebreak: Some(tb),
+                ..
+            } => assert_eq!(*tb, TiebreakState::new(7)),
+            other => panic!("expected a supertiebreak, got {other:?}"),
+        }
+        assert_eq!(state.validate_with(&rules), Ok(()));
+
+        for _ in 0..7 {
+            state = state.score_point_with(Player::Player2, &rules);
+        }
+        assert_eq!(state.winner(), Some(Player::Player2));
+        assert_eq!(state.validate_with(&rules), Ok(()));
+    }
+
+    #[test]
+    fn test_cumulative_level_totals_go_to_supertiebreak() {
+        let rules = CumulativeGamesRules::team_tennis();
+        let state = play_sets(
+            MatchState::new_with(MatchConfig::team_tennis(), &rules),
+            &[(5, 0), (0, 5), (5, 3), (5, 3), (1, 5)],
+            &rules,
+        );
+        // Level at 16-16: no overtime, straight to the supertiebreak
+        assert_eq!(state.total_games(), (16, 16));
+        assert!(matches!(
+            current_set(&state),
+            SetState::Playing {
+                tiebreak: Some(_),
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn test_tiebreak_only_match() {
+        let mut state = MatchState::new_with(MatchConfig::tiebreaks(7, 2), &TiebreakOnlyRules);
+        assert!(matches!(
+            current_set(&state),
+            SetState::Playing {
+                tiebreak: Some(_),
+                ..
+            }
+        ));
+        for _ in 0..7 {
+            state = state.score_point_with(Player::Player1, &TiebreakOnlyRules);
+        }
+        assert_eq!(state.serving_player(), Some(Player::Player2));
+        assert_eq!(state.validate_with(&TiebreakOnlyRules), Ok(()));
+        for _ in 0..7 {
+            state = state.score_point_with(Player::Player1, &TiebreakOnlyRules);
+        }
+        assert_eq!(state.winner(), Some(Player::Player1));
+        assert_eq!(state.total_games(), (2, 0));
+    }
+
+    #[test]
+    fn test_tiebreak_only_doubles_alternates_first_server() {
+        let config = MatchConfig {
+            serve_order: doubles_config().serve_order,
+            match_type: MatchType::Doubles,
+            ..MatchConfig::tiebreaks(7, 3)
+        };
+        let mut state = MatchState::new_with(config, &TiebreakOnlyRules);
+        // A 7-0 tiebreak and a 7-5 one both hand the first serve on by one
+        for _ in 0..7 {
+            state = state.score_point_with(Player::Player1, &TiebreakOnlyRules);
+        }
+        assert_eq!(state.current_server(), 1);
+        for _ in 0..5 {
+            state = state.score_point_with(Player::Player1, &TiebreakOnlyRules);
+            state = state.score_point_with(Player::Player2, &TiebreakOnlyRules);
+        }
+        for _ in 0..2 {
+            state = state.score_point_with(Player::Player2, &TiebreakOnlyRules);
+        }
+        assert_eq!(state.current_server(), 2);
+        assert_eq!(state.validate_with(&TiebreakOnlyRules), Ok(()));
+    }
 }
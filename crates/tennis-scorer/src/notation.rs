@@ -0,0 +1,766 @@
+//! Standard score notation, e.g. "6-4 3-6 7-6(5)" for a finished match or
+//! "6-4 2-1 30-15*" for one in progress.
+//!
+//! Scores are always written Player1 first. An asterisk marks the server,
+//! before Player1's points or after Player2's. A tiebreak set shows the
+//! loser's tiebreak points in parentheses, a match tiebreak is written in
+//! brackets ("[10-8]"), "ret." follows a match ended by retirement and
+//! "time" one decided when time was called on a timed match.
+//! A cumulative-games match in progress ends with the running game totals,
+//! e.g. "5-3 2-1 30-15* (7-4 games)"; its supertiebreak is bracketed like a
+//! match tiebreak.
+
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::game::GameState;
+use crate::history::{CompletionReason, MatchWithHistory};
+use crate::match_state::MatchState;
+use crate::rules::{ScoringRules, SetContext, StandardRules};
+use crate::set::SetState;
+use crate::tiebreak::TiebreakState;
+use crate::types::{Player, Point};
+
+impl fmt::Display for Point {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            Point::OweThirty => "-30",
+            Point::OweFifteen => "-15",
+            Point::Love => "0",
+            Point::Fifteen => "15",
+            Point::Thirty => "30",
+            Point::Forty => "40",
+        };
+        f.write_str(s)
+    }
+}
+
+impl fmt::Display for GameState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GameState::Points { player1, player2 } => write!(f, "{player1}-{player2}"),
+            GameState::Deuce { .. } => f.write_str("40-40"),
+            GameState::Advantage {
+                player: Player::Player1,
+                ..
+            } => f.write_str("AD-40"),
+            GameState::Advantage {
+                player: Player::Player2,
+                ..
+            } => f.write_str("40-AD"),
+            GameState::Completed(Player::Player1) => f.write_str("G-"),
+            GameState::Completed(Player::Player2) => f.write_str("-G"),
+        }
+    }
+}
+
+impl fmt::Display for TiebreakState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.points() {
+            Some((p1, p2)) => write!(f, "{p1}-{p2}"),
+            None => f.write_str("-"),
+        }
+    }
+}
+
+impl fmt::Display for MatchState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.display_with(&StandardRules).fmt(f)
+    }
+}
+
+impl MatchState {
+    /// The score written for a match played under `rules`; `Display` writes
+    /// it under the standard rules.
+    pub fn display_with<'a>(&'a self, rules: &'a dyn ScoringRules) -> ScoreDisplay<'a> {
+        ScoreDisplay { state: self, rules }
+    }
+}
+
+/// A match score written under the rules it is played with (see
+/// [`MatchState::display_with`]).
+#[derive(Debug, Clone, Copy)]
+pub struct ScoreDisplay<'a> {
+    state: &'a MatchState,
+    rules: &'a dyn ScoringRules,
+}
+
+impl fmt::Display for ScoreDisplay<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (state, rules) = (self.state, self.rules);
+        let (sets, config) = match state {
+            MatchState::Completed { .. } => {
+                return ScoreLine::from_state_with(state, rules).fmt(f);
+            }
+            MatchState::Playing { sets, config, .. } => (sets, config),
+        };
+
+        // A hand-built or deserialised state may have no sets to write
+        let Some((current, completed)) = sets.split_last() else {
+            return Ok(());
+        };
+        for (i, set) in completed.iter().enumerate() {
+            let context = rules.set_context(config, &completed[..i]);
+            write!(f, "{} ", SetLine::from_set(set, rules, &context))?;
+        }
+        let context = rules.set_context(config, completed);
+
+        let marker = |player| {
+            if state.serving_player() == Some(player) {
+                "*"
+            } else {
+                ""
+            }
+        };
+        let (p1_marker, p2_marker) = (marker(Player::Player1), marker(Player::Player2));
+
+        match current {
+            SetState::Playing {
+                player1_games,
+                player2_games,
+                current_game,
+                tiebreak,
+            } => match tiebreak.as_ref().and_then(TiebreakState::points) {
+                Some((p1, p2)) if rules.set_starts_in_tiebreak(&context) => {
+                    write!(f, "[{p1_marker}{p1}-{p2}{p2_marker}]")
+                }
+                Some((p1, p2)) => write!(
+                    f,
+                    "{player1_games}-{player2_games} {p1_marker}{p1}-{p2}{p2_marker}"
+                ),
+                None => write!(
+                    f,
+                    "{player1_games}-{player2_games} {p1_marker}{current_game}{p2_marker}"
+                ),
+            },
+            SetState::Completed { .. } => {
+                write!(f, "{}", SetLine::from_set(current, rules, &context))
+            }
+        }?;
+
+        if rules.decided_by_total_games() {
+            let (p1_total, p2_total) = state.total_games();
+            write!(f, " ({p1_total}-{p2_total} games)")?;
+        }
+        Ok(())
+    }
+}
+
+/// One set of a written score line.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SetLine {
+    /// A set scored in games, e.g. "6-4" or "7-6(5)".
+    Games {
+        player1: u8,
+        player2: u8,
+        /// The losing side's points in the tiebreak that decided the set.
+        tiebreak_loser_points: Option<u8>,
+    },
+    /// A set played as a single tiebreak, such as a match tiebreak in place
+    /// of a deciding set, e.g. "[10-8]".
+    MatchTiebreak { player1: u8, player2: u8 },
+}
+
+impl SetLine {
+    /// Score of `set`, played in the given context. A set the rules play
+    /// as a single tiebreak from the first point is written as a match
+    /// tiebreak, whatever its games.
+    pub fn from_set(set: &SetState, rules: &dyn ScoringRules, context: &SetContext) -> SetLine {
+        let single_tiebreak = rules.set_starts_in_tiebreak(context);
+        match set {
+            SetState::Completed {
+                tiebreak_score: Some((p1, p2)),
+                ..
+            } if single_tiebreak => SetLine::MatchTiebreak {
+                player1: *p1,
+                player2: *p2,
+            },
+            SetState::Completed {
+                player1_games,
+                player2_games,
+                tiebreak_score,
+                ..
+            } => SetLine::Games {
+                player1: *player1_games,
+                player2: *player2_games,
+                tiebreak_loser_points: tiebreak_score.map(|(p1, p2)| p1.min(p2)),
+            },
+            SetState::Playing {
+                player1_games,
+                player2_games,
+                tiebreak,
+                ..
+            } => match tiebreak.as_ref().and_then(TiebreakState::points) {
+                Some((p1, p2)) if single_tiebreak => SetLine::MatchTiebreak {
+                    player1: p1,
+                    player2: p2,
+                },
+                _ => SetLine::Games {
+                    player1: *player1_games,
+                    player2: *player2_games,
+                    tiebreak_loser_points: None,
+                },
+            },
+        }
+    }
+
+    /// The side ahead in this set, if any.
+    pub fn leader(&self) -> Option<Player> {
+        let (p1, p2) = match self {
+            SetLine::Games {
+                player1, player2, ..
+            }
+            | SetLine::MatchTiebreak { player1, player2 } => (*player1, *player2),
+        };
+        match p1.cmp(&p2) {
+            std::cmp::Ordering::Greater => Some(Player::Player1),
+            std::cmp::Ordering::Less => Some(Player::Player2),
+            std::cmp::Ordering::Equal => None,
+        }
+    }
+}
+
+impl fmt::Display for SetLine {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SetLine::Games {
+                player1,
+                player2,
+                tiebreak_loser_points: Some(points),
+            } => write!(f, "{player1}-{player2}({points})"),
+            SetLine::Games {
+                player1,
+                player2,
+                tiebreak_loser_points: None,
+            } => write!(f, "{player1}-{player2}"),
+            SetLine::MatchTiebreak { player1, player2 } => write!(f, "[{player1}-{player2}]"),
+        }
+    }
+}
+
+/// A written set-by-set score, such as the final score of a match.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScoreLine {
+    pub sets: Vec<SetLine>,
+    /// The match ended by retirement during the last set.
+    pub retired: bool,
+    /// Time was called on the match during the last set.
+    #[serde(default)]
+    pub time_called: bool,
+}
+
+impl ScoreLine {
+    /// Set scores of a match, ignoring the game in progress.
+    pub fn from_state(state: &MatchState) -> ScoreLine {
+        Self::from_state_with(state, &StandardRules)
+    }
+
+    /// As [`from_state`](Self::from_state), for a match played under `rules`.
+    pub fn from_state_with(state: &MatchState, rules: &dyn ScoringRules) -> ScoreLine {
+        let (sets, retired) = match state {
+            MatchState::Playing { sets, .. } => (sets, false),
+            MatchState::Completed { sets, .. } => {
+                (sets, matches!(sets.last(), Some(SetState::Playing { .. })))
+            }
+        };
+        let config = state.config();
+        ScoreLine {
+            sets: sets
+                .iter()
+                .enumerate()
+                .map(|(i, set)| {
+                    let context = rules.set_context(config, &sets[..i]);
+                    SetLine::from_set(set, rules, &context)
+                })
+                .collect(),
+            retired,
+            time_called: false,
+        }
+    }
+
+    /// Set scores of a recorded match. Unlike
+    /// [`from_state_with`](Self::from_state_with), a timed match decided
+    /// when time was called is written as such rather than as a retirement.
+    pub fn from_recording(recording: &MatchWithHistory) -> ScoreLine {
+        let mut line = Self::from_state_with(recording.current(), recording.rules());
+        if recording
+            .completion()
+            .is_some_and(|c| c.reason == CompletionReason::TimeLimit)
+        {
+            line.time_called = line.retired;
+            line.retired = false;
+        }
+        line
+    }
+
+    /// Games won by (player1, player2) across all sets, the result of a
+    /// cumulative-games match. A match tiebreak counts as one game.
+    pub fn games_won(&self) -> (u16, u16) {
+        self.sets.iter().fold((0, 0), |(p1, p2), set| match set {
+            SetLine::Games {
+                player1, player2, ..
+            } => (p1 + u16::from(*player1), p2 + u16::from(*player2)),
+            SetLine::MatchTiebreak { .. } => match set.leader() {
+                Some(Player::Player1) => (p1 + 1, p2),
+                Some(Player::Player2) => (p1, p2 + 1),
+                None => (p1, p2),
+            },
+        })
+    }
+
+    /// Sets won by (player1, player2). An unfinished last set (one abandoned
+    /// by retirement or when time was called) is not counted.
+    pub fn sets_won(&self) -> (u8, u8) {
+        let finished = if self.retired || self.time_called {
+            &self.sets[..self.sets.len().saturating_sub(1)]
+        } else {
+            &self.sets[..]
+        };
+        finished
+            .iter()
+            .fold((0, 0), |(p1, p2), set| match set.leader() {
+                Some(Player::Player1) => (p1 + 1, p2),
+                Some(Player::Player2) => (p1, p2 + 1),
+                None => (p1, p2),
+            })
+    }
+}
+
+impl fmt::Display for ScoreLine {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, set) in self.sets.iter().enumerate() {
+            if i > 0 {
+                f.write_str(" ")?;
+            }
+            write!(f, "{set}")?;
+        }
+        if self.retired {
+            f.write_str(" ret.")?;
+        }
+        if self.time_called {
+            f.write_str(" time")?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseScoreError {
+    Empty,
+    /// A token that is not a set score such as "6-4", "7-6(5)" or "[10-8]".
+    InvalidSet(String),
+    /// "ret." appeared somewhere other than the end of the score.
+    MisplacedRetirement,
+    /// "time" appeared somewhere other than the end of the score.
+    MisplacedTimeCalled,
+}
+
+impl fmt::Display for ParseScoreError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseScoreError::Empty => write!(f, "empty score"),
+            ParseScoreError::InvalidSet(token) => write!(f, "invalid set score \"{token}\""),
+            ParseScoreError::MisplacedRetirement => {
+                write!(f, "retirement must come at the end of the score")
+            }
+            ParseScoreError::MisplacedTimeCalled => {
+                write!(f, "time called must come at the end of the score")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ParseScoreError {}
+
+impl FromStr for ScoreLine {
+    type Err = ParseScoreError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let tokens: Vec<&str> = s.split_whitespace().collect();
+        let mut sets = Vec::with_capacity(tokens.len());
+        let mut retired = false;
+        let mut time_called = false;
+
+        for token in tokens {
+            if retired {
+                return Err(ParseScoreError::MisplacedRetirement);
+            }
+            if time_called {
+                return Err(ParseScoreError::MisplacedTimeCalled);
+            }
+            match token.to_ascii_lowercase().as_str() {
+                "ret" | "ret." | "retired" => {
+                    retired = true;
+                    continue;
+                }
+                "time" => {
+                    time_called = true;
+                    continue;
+                }
+                _ => {}
+            }
+            sets.push(parse_set(token)?);
+        }
+
+        if sets.is_empty() {
+            return Err(ParseScoreError::Empty);
+        }
+        Ok(ScoreLine {
+            sets,
+            retired,
+            time_called,
+        })
+    }
+}
+
+fn parse_set(token: &str) -> Result<SetLine, ParseScoreError> {
+    let invalid = || ParseScoreError::InvalidSet(token.to_string());
+
+    if let Some(inner) = token.strip_prefix('[').and_then(|t| t.strip_suffix(']')) {
+        let (player1, player2) = parse_pair(inner).ok_or_else(invalid)?;
+        return Ok(SetLine::MatchTiebreak { player1, player2 });
+    }
+
+    let (games, tiebreak_loser_points) = match token.split_once('(') {
+        Some((games, rest)) => {
+            let points = rest
+                .strip_suffix(')')
+                .and_then(|p| p.parse::<u8>().ok())
+                .ok_or_else(invalid)?;
+            (games, Some(points))
+        }
+        None => (token, None),
+    };
+    let (player1, player2) = parse_pair(games).ok_or_else(invalid)?;
+    Ok(SetLine::Games {
+        player1,
+        player2,
+        tiebreak_loser_points,
+    })
+}
+
+fn parse_pair(s: &str) -> Option<(u8, u8)> {
+    let (a, b) = s.split_once('-')?;
+    Some((a.parse().ok()?, b.parse().ok()?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::MatchConfig;
+    use crate::rules::{CumulativeGamesRules, TiebreakOnlyRules};
+
+    fn score_game(state: MatchState, winner: Player, rules: &dyn ScoringRules) -> MatchState {
+        let mut s = state;
+        for _ in 0..4 {
+            s = s.score_point_with(winner, rules);
+        }
+        s
+    }
+
+    fn score_games(state: MatchState, p1_games: u8, p2_games: u8) -> MatchState {
+        score_games_with(state, p1_games, p2_games, &StandardRules)
+    }
+
+    fn score_games_with(
+        state: MatchState,
+        p1_games: u8,
+        p2_games: u8,
+        rules: &dyn ScoringRules,
+    ) -> MatchState {
+        let mut s = state;
+        for _ in 0..p1_games.min(p2_games) {
+            s = score_game(s, Player::Player1, rules);
+            s = score_game(s, Player::Player2, rules);
+        }
+        for _ in p2_games..p1_games {
+            s = score_game(s, Player::Player1, rules);
+        }
+        for _ in p1_games..p2_games {
+            s = score_game(s, Player::Player2, rules);
+        }
+        s
+    }
+
+    #[test]
+    fn test_display_new_match() {
+        let state = MatchState::new(MatchConfig::default());
+        assert_eq!(state.to_string(), "0-0 *0-0");
+    }
+
+    #[test]
+    fn test_display_in_progress_with_server_marker() {
+        let state = MatchState::new(MatchConfig::default());
+        let state = score_games(state, 6, 4);
+        let state = score_games(state, 2, 1);
+        // 13 games played: Player2 serves
+        let state = state.score_point(Player::Player1);
+        let state = state.score_point(Player::Player1);
+        let state = state.score_point(Player::Player2);
+        assert_eq!(state.to_string(), "6-4 2-1 30-15*");
+    }
+
+    #[test]
+    fn test_display_deuce_and_advantage() {
+        let mut state = MatchState::new(MatchConfig::default());
+        for _ in 0..3 {
+            state = state.score_point(Player::Player1);
+            state = state.score_point(Player::Player2);
+        }
+        assert_eq!(state.to_string(), "0-0 *40-40");
+        let state = state.score_point(Player::Player2);
+        assert_eq!(state.to_string(), "0-0 *40-AD");
+    }
+
+    #[test]
+    fn test_display_completed_match_with_tiebreak() {
+        let state = MatchState::new(MatchConfig::default());
+        let state = score_games(state, 6, 4);
+        let state = score_games(state, 3, 6);
+        let mut state = score_games(state, 6, 6);
+        for _ in 0..5 {
+            state = state.score_point(Player::Player1);
+            state = state.score_point(Player::Player2);
+        }
+        state = state.score_point(Player::Player1);
+        state = state.score_point(Player::Player1);
+
+        assert_eq!(state.winner(), Some(Player::Player1));
+        assert_eq!(state.to_string(), "6-4 3-6 7-6(5)");
+    }
+
+    #[test]
+    fn test_display_tiebreak_in_progress() {
+        let state = MatchState::new(MatchConfig::default());
+        let state = score_games(state, 6, 6);
+        let state = state.score_point(Player::Player2);
+        // Player1 served the first tiebreak point; Player2 serves the next two
+        assert_eq!(state.to_string(), "6-6 0-1*");
+    }
+
+    #[test]
+    fn test_display_match_tiebreak() {
+        let config = MatchConfig {
+            match_tiebreak_points: Some(10),
+            ..MatchConfig::default()
+        };
+        let state = MatchState::new(config);
+        let state = score_games(state, 6, 4);
+        let mut state = score_games(state, 4, 6);
+        for _ in 0..8 {
+            state = state.score_point(Player::Player1);
+            state = state.score_point(Player::Player2);
+        }
+        assert_eq!(state.to_string(), "6-4 4-6 [*8-8]");
+
+        state = state.score_point(Player::Player1);
+        state = state.score_point(Player::Player1);
+        assert_eq!(state.winner(), Some(Player::Player1));
+        assert_eq!(state.to_string(), "6-4 4-6 [10-8]");
+    }
+
+    /// Sets go to a tiebreak after a single game.
+    #[derive(Debug)]
+    struct OneGameSets;
+
+    impl ScoringRules for OneGameSets {
+        fn tiebreak_due(&self, player1_games: u8, player2_games: u8, _set: &SetContext) -> bool {
+            player1_games + player2_games == 1
+        }
+    }
+
+    #[test]
+    fn test_regular_tiebreak_after_one_game_is_not_a_match_tiebreak() {
+        let mut state = MatchState::new_with(MatchConfig::default(), &OneGameSets);
+        for _ in 0..4 {
+            state = state.score_point_with(Player::Player1, &OneGameSets);
+        }
+        state = state.score_point_with(Player::Player2, &OneGameSets);
+        assert_eq!(state.to_string(), "1-0 *0-1");
+        assert_eq!(
+            ScoreLine::from_state_with(&state, &OneGameSets).sets,
+            vec![SetLine::Games {
+                player1: 1,
+                player2: 0,
+                tiebreak_loser_points: None,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_display_retirement() {
+        let state = MatchState::Completed {
+            winner: Player::Player2,
+            player1_sets: 0,
+            player2_sets: 1,
+            sets: vec![
+                SetState::Completed {
+                    winner: Player::Player2,
+                    player1_games: 4,
+                    player2_games: 6,
+                    tiebreak_score: None,
+                },
+                SetState::Playing {
+                    player1_games: 3,
+                    player2_games: 1,
+                    current_game: GameState::new(),
+                    tiebreak: None,
+                },
+            ],
+            config: MatchConfig::default(),
+        };
+        assert_eq!(state.to_string(), "4-6 3-1 ret.");
+    }
+
+    #[test]
+    fn test_display_without_sets_is_empty() {
+        let mut state = MatchState::new(MatchConfig::default());
+        if let MatchState::Playing { sets, .. } = &mut state {
+            sets.clear();
+        }
+        assert_eq!(state.to_string(), "");
+    }
+
+    #[test]
+    fn test_parse_score_line() {
+        let line: ScoreLine = "6-4 3-6 7-6(5)".parse().unwrap();
+        assert_eq!(
+            line.sets,
+            vec![
+                SetLine::Games {
+                    player1: 6,
+                    player2: 4,
+                    tiebreak_loser_points: None,
+                },
+                SetLine::Games {
+                    player1: 3,
+                    player2: 6,
+                    tiebreak_loser_points: None,
+                },
+                SetLine::Games {
+                    player1: 7,
+                    player2: 6,
+                    tiebreak_loser_points: Some(5),
+                },
+            ]
+        );
+        assert!(!line.retired);
+        assert_eq!(line.sets_won(), (2, 1));
+    }
+
+    #[test]
+    fn test_parse_match_tiebreak_and_retirement() {
+        let line: ScoreLine = "6-4 4-6 [10-8]".parse().unwrap();
+        assert_eq!(
+            line.sets[2],
+            SetLine::MatchTiebreak {
+                player1: 10,
+                player2: 8,
+            }
+        );
+        assert_eq!(line.sets_won(), (2, 1));
+
+        let line: ScoreLine = "4-6 3-1 ret.".parse().unwrap();
+        assert!(line.retired);
+        assert_eq!(line.sets_won(), (0, 1));
+    }
+
+    #[test]
+    fn test_parse_display_roundtrip() {
+        for text in ["6-4 3-6 7-6(5)", "6-4 4-6 [10-8]", "7-5 3-1 ret.", "6-0"] {
+            let line: ScoreLine = text.parse().unwrap();
+            assert_eq!(line.to_string(), text);
+        }
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!("".parse::<ScoreLine>(), Err(ParseScoreError::Empty));
+        assert_eq!(
+            "6-4 x-2".parse::<ScoreLine>(),
+            Err(ParseScoreError::InvalidSet("x-2".to_string()))
+        );
+        assert_eq!(
+            "7-6(5".parse::<ScoreLine>(),
+            Err(ParseScoreError::InvalidSet("7-6(5".to_string()))
+        );
+        assert_eq!(
+            "6-4 ret. 6-2".parse::<ScoreLine>(),
+            Err(ParseScoreError::MisplacedRetirement)
+        );
+    }
+
+    #[test]
+    fn test_score_line_from_state_matches_display() {
+        let state = MatchState::new(MatchConfig::default());
+        let state = score_games(state, 6, 2);
+        let state = score_games(state, 6, 3);
+        assert_eq!(state.winner(), Some(Player::Player1));
+        let line = ScoreLine::from_state(&state);
+        assert_eq!(line.to_string(), state.to_string());
+        assert_eq!(line.sets_won(), (2, 0));
+    }
+
+    #[test]
+    fn test_score_line_of_match_ended_on_time() {
+        use crate::config::{TimeLimit, TimedDecider, TimedLeader};
+
+        let config = MatchConfig {
+            time_limit: Some(TimeLimit {
+                minutes: 45,
+                leader: TimedLeader::SetsThenGames,
+                decider: TimedDecider::SuddenDeathPoint,
+            }),
+            ..MatchConfig::default()
+        };
+        let mut winners = vec![Player::Player1; 6 * 4];
+        winners.extend([Player::Player2; 4]);
+        let recording = winners
+            .into_iter()
+            .fold(MatchWithHistory::new(MatchState::new(config)), |m, p| {
+                m.score_point(p)
+            })
+            .end_on_time()
+            .unwrap();
+
+        let line = ScoreLine::from_recording(&recording);
+        assert!(line.time_called);
+        assert!(!line.retired);
+        assert_eq!(line.to_string(), "6-0 0-1 time");
+        assert_eq!(line.sets_won(), (1, 0));
+        assert_eq!("6-0 0-1 time".parse::<ScoreLine>(), Ok(line));
+        assert_eq!(
+            "6-0 time 0-1".parse::<ScoreLine>(),
+            Err(ParseScoreError::MisplacedTimeCalled)
+        );
+    }
+
+    #[test]
+    fn test_display_cumulative_games_totals() {
+        let rules = CumulativeGamesRules::team_tennis();
+        let state = MatchState::new_with(MatchConfig::team_tennis(), &rules);
+        let state = score_games_with(state, 5, 3, &rules);
+        let state = score_games_with(state, 3, 5, &rules);
+        let state = score_games_with(state, 2, 1, &rules);
+        assert_eq!(
+            state.display_with(&rules).to_string(),
+            "5-3 3-5 2-1 0-0* (10-9 games)"
+        );
+
+        let line: ScoreLine = "5-3 3-5 5-1 2-5 4-5(3) [7-5]".parse().unwrap();
+        assert_eq!(line.games_won(), (20, 19));
+    }
+
+    #[test]
+    fn test_display_tiebreak_only_match() {
+        let rules = TiebreakOnlyRules;
+        let mut state = MatchState::new_with(MatchConfig::tiebreaks(7, 2), &rules);
+        for _ in 0..7 {
+            state = state.score_point_with(Player::Player1, &rules);
+        }
+        state = state.score_point_with(Player::Player2, &rules);
+        assert_eq!(state.display_with(&rules).to_string(), "[7-0] [*0-1]");
+    }
+}
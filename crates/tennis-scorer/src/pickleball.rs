@@ -0,0 +1,538 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::MatchType;
+use crate::history::{ScoreHistory, Scoring};
+use crate::invariant::InvariantError;
+use crate::types::Player;
+
+/// Format of a pickleball match scored with side-out scoring.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PickleballConfig {
+    /// Points needed to win a game, usually 11, 15 or 21.
+    pub points_to_win: u8,
+    /// Lead needed to win a game once a side reaches `points_to_win`.
+    pub win_by: u8,
+    /// Games needed to win the match (2 for best of three).
+    pub games_to_win: u8,
+    #[serde(default)]
+    pub match_type: MatchType,
+    /// The side serving first in game one. First serve alternates by game.
+    pub first_server: Player,
+}
+
+impl Default for PickleballConfig {
+    fn default() -> Self {
+        Self {
+            points_to_win: 11,
+            win_by: 2,
+            games_to_win: 2,
+            match_type: MatchType::Doubles,
+            first_server: Player::Player1,
+        }
+    }
+}
+
+impl PickleballConfig {
+    /// Check the config describes a match that can be won: each target is
+    /// at least one, and a game can end on `points_to_win` (the margin is
+    /// no more than the target).
+    pub fn validate(&self) -> Result<(), InvariantError> {
+        if self.points_to_win == 0
+            || self.games_to_win == 0
+            || self.win_by == 0
+            || self.win_by > self.points_to_win
+        {
+            return Err(InvariantError::InvalidConfig);
+        }
+        Ok(())
+    }
+}
+
+/// A single pickleball game. Only the serving side scores; losing a rally
+/// on serve passes the serve to the partner (server 2) in doubles, or over
+/// to the other side (a side out).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PickleballGame {
+    Playing {
+        player1_points: u8,
+        player2_points: u8,
+        serving: Player,
+        /// 1 or 2 in doubles; `None` in singles.
+        server_number: Option<u8>,
+    },
+    Completed {
+        winner: Player,
+        player1_points: u8,
+        player2_points: u8,
+    },
+}
+
+impl PickleballGame {
+    /// A new game at 0-0. In doubles the first serving side starts on
+    /// server 2 ("0-0-2"), so it loses the serve after one fault.
+    pub fn new(serving: Player, match_type: &MatchType) -> Self {
+        let server_number = match match_type {
+            MatchType::Singles => None,
+            MatchType::Doubles => Some(2),
+        };
+        PickleballGame::Playing {
+            player1_points: 0,
+            player2_points: 0,
+            serving,
+            server_number,
+        }
+    }
+
+    pub fn score_rally(&self, rally_winner: Player, config: &PickleballConfig) -> Self {
+        let PickleballGame::Playing {
+            player1_points,
+            player2_points,
+            serving,
+            server_number,
+        } = *self
+        else {
+            return self.clone();
+        };
+
+        if rally_winner != serving {
+            return match server_number {
+                Some(1) => PickleballGame::Playing {
+                    player1_points,
+                    player2_points,
+                    serving,
+                    server_number: Some(2),
+                },
+                _ => PickleballGame::Playing {
+                    player1_points,
+                    player2_points,
+                    serving: serving.opponent(),
+                    server_number: server_number.map(|_| 1),
+                },
+            };
+        }
+
+        let (player1_points, player2_points) = match serving {
+            Player::Player1 => (player1_points + 1, player2_points),
+            Player::Player2 => (player1_points, player2_points + 1),
+        };
+        let (own, other) = match serving {
+            Player::Player1 => (player1_points, player2_points),
+            Player::Player2 => (player2_points, player1_points),
+        };
+        if own >= config.points_to_win && own >= other + config.win_by {
+            PickleballGame::Completed {
+                winner: serving,
+                player1_points,
+                player2_points,
+            }
+        } else {
+            PickleballGame::Playing {
+                player1_points,
+                player2_points,
+                serving,
+                server_number,
+            }
+        }
+    }
+
+    pub fn winner(&self) -> Option<Player> {
+        match self {
+            PickleballGame::Completed { winner, .. } => Some(*winner),
+            PickleballGame::Playing { .. } => None,
+        }
+    }
+
+    pub fn points(&self) -> (u8, u8) {
+        match self {
+            PickleballGame::Playing {
+                player1_points,
+                player2_points,
+                ..
+            }
+            | PickleballGame::Completed {
+                player1_points,
+                player2_points,
+                ..
+            } => (*player1_points, *player2_points),
+        }
+    }
+
+    /// The side serving, while the game is in play.
+    pub fn serving(&self) -> Option<Player> {
+        match self {
+            PickleballGame::Playing { serving, .. } => Some(*serving),
+            PickleballGame::Completed { .. } => None,
+        }
+    }
+}
+
+/// The score call: serving side's score first, then the receiving side's,
+/// then the server number in doubles (e.g. "4-2-1"). Finished games show
+/// player 1's score first.
+impl fmt::Display for PickleballGame {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PickleballGame::Playing {
+                player1_points,
+                player2_points,
+                serving,
+                server_number,
+            } => {
+                let (own, other) = match serving {
+                    Player::Player1 => (player1_points, player2_points),
+                    Player::Player2 => (player2_points, player1_points),
+                };
+                match server_number {
+                    Some(number) => write!(f, "{own}-{other}-{number}"),
+                    None => write!(f, "{own}-{other}"),
+                }
+            }
+            PickleballGame::Completed {
+                player1_points,
+                player2_points,
+                ..
+            } => write!(f, "{player1_points}-{player2_points}"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PickleballState {
+    Playing {
+        games: Vec<PickleballGame>,
+        player1_games: u8,
+        player2_games: u8,
+        config: PickleballConfig,
+    },
+    Completed {
+        winner: Player,
+        player1_games: u8,
+        player2_games: u8,
+        games: Vec<PickleballGame>,
+    },
+}
+
+impl PickleballState {
+    pub fn new(config: PickleballConfig) -> Self {
+        let first = PickleballGame::new(config.first_server, &config.match_type);
+        PickleballState::Playing {
+            games: vec![first],
+            player1_games: 0,
+            player2_games: 0,
+            config,
+        }
+    }
+
+    pub fn score_rally(&self, rally_winner: Player) -> Self {
+        let PickleballState::Playing {
+            games,
+            player1_games,
+            player2_games,
+            config,
+        } = self
+        else {
+            return self.clone();
+        };
+
+        let mut games = games.clone();
+        let current = games.last_mut().expect("a match in play has a game");
+        *current = current.score_rally(rally_winner, config);
+
+        let (player1_games, player2_games) = match current.winner() {
+            Some(Player::Player1) => (player1_games + 1, *player2_games),
+            Some(Player::Player2) => (*player1_games, player2_games + 1),
+            None => (*player1_games, *player2_games),
+        };
+        if current.winner().is_none() {
+            return PickleballState::Playing {
+                games,
+                player1_games,
+                player2_games,
+                config: config.clone(),
+            };
+        }
+
+        if player1_games >= config.games_to_win || player2_games >= config.games_to_win {
+            let winner = if player1_games > player2_games {
+                Player::Player1
+            } else {
+                Player::Player2
+            };
+            return PickleballState::Completed {
+                winner,
+                player1_games,
+                player2_games,
+                games,
+            };
+        }
+
+        let first_server = if games.len() % 2 == 0 {
+            config.first_server
+        } else {
+            config.first_server.opponent()
+        };
+        games.push(PickleballGame::new(first_server, &config.match_type));
+        PickleballState::Playing {
+            games,
+            player1_games,
+            player2_games,
+            config: config.clone(),
+        }
+    }
+
+    pub fn winner(&self) -> Option<Player> {
+        match self {
+            PickleballState::Completed { winner, .. } => Some(*winner),
+            PickleballState::Playing { .. } => None,
+        }
+    }
+
+    pub fn games(&self) -> &[PickleballGame] {
+        match self {
+            PickleballState::Playing { games, .. } | PickleballState::Completed { games, .. } => {
+                games
+            }
+        }
+    }
+
+    /// Games won by each side.
+    pub fn games_won(&self) -> (u8, u8) {
+        match self {
+            PickleballState::Playing {
+                player1_games,
+                player2_games,
+                ..
+            }
+            | PickleballState::Completed {
+                player1_games,
+                player2_games,
+                ..
+            } => (*player1_games, *player2_games),
+        }
+    }
+
+    /// The side serving the next rally, while the match is in play.
+    pub fn serving(&self) -> Option<Player> {
+        match self {
+            PickleballState::Playing { games, .. } => games.last().and_then(|g| g.serving()),
+            PickleballState::Completed { .. } => None,
+        }
+    }
+
+    /// The server number (1 or 2) of the next rally in doubles.
+    pub fn server_number(&self) -> Option<u8> {
+        match self {
+            PickleballState::Playing { games, .. } => match games.last() {
+                Some(PickleballGame::Playing { server_number, .. }) => *server_number,
+                _ => None,
+            },
+            PickleballState::Completed { .. } => None,
+        }
+    }
+}
+
+impl Scoring for PickleballState {
+    fn score_point(&self, scorer: Player) -> Self {
+        self.score_rally(scorer)
+    }
+
+    fn winner(&self) -> Option<Player> {
+        PickleballState::winner(self)
+    }
+}
+
+/// Finished games as "11-7", followed by the score call of the game in play
+/// (e.g. "11-7 4-2-1").
+impl fmt::Display for PickleballState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let parts: Vec<String> = self.games().iter().map(|g| g.to_string()).collect();
+        write!(f, "{}", parts.join(" "))
+    }
+}
+
+/// A pickleball match with undo and point timestamps.
+pub type PickleballWithHistory = ScoreHistory<PickleballState>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, UNIX_EPOCH};
+
+    fn singles() -> PickleballConfig {
+        PickleballConfig {
+            match_type: MatchType::Singles,
+            ..PickleballConfig::default()
+        }
+    }
+
+    fn rallies(state: PickleballState, winners: &[Player]) -> PickleballState {
+        winners.iter().fold(state, |s, &p| s.score_rally(p))
+    }
+
+    /// Win a game on serve for `player`, taking the serve first if needed.
+    fn win_game(state: PickleballState, player: Player) -> PickleballState {
+        let games = state.games().len();
+        let mut state = state;
+        while state.games().len() == games && state.winner().is_none() {
+            state = state.score_rally(player);
+        }
+        state
+    }
+
+    #[test]
+    fn test_validate_config() {
+        assert_eq!(PickleballConfig::default().validate(), Ok(()));
+        for config in [
+            PickleballConfig {
+                points_to_win: 0,
+                ..PickleballConfig::default()
+            },
+            PickleballConfig {
+                games_to_win: 0,
+                ..PickleballConfig::default()
+            },
+            PickleballConfig {
+                win_by: 0,
+                ..PickleballConfig::default()
+            },
+            PickleballConfig {
+                points_to_win: 1,
+                win_by: 2,
+                ..PickleballConfig::default()
+            },
+        ] {
+            assert_eq!(config.validate(), Err(InvariantError::InvalidConfig));
+        }
+    }
+
+    #[test]
+    fn test_serde_roundtrip() {
+        let state = rallies(
+            PickleballState::new(PickleballConfig::default()),
+            &[Player::Player1, Player::Player2],
+        );
+        let json = serde_json::to_string(&state).unwrap();
+        assert_eq!(
+            serde_json::from_str::<PickleballState>(&json).unwrap(),
+            state
+        );
+    }
+
+    #[test]
+    fn test_doubles_starts_on_second_server() {
+        let state = PickleballState::new(PickleballConfig::default());
+        assert_eq!(state.to_string(), "0-0-2");
+        assert_eq!(state.serving(), Some(Player::Player1));
+        assert_eq!(state.server_number(), Some(2));
+
+        // One lost rally at 0-0-2 is a side out
+        let state = state.score_rally(Player::Player2);
+        assert_eq!(state.serving(), Some(Player::Player2));
+        assert_eq!(state.to_string(), "0-0-1");
+    }
+
+    #[test]
+    fn test_doubles_server_rotation() {
+        let state = PickleballState::new(PickleballConfig::default());
+        let state = rallies(state, &[Player::Player2, Player::Player2, Player::Player2]);
+        assert_eq!(state.to_string(), "2-0-1");
+
+        // Server 1 loses the rally: partner serves
+        let state = state.score_rally(Player::Player1);
+        assert_eq!(state.to_string(), "2-0-2");
+        assert_eq!(state.serving(), Some(Player::Player2));
+
+        // Server 2 loses the rally: side out
+        let state = state.score_rally(Player::Player1);
+        assert_eq!(state.to_string(), "0-2-1");
+        assert_eq!(state.serving(), Some(Player::Player1));
+    }
+
+    #[test]
+    fn test_only_serving_side_scores() {
+        let state = PickleballState::new(singles());
+        assert_eq!(state.to_string(), "0-0");
+        let state = rallies(state, &[Player::Player1, Player::Player2, Player::Player2]);
+        // The second rally is a side out, not a point
+        assert_eq!(state.games()[0].points(), (1, 1));
+        assert_eq!(state.serving(), Some(Player::Player2));
+        assert_eq!(state.to_string(), "1-1");
+    }
+
+    #[test]
+    fn test_game_needs_two_point_lead() {
+        let mut game = PickleballGame::Playing {
+            player1_points: 10,
+            player2_points: 10,
+            serving: Player::Player1,
+            server_number: None,
+        };
+        let config = singles();
+        game = game.score_rally(Player::Player1, &config);
+        assert_eq!(game.winner(), None);
+        game = game.score_rally(Player::Player1, &config);
+        assert_eq!(game.winner(), Some(Player::Player1));
+        assert_eq!(game.points(), (12, 10));
+    }
+
+    #[test]
+    fn test_games_to_fifteen() {
+        let config = PickleballConfig {
+            points_to_win: 15,
+            ..singles()
+        };
+        let state = rallies(PickleballState::new(config), &[Player::Player1; 14]);
+        assert_eq!(state.games_won(), (0, 0));
+        let state = state.score_rally(Player::Player1);
+        assert_eq!(state.games_won(), (1, 0));
+    }
+
+    #[test]
+    fn test_first_serve_alternates_by_game() {
+        let state = win_game(
+            PickleballState::new(PickleballConfig::default()),
+            Player::Player1,
+        );
+        assert_eq!(state.games_won(), (1, 0));
+        assert_eq!(state.serving(), Some(Player::Player2));
+        assert_eq!(state.server_number(), Some(2));
+        assert_eq!(state.to_string(), "11-0 0-0-2");
+    }
+
+    #[test]
+    fn test_best_of_three() {
+        let state = PickleballState::new(singles());
+        let state = win_game(state, Player::Player1);
+        let state = win_game(state, Player::Player2);
+        assert_eq!(state.winner(), None);
+        let state = win_game(state, Player::Player1);
+        assert_eq!(state.winner(), Some(Player::Player1));
+        assert_eq!(state.games_won(), (2, 1));
+        assert_eq!(state.to_string(), "11-0 0-11 11-0");
+
+        // Rallies after the match is over are ignored
+        assert_eq!(state.score_rally(Player::Player2), state);
+    }
+
+    #[test]
+    fn test_history_undo_and_timestamps() {
+        let start = UNIX_EPOCH + Duration::from_secs(1_000_000);
+        let recording = PickleballWithHistory::from_events(
+            PickleballState::new(PickleballConfig::default()),
+            &[
+                (Player::Player1, start),
+                (Player::Player2, start + Duration::from_secs(20)),
+            ],
+        )
+        .unwrap();
+        assert_eq!(recording.current().to_string(), "0-1-1");
+        assert_eq!(recording.history_len(), 2);
+
+        let undone = recording.undo();
+        assert_eq!(undone.current().to_string(), "1-0-2");
+        assert_eq!(undone.last_timestamp(), Some(start));
+        assert!(recording.score_point_at(Player::Player1, start).is_err());
+    }
+}
@@ -0,0 +1,447 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::TiebreakRule;
+use crate::history::{ScoreHistory, Scoring};
+use crate::types::Player;
+
+/// Who serves the next rally.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ServeRule {
+    /// The rally winner serves next, and the winner of a game serves first
+    /// in the next (squash, badminton).
+    WinnerServes,
+    /// Serve changes every `every` points, and every point once both sides
+    /// are one point from the target. First serve alternates by game
+    /// (table tennis).
+    Alternate { every: u8 },
+}
+
+/// Format of a rally-point match, where every rally scores a point.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RallyConfig {
+    /// Points needed to win a game.
+    pub points_to_win: u8,
+    /// Lead needed to win a game once a side reaches `points_to_win`.
+    pub win_by: u8,
+    /// When set, the first side to this many points wins the game whatever
+    /// the margin (30 in badminton).
+    #[serde(default)]
+    pub cap: Option<u8>,
+    pub serve_rule: ServeRule,
+    /// Games needed to win the match (3 for best of five).
+    pub games_to_win: u8,
+    /// The side serving first in game one.
+    pub first_server: Player,
+}
+
+impl RallyConfig {
+    /// Squash: PAR-11, win by two, best of five.
+    pub fn squash() -> Self {
+        Self {
+            points_to_win: 11,
+            win_by: 2,
+            cap: None,
+            serve_rule: ServeRule::WinnerServes,
+            games_to_win: 3,
+            first_server: Player::Player1,
+        }
+    }
+
+    /// Badminton: 21 points, win by two capped at 30, best of three.
+    pub fn badminton() -> Self {
+        Self {
+            points_to_win: 21,
+            win_by: 2,
+            cap: Some(30),
+            serve_rule: ServeRule::WinnerServes,
+            games_to_win: 2,
+            first_server: Player::Player1,
+        }
+    }
+
+    /// Table tennis: 11 points, win by two, serve every two points, best of
+    /// five.
+    pub fn table_tennis() -> Self {
+        Self {
+            points_to_win: 11,
+            win_by: 2,
+            cap: None,
+            serve_rule: ServeRule::Alternate { every: 2 },
+            games_to_win: 3,
+            first_server: Player::Player1,
+        }
+    }
+
+    /// Games are won like a tiebreak to `points_to_win`, so share its rule.
+    fn game_winner(&self, player1_points: u8, player2_points: u8) -> Option<Player> {
+        TiebreakRule {
+            win_by: self.win_by,
+            cap: self.cap,
+        }
+        .winner(player1_points, player2_points, self.points_to_win)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RallyGame {
+    Playing {
+        player1_points: u8,
+        player2_points: u8,
+        serving: Player,
+    },
+    Completed {
+        winner: Player,
+        player1_points: u8,
+        player2_points: u8,
+    },
+}
+
+impl RallyGame {
+    pub fn new(serving: Player) -> Self {
+        RallyGame::Playing {
+            player1_points: 0,
+            player2_points: 0,
+            serving,
+        }
+    }
+
+    pub fn score_rally(&self, rally_winner: Player, config: &RallyConfig) -> Self {
+        let RallyGame::Playing {
+            player1_points,
+            player2_points,
+            serving,
+        } = *self
+        else {
+            return self.clone();
+        };
+
+        let (player1_points, player2_points) = match rally_winner {
+            Player::Player1 => (player1_points + 1, player2_points),
+            Player::Player2 => (player1_points, player2_points + 1),
+        };
+        if let Some(winner) = config.game_winner(player1_points, player2_points) {
+            return RallyGame::Completed {
+                winner,
+                player1_points,
+                player2_points,
+            };
+        }
+
+        let serving = match config.serve_rule {
+            ServeRule::WinnerServes => rally_winner,
+            ServeRule::Alternate { every } => {
+                let level_at_end = player1_points >= config.points_to_win.saturating_sub(1)
+                    && player2_points >= config.points_to_win.saturating_sub(1);
+                let played = u16::from(player1_points) + u16::from(player2_points);
+                if level_at_end || played % u16::from(every.max(1)) == 0 {
+                    serving.opponent()
+                } else {
+                    serving
+                }
+            }
+        };
+        RallyGame::Playing {
+            player1_points,
+            player2_points,
+            serving,
+        }
+    }
+
+    pub fn winner(&self) -> Option<Player> {
+        match self {
+            RallyGame::Completed { winner, .. } => Some(*winner),
+            RallyGame::Playing { .. } => None,
+        }
+    }
+
+    pub fn points(&self) -> (u8, u8) {
+        match self {
+            RallyGame::Playing {
+                player1_points,
+                player2_points,
+                ..
+            }
+            | RallyGame::Completed {
+                player1_points,
+                player2_points,
+                ..
+            } => (*player1_points, *player2_points),
+        }
+    }
+
+    /// The side serving, while the game is in play.
+    pub fn serving(&self) -> Option<Player> {
+        match self {
+            RallyGame::Playing { serving, .. } => Some(*serving),
+            RallyGame::Completed { .. } => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RallyState {
+    Playing {
+        games: Vec<RallyGame>,
+        player1_games: u8,
+        player2_games: u8,
+        config: RallyConfig,
+    },
+    Completed {
+        winner: Player,
+        player1_games: u8,
+        player2_games: u8,
+        games: Vec<RallyGame>,
+    },
+}
+
+impl RallyState {
+    pub fn new(config: RallyConfig) -> Self {
+        RallyState::Playing {
+            games: vec![RallyGame::new(config.first_server)],
+            player1_games: 0,
+            player2_games: 0,
+            config,
+        }
+    }
+
+    pub fn score_rally(&self, rally_winner: Player) -> Self {
+        let RallyState::Playing {
+            games,
+            player1_games,
+            player2_games,
+            config,
+        } = self
+        else {
+            return self.clone();
+        };
+
+        let mut games = games.clone();
+        let current = games.last_mut().expect("a match in play has a game");
+        *current = current.score_rally(rally_winner, config);
+
+        let Some(game_winner) = current.winner() else {
+            return RallyState::Playing {
+                games,
+                player1_games: *player1_games,
+                player2_games: *player2_games,
+                config: config.clone(),
+            };
+        };
+        let (player1_games, player2_games) = match game_winner {
+            Player::Player1 => (player1_games + 1, *player2_games),
+            Player::Player2 => (*player1_games, player2_games + 1),
+        };
+
+        if player1_games >= config.games_to_win || player2_games >= config.games_to_win {
+            return RallyState::Completed {
+                winner: game_winner,
+                player1_games,
+                player2_games,
+                games,
+            };
+        }
+
+        let first_server = match config.serve_rule {
+            ServeRule::WinnerServes => game_winner,
+            ServeRule::Alternate { .. } if games.len() % 2 == 0 => config.first_server,
+            ServeRule::Alternate { .. } => config.first_server.opponent(),
+        };
+        games.push(RallyGame::new(first_server));
+        RallyState::Playing {
+            games,
+            player1_games,
+            player2_games,
+            config: config.clone(),
+        }
+    }
+
+    pub fn winner(&self) -> Option<Player> {
+        match self {
+            RallyState::Completed { winner, .. } => Some(*winner),
+            RallyState::Playing { .. } => None,
+        }
+    }
+
+    pub fn games(&self) -> &[RallyGame] {
+        match self {
+            RallyState::Playing { games, .. } | RallyState::Completed { games, .. } => games,
+        }
+    }
+
+    /// Games won by each side.
+    pub fn games_won(&self) -> (u8, u8) {
+        match self {
+            RallyState::Playing {
+                player1_games,
+                player2_games,
+                ..
+            }
+            | RallyState::Completed {
+                player1_games,
+                player2_games,
+                ..
+            } => (*player1_games, *player2_games),
+        }
+    }
+
+    /// The side serving the next rally, while the match is in play.
+    pub fn serving(&self) -> Option<Player> {
+        match self {
+            RallyState::Playing { games, .. } => games.last().and_then(RallyGame::serving),
+            RallyState::Completed { .. } => None,
+        }
+    }
+}
+
+impl Scoring for RallyState {
+    fn score_point(&self, scorer: Player) -> Self {
+        self.score_rally(scorer)
+    }
+
+    fn winner(&self) -> Option<Player> {
+        RallyState::winner(self)
+    }
+}
+
+/// Game scores in player 1's favour, e.g. "11-7 9-11 *4-3", with a "*" on
+/// the server's side of the game in play.
+impl fmt::Display for RallyState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let parts: Vec<String> = self
+            .games()
+            .iter()
+            .map(|game| {
+                let (p1, p2) = game.points();
+                match game.serving() {
+                    Some(Player::Player1) => format!("*{p1}-{p2}"),
+                    Some(Player::Player2) => format!("{p1}-{p2}*"),
+                    None => format!("{p1}-{p2}"),
+                }
+            })
+            .collect();
+        write!(f, "{}", parts.join(" "))
+    }
+}
+
+/// A rally-point match with undo and point timestamps.
+pub type RallyWithHistory = ScoreHistory<RallyState>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, UNIX_EPOCH};
+
+    fn rallies(state: RallyState, winners: &[Player]) -> RallyState {
+        winners.iter().fold(state, |s, &p| s.score_rally(p))
+    }
+
+    fn alternating(state: RallyState, pairs: usize) -> RallyState {
+        (0..pairs).fold(state, |s, _| {
+            s.score_rally(Player::Player1).score_rally(Player::Player2)
+        })
+    }
+
+    #[test]
+    fn test_winner_serves() {
+        let state = RallyState::new(RallyConfig::squash());
+        assert_eq!(state.to_string(), "*0-0");
+        let state = state.score_rally(Player::Player2);
+        assert_eq!(state.serving(), Some(Player::Player2));
+        assert_eq!(state.to_string(), "0-1*");
+    }
+
+    #[test]
+    fn test_squash_game_needs_two_point_lead() {
+        let state = alternating(RallyState::new(RallyConfig::squash()), 10);
+        let state = state.score_rally(Player::Player1);
+        assert_eq!(state.games_won(), (0, 0));
+        let state = state.score_rally(Player::Player1);
+        assert_eq!(state.games_won(), (1, 0));
+        assert_eq!(state.to_string(), "12-10 *0-0");
+    }
+
+    #[test]
+    fn test_badminton_cap() {
+        let state = alternating(RallyState::new(RallyConfig::badminton()), 29);
+        assert_eq!(state.games()[0].points(), (29, 29));
+        let state = state.score_rally(Player::Player2);
+        assert_eq!(state.games_won(), (0, 1));
+        assert_eq!(state.games()[0].points(), (29, 30));
+        // The game winner serves first in the next game
+        assert_eq!(state.serving(), Some(Player::Player2));
+    }
+
+    #[test]
+    fn test_table_tennis_serve_every_two_points() {
+        let state = RallyState::new(RallyConfig::table_tennis());
+        let state = state.score_rally(Player::Player2);
+        assert_eq!(state.serving(), Some(Player::Player1));
+        let state = state.score_rally(Player::Player2);
+        assert_eq!(state.serving(), Some(Player::Player2));
+        let state = rallies(state, &[Player::Player1, Player::Player1]);
+        assert_eq!(state.serving(), Some(Player::Player1));
+    }
+
+    #[test]
+    fn test_table_tennis_serve_every_point_at_ten_all() {
+        let state = alternating(RallyState::new(RallyConfig::table_tennis()), 10);
+        assert_eq!(state.games()[0].points(), (10, 10));
+        let serving = state.serving().unwrap();
+        let state = state.score_rally(Player::Player1);
+        assert_eq!(state.serving(), Some(serving.opponent()));
+        let state = state.score_rally(Player::Player2);
+        assert_eq!(state.serving(), Some(serving));
+    }
+
+    #[test]
+    fn test_table_tennis_first_serve_alternates_by_game() {
+        let state = rallies(
+            RallyState::new(RallyConfig::table_tennis()),
+            &[Player::Player1; 11],
+        );
+        assert_eq!(state.games_won(), (1, 0));
+        assert_eq!(state.serving(), Some(Player::Player2));
+    }
+
+    #[test]
+    fn test_best_of_n_games() {
+        let state = rallies(
+            RallyState::new(RallyConfig::squash()),
+            &[Player::Player1; 33],
+        );
+        assert_eq!(state.winner(), Some(Player::Player1));
+        assert_eq!(state.games_won(), (3, 0));
+        assert_eq!(state.to_string(), "11-0 11-0 11-0");
+        assert_eq!(state.score_rally(Player::Player2), state);
+    }
+
+    #[test]
+    fn test_serde_round_trip() {
+        let state = rallies(
+            RallyState::new(RallyConfig::badminton()),
+            &[Player::Player1, Player::Player2, Player::Player2],
+        );
+        let json = serde_json::to_string(&state).unwrap();
+        let restored: RallyState = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored, state);
+    }
+
+    #[test]
+    fn test_history_undo_and_events() {
+        let start = UNIX_EPOCH + Duration::from_secs(1_000_000);
+        let recording = RallyWithHistory::new(RallyState::new(RallyConfig::squash()))
+            .score_point_at(Player::Player1, start)
+            .unwrap()
+            .score_point_at(Player::Player2, start + Duration::from_secs(15))
+            .unwrap();
+        assert_eq!(recording.current().to_string(), "1-1*");
+        assert_eq!(recording.point_events().len(), 2);
+
+        let undone = recording.undo();
+        assert_eq!(undone.current().to_string(), "*1-0");
+        assert_eq!(undone.history_len(), 1);
+        assert!(recording.score_point_at(Player::Player1, start).is_err());
+    }
+}
@@ -0,0 +1,496 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::MatchConfig;
+use crate::game::GameState;
+use crate::invariant::InvariantError;
+use crate::set::SetState;
+use crate::types::Player;
+
+/// What a set is played as.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum SetKind {
+    #[default]
+    Regular,
+    /// Extended play in a cumulative-games match: the set ends when `leader`
+    /// wins a game, or when the other side has won `deficit` games and so
+    /// levelled the totals.
+    Overtime { leader: Player, deficit: u8 },
+    /// A tiebreak to `points` deciding a cumulative-games match whose totals
+    /// are level.
+    Supertiebreak { points: u8 },
+}
+
+/// Where a set sits in the match, passed to the set-level rules.
+#[derive(Debug, Clone, Copy)]
+pub struct SetContext<'a> {
+    pub config: &'a MatchConfig,
+    /// Whether this set decides the match (both sides one set from winning).
+    pub is_final_set: bool,
+    pub kind: SetKind,
+}
+
+/// The rules the state machine delegates to at game, set and match level.
+///
+/// Every method has a default implementing standard tennis scoring as
+/// configured by `MatchConfig`; [`StandardRules`] uses them all. A format
+/// overrides only the methods that differ and is passed to the `*_with`
+/// scoring methods, or to `MatchWithHistory::with_rules`.
+pub trait ScoringRules: fmt::Debug + Send + Sync {
+    /// The score each regular game starts at (love-all, or the handicap).
+    fn new_game(&self, set: &SetContext) -> GameState {
+        set.config.handicap.starting_game()
+    }
+
+    /// The game after `scorer` wins a point of a regular (non-tiebreak) game.
+    fn score_game_point(&self, game: &GameState, scorer: Player, set: &SetContext) -> GameState {
+        game.score_point_deciding_at(scorer, set.config.deciding_deuce())
+    }
+
+    /// Check `game` is a score these rules can produce.
+    fn validate_game(&self, game: &GameState, set: &SetContext) -> Result<(), InvariantError> {
+        game.validate_deciding_at(set.config.deciding_deuce())
+    }
+
+    /// Points needed to win a tiebreak played in this set.
+    fn tiebreak_target(&self, set: &SetContext) -> u8 {
+        match set.config.match_tiebreak_points {
+            Some(points) if set.is_final_set => points,
+            _ => set.config.tiebreak_points,
+        }
+    }
+
+    /// Winner of a tiebreak at these points, if it is over: first to the
+    /// target with the lead required by the config's tiebreak rule for this
+    /// kind of tiebreak (two points by default), or first to its cap.
+    fn tiebreak_winner(
+        &self,
+        player1_points: u8,
+        player2_points: u8,
+        target_points: u8,
+        set: &SetContext,
+    ) -> Option<Player> {
+        let rule = if is_match_tiebreak(set) {
+            set.config.match_tiebreak_rule
+        } else {
+            set.config.tiebreak_rule
+        };
+        rule.winner(player1_points, player2_points, target_points)
+    }
+
+    /// Whether the set is played as a single tiebreak from the first point:
+    /// a match tiebreak in place of the deciding set.
+    fn set_starts_in_tiebreak(&self, set: &SetContext) -> bool {
+        is_match_tiebreak(set)
+    }
+
+    /// Position in a serve rotation of `rotation_len` of whoever serves
+    /// first in the set after `set`, which was won in a tiebreak that
+    /// `first_server` opened and `last_server` closed: the rotation carries
+    /// on after the last server.
+    fn next_set_first_server(
+        &self,
+        _first_server: usize,
+        last_server: usize,
+        rotation_len: usize,
+        _set: &SetContext,
+    ) -> usize {
+        (last_server + 1) % rotation_len
+    }
+
+    /// Whether a tiebreak starts at these games (6-6, unless the deciding
+    /// set is played out).
+    fn tiebreak_due(&self, player1_games: u8, player2_games: u8, set: &SetContext) -> bool {
+        player1_games == 6
+            && player2_games == 6
+            && (!set.is_final_set || set.config.final_set_tiebreak)
+    }
+
+    /// Winner of the set at these games, if it is over: six games with a
+    /// two-game lead. Sets won in a tiebreak are decided by the tiebreak.
+    fn set_winner(
+        &self,
+        player1_games: u8,
+        player2_games: u8,
+        _set: &SetContext,
+    ) -> Option<Player> {
+        first_to_by_two(player1_games, player2_games, 6)
+    }
+
+    /// The set played after the `completed` sets; the deciding set once
+    /// both sides are a set from winning.
+    fn set_context<'a>(&self, config: &'a MatchConfig, completed: &[SetState]) -> SetContext<'a> {
+        let (player1_sets, player2_sets) = sets_won(completed);
+        SetContext {
+            config,
+            is_final_set: player1_sets == config.sets_to_win - 1
+                && player2_sets == config.sets_to_win - 1,
+            kind: SetKind::Regular,
+        }
+    }
+
+    /// Winner of the match once the `completed` sets have been played, if
+    /// it is over: the first to `sets_to_win` sets.
+    fn match_winner(&self, config: &MatchConfig, completed: &[SetState]) -> Option<Player> {
+        let (player1_sets, player2_sets) = sets_won(completed);
+        if player1_sets >= config.sets_to_win {
+            Some(Player::Player1)
+        } else if player2_sets >= config.sets_to_win {
+            Some(Player::Player2)
+        } else {
+            None
+        }
+    }
+
+    /// Whether the match is decided by games won across all sets, so that
+    /// scores show the totals.
+    fn decided_by_total_games(&self) -> bool {
+        false
+    }
+
+    /// Check the config holds values these rules can play with.
+    fn validate_config(&self, config: &MatchConfig) -> Result<(), InvariantError> {
+        if config.sets_to_win == 0 {
+            return Err(InvariantError::InvalidConfig);
+        }
+        validate_tiebreaks(config)
+    }
+}
+
+/// Standard tennis scoring, as configured by `MatchConfig`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct StandardRules;
+
+impl ScoringRules for StandardRules {}
+
+/// A series of stand-alone tiebreaks, as in practice tiebreak sessions:
+/// every set is a single tiebreak to the config's `tiebreak_points` and the
+/// first to `sets_to_win` tiebreaks wins. Who serves first alternates from
+/// one tiebreak to the next.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TiebreakOnlyRules;
+
+impl ScoringRules for TiebreakOnlyRules {
+    fn set_starts_in_tiebreak(&self, _set: &SetContext) -> bool {
+        true
+    }
+
+    fn next_set_first_server(
+        &self,
+        first_server: usize,
+        _last_server: usize,
+        rotation_len: usize,
+        _set: &SetContext,
+    ) -> usize {
+        (first_server + 1) % rotation_len
+    }
+}
+
+/// Cumulative-games scoring (team tennis): a fixed number of sets is always
+/// played and the side with more games in total wins the match. The
+/// config's `sets_to_win` is ignored.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CumulativeGamesRules {
+    pub sets: u8,
+    /// Games needed to win a set, by two; a tiebreak is played at one game
+    /// fewer all (5 gives a tiebreak at 4-4).
+    pub games_per_set: u8,
+    /// Extended play: if the side behind on games wins the last set, play
+    /// continues until the leader wins a game or the totals are level.
+    pub overtime: bool,
+    /// Points in the supertiebreak played when the totals are level.
+    pub supertiebreak_points: u8,
+}
+
+impl CumulativeGamesRules {
+    /// Team tennis: five sets to five games, extended play, and a
+    /// supertiebreak to seven if the games are level. Pair with
+    /// `MatchConfig::team_tennis`.
+    pub fn team_tennis() -> Self {
+        Self {
+            sets: 5,
+            games_per_set: 5,
+            overtime: true,
+            supertiebreak_points: 7,
+        }
+    }
+
+    /// The kind of set played after the `completed` sets, or the winner if
+    /// the match is over.
+    fn progress(&self, completed: &[SetState]) -> Result<SetKind, Player> {
+        let regular_sets = usize::from(self.sets);
+        if completed.len() < regular_sets {
+            return Ok(SetKind::Regular);
+        }
+        if let Some((last, earlier)) = completed.split_last()
+            && let Ok(SetKind::Supertiebreak { .. }) = self.progress(earlier)
+            && let Some(winner) = last.winner()
+        {
+            return Err(winner);
+        }
+        let (player1_games, player2_games) =
+            completed.iter().fold((0u16, 0u16), |(p1, p2), set| {
+                let (g1, g2) = set.games();
+                (p1 + u16::from(g1), p2 + u16::from(g2))
+            });
+        let leader = match player1_games.cmp(&player2_games) {
+            std::cmp::Ordering::Greater => Player::Player1,
+            std::cmp::Ordering::Less => Player::Player2,
+            std::cmp::Ordering::Equal => {
+                return Ok(SetKind::Supertiebreak {
+                    points: self.supertiebreak_points,
+                });
+            }
+        };
+        let trailer_won_last = completed.last().and_then(SetState::winner) != Some(leader);
+        if self.overtime && completed.len() == regular_sets && trailer_won_last {
+            let deficit = player1_games.abs_diff(player2_games);
+            return Ok(SetKind::Overtime {
+                leader,
+                deficit: u8::try_from(deficit).unwrap_or(u8::MAX),
+            });
+        }
+        Err(leader)
+    }
+}
+
+impl ScoringRules for CumulativeGamesRules {
+    fn tiebreak_target(&self, set: &SetContext) -> u8 {
+        match set.kind {
+            SetKind::Supertiebreak { points } => points,
+            _ => set.config.tiebreak_points,
+        }
+    }
+
+    fn tiebreak_winner(
+        &self,
+        player1_points: u8,
+        player2_points: u8,
+        target_points: u8,
+        set: &SetContext,
+    ) -> Option<Player> {
+        let rule = match set.kind {
+            SetKind::Supertiebreak { .. } => set.config.match_tiebreak_rule,
+            _ => set.config.tiebreak_rule,
+        };
+        rule.winner(player1_points, player2_points, target_points)
+    }
+
+    fn set_starts_in_tiebreak(&self, set: &SetContext) -> bool {
+        matches!(set.kind, SetKind::Supertiebreak { .. })
+    }
+
+    /// One game short of the set, all; never in extended play.
+    fn tiebreak_due(&self, player1_games: u8, player2_games: u8, set: &SetContext) -> bool {
+        match set.kind {
+            SetKind::Overtime { .. } => false,
+            _ => {
+                player1_games == player2_games
+                    && player1_games == self.games_per_set.saturating_sub(1)
+            }
+        }
+    }
+
+    fn set_winner(&self, player1_games: u8, player2_games: u8, set: &SetContext) -> Option<Player> {
+        let SetKind::Overtime { leader, deficit } = set.kind else {
+            return first_to_by_two(player1_games, player2_games, self.games_per_set);
+        };
+        let (leader_games, trailer_games) = match leader {
+            Player::Player1 => (player1_games, player2_games),
+            Player::Player2 => (player2_games, player1_games),
+        };
+        if leader_games > 0 {
+            Some(leader)
+        } else if trailer_games >= deficit {
+            Some(leader.opponent())
+        } else {
+            None
+        }
+    }
+
+    fn set_context<'a>(&self, config: &'a MatchConfig, completed: &[SetState]) -> SetContext<'a> {
+        let kind = self.progress(completed).unwrap_or_default();
+        SetContext {
+            config,
+            is_final_set: matches!(kind, SetKind::Supertiebreak { .. }),
+            kind,
+        }
+    }
+
+    fn match_winner(&self, _config: &MatchConfig, completed: &[SetState]) -> Option<Player> {
+        self.progress(completed).err()
+    }
+
+    fn decided_by_total_games(&self) -> bool {
+        true
+    }
+
+    fn validate_config(&self, config: &MatchConfig) -> Result<(), InvariantError> {
+        if self.sets == 0 || self.games_per_set == 0 || self.supertiebreak_points == 0 {
+            return Err(InvariantError::InvalidConfig);
+        }
+        validate_tiebreaks(config)
+    }
+}
+
+/// Whether the set is a match tiebreak, rather than a set that may end in a
+/// tiebreak.
+fn is_match_tiebreak(set: &SetContext) -> bool {
+    set.is_final_set && set.config.match_tiebreak_points.is_some()
+}
+
+/// Sets won by (player1, player2) among `sets`.
+fn sets_won(sets: &[SetState]) -> (u8, u8) {
+    sets.iter()
+        .fold((0, 0), |(p1, p2), set| match set.winner() {
+            Some(Player::Player1) => (p1 + 1, p2),
+            Some(Player::Player2) => (p1, p2 + 1),
+            None => (p1, p2),
+        })
+}
+
+/// The config's tiebreak targets and margins can be played.
+fn validate_tiebreaks(config: &MatchConfig) -> Result<(), InvariantError> {
+    if config.tiebreak_points == 0
+        || config.match_tiebreak_points == Some(0)
+        || config.tiebreak_rule.win_by == 0
+        || config.match_tiebreak_rule.win_by == 0
+    {
+        Err(InvariantError::InvalidConfig)
+    } else {
+        Ok(())
+    }
+}
+
+/// Winner once one side has `games` games and a two-game lead.
+fn first_to_by_two(player1_games: u8, player2_games: u8, games: u8) -> Option<Player> {
+    let leader = player1_games.max(player2_games);
+    if leader >= games && player1_games.abs_diff(player2_games) >= 2 {
+        Some(leader_of(player1_games, player2_games))
+    } else {
+        None
+    }
+}
+
+fn leader_of(player1: u8, player2: u8) -> Player {
+    if player1 > player2 {
+        Player::Player1
+    } else {
+        Player::Player2
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::match_state::MatchState;
+
+    /// Short sets: first to four games, tiebreak at 3-3.
+    #[derive(Debug)]
+    struct ShortSets;
+
+    impl ScoringRules for ShortSets {
+        fn tiebreak_due(&self, player1_games: u8, player2_games: u8, _set: &SetContext) -> bool {
+            player1_games == 3 && player2_games == 3
+        }
+
+        fn set_winner(
+            &self,
+            player1_games: u8,
+            player2_games: u8,
+            _set: &SetContext,
+        ) -> Option<Player> {
+            let leader = player1_games.max(player2_games);
+            if leader >= 4 && player1_games.abs_diff(player2_games) >= 2 {
+                Some(leader_of(player1_games, player2_games))
+            } else {
+                None
+            }
+        }
+    }
+
+    fn win_games(
+        state: MatchState,
+        player: Player,
+        games: usize,
+        rules: &dyn ScoringRules,
+    ) -> MatchState {
+        let mut state = state;
+        for _ in 0..games * 4 {
+            state = state.score_point_with(player, rules);
+        }
+        state
+    }
+
+    #[test]
+    fn test_standard_set_winner() {
+        let config = MatchConfig::default();
+        let set = SetContext {
+            config: &config,
+            is_final_set: false,
+            kind: SetKind::Regular,
+        };
+        assert_eq!(StandardRules.set_winner(6, 4, &set), Some(Player::Player1));
+        assert_eq!(StandardRules.set_winner(6, 5, &set), None);
+        assert_eq!(StandardRules.set_winner(5, 7, &set), Some(Player::Player2));
+        assert!(StandardRules.tiebreak_due(6, 6, &set));
+    }
+
+    #[test]
+    fn test_standard_tiebreak_winner() {
+        let config = MatchConfig::default();
+        let set = SetContext {
+            config: &config,
+            is_final_set: false,
+            kind: SetKind::Regular,
+        };
+        assert_eq!(
+            StandardRules.tiebreak_winner(7, 5, 7, &set),
+            Some(Player::Player1)
+        );
+        assert_eq!(StandardRules.tiebreak_winner(7, 6, 7, &set), None);
+        assert_eq!(
+            StandardRules.tiebreak_winner(10, 12, 7, &set),
+            Some(Player::Player2)
+        );
+    }
+
+    #[test]
+    fn test_custom_rules_drive_state_machine() {
+        let state = MatchState::new_with(MatchConfig::default(), &ShortSets);
+        let state = win_games(state, Player::Player1, 4, &ShortSets);
+
+        match &state {
+            MatchState::Playing {
+                player1_sets, sets, ..
+            } => {
+                assert_eq!(*player1_sets, 1);
+                assert_eq!(sets.len(), 2);
+            }
+            _ => panic!("match should still be in progress"),
+        }
+        assert_eq!(state.validate_with(&ShortSets), Ok(()));
+        // Under standard rules a 4-0 set is not over
+        assert!(state.validate().is_err());
+    }
+
+    #[test]
+    fn test_custom_rules_tiebreak() {
+        let mut state = MatchState::new_with(MatchConfig::default(), &ShortSets);
+        for _ in 0..3 {
+            state = win_games(state, Player::Player1, 1, &ShortSets);
+            state = win_games(state, Player::Player2, 1, &ShortSets);
+        }
+        match &state {
+            MatchState::Playing { sets, .. } => assert!(matches!(
+                sets.last(),
+                Some(crate::set::SetState::Playing {
+                    tiebreak: Some(_),
+                    ..
+                })
+            )),
+            _ => panic!("match should still be in progress"),
+        }
+    }
+}
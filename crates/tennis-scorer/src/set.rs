@@ -1,4 +1,7 @@
+use crate::config::MatchConfig;
 use crate::game::GameState;
+use crate::invariant::InvariantError;
+use crate::rules::{ScoringRules, SetContext, SetKind, StandardRules};
 use crate::tiebreak::TiebreakState;
 use crate::types::Player;
 
@@ -14,6 +17,9 @@ pub enum SetState {
         winner: Player,
         player1_games: u8,
         player2_games: u8,
+        /// Final points of the tiebreak that decided the set, as
+        /// (player1, player2).
+        tiebreak_score: Option<(u8, u8)>,
     },
 }
 
@@ -27,6 +33,25 @@ impl SetState {
         }
     }
 
+    /// A fresh set under `rules`, already in a tiebreak if the set is played
+    /// as one (e.g. a match tiebreak).
+    pub fn new_with(rules: &dyn ScoringRules, set: &SetContext) -> Self {
+        let tiebreak = rules
+            .set_starts_in_tiebreak(set)
+            .then(|| TiebreakState::new(rules.tiebreak_target(set)));
+        let current_game = match tiebreak {
+            Some(_) => GameState::new(),
+            None => rules.new_game(set),
+        };
+        SetState::Playing {
+            player1_games: 0,
+            player2_games: 0,
+            current_game,
+            tiebreak,
+        }
+    }
+
+    /// Score a point under standard rules with the given options.
     pub fn score_point(
         &self,
         scorer: Player,
@@ -34,6 +59,26 @@ impl SetState {
         tiebreak_points: u8,
         is_final_set: bool,
         final_set_tiebreak: bool,
+    ) -> SetState {
+        let config = MatchConfig {
+            no_ad_scoring: no_ad,
+            tiebreak_points,
+            final_set_tiebreak,
+            ..MatchConfig::default()
+        };
+        let set = SetContext {
+            config: &config,
+            is_final_set,
+            kind: SetKind::Regular,
+        };
+        self.score_point_with(scorer, &StandardRules, &set)
+    }
+
+    pub fn score_point_with(
+        &self,
+        scorer: Player,
+        rules: &dyn ScoringRules,
+        set: &SetContext,
     ) -> SetState {
         match self {
             SetState::Completed { .. } => self.clone(),
@@ -45,7 +90,7 @@ impl SetState {
                 tiebreak,
             } => {
                 if let Some(tb) = tiebreak {
-                    let new_tb = tb.score_point(scorer);
+                    let new_tb = tb.score_point_with(scorer, rules, set);
                     if let Some(winner) = new_tb.winner() {
                         let (final_p1, final_p2) = match winner {
                             Player::Player1 => (player1_games + 1, *player2_games),
@@ -55,6 +100,10 @@ impl SetState {
                             winner,
                             player1_games: final_p1,
                             player2_games: final_p2,
+                            tiebreak_score: tb.points().map(|(p1, p2)| match scorer {
+                                Player::Player1 => (p1 + 1, p2),
+                                Player::Player2 => (p1, p2 + 1),
+                            }),
                         }
                     } else {
                         SetState::Playing {
@@ -65,7 +114,7 @@ impl SetState {
                         }
                     }
                 } else {
-                    let new_game = current_game.score_point(scorer, no_ad);
+                    let new_game = rules.score_game_point(current_game, scorer, set);
 
                     if let Some(game_winner) = new_game.winner() {
                         let (new_p1, new_p2) = match game_winner {
@@ -73,30 +122,26 @@ impl SetState {
                             Player::Player2 => (*player1_games, player2_games + 1),
                         };
 
-                        if let Some(set_winner) =
-                            Self::check_set_winner(new_p1, new_p2, is_final_set, final_set_tiebreak)
-                        {
+                        if let Some(set_winner) = rules.set_winner(new_p1, new_p2, set) {
                             SetState::Completed {
                                 winner: set_winner,
                                 player1_games: new_p1,
                                 player2_games: new_p2,
-                            }
-                        } else if new_p1 == 6
-                            && new_p2 == 6
-                            && (!is_final_set || final_set_tiebreak)
-                        {
-                            SetState::Playing {
-                                player1_games: new_p1,
-                                player2_games: new_p2,
-                                current_game: GameState::new(),
-                                tiebreak: Some(TiebreakState::new(tiebreak_points)),
+                                tiebreak_score: None,
                             }
                         } else {
+                            let tiebreak = rules
+                                .tiebreak_due(new_p1, new_p2, set)
+                                .then(|| TiebreakState::new(rules.tiebreak_target(set)));
+                            let current_game = match tiebreak {
+                                Some(_) => GameState::new(),
+                                None => rules.new_game(set),
+                            };
                             SetState::Playing {
                                 player1_games: new_p1,
                                 player2_games: new_p2,
-                                current_game: GameState::new(),
-                                tiebreak: None,
+                                current_game,
+                                tiebreak,
                             }
                         }
                     } else {
@@ -112,32 +157,172 @@ impl SetState {
         }
     }
 
-    fn check_set_winner(
-        p1_games: u8,
-        p2_games: u8,
-        _is_final_set: bool,
-        _final_set_tiebreak: bool,
-    ) -> Option<Player> {
-        let leader = p1_games.max(p2_games);
-        let trailer = p1_games.min(p2_games);
-        let lead = leader - trailer;
-
-        if (leader >= 6 && lead >= 2) || (leader == 7 && trailer == 6) {
-            if p1_games > p2_games {
-                Some(Player::Player1)
-            } else {
-                Some(Player::Player2)
+    pub fn winner(&self) -> Option<Player> {
+        match self {
+            SetState::Completed { winner, .. } => Some(*winner),
+            _ => None,
+        }
+    }
+
+    /// Games won as (player1, player2), a tiebreak counting as a game.
+    pub fn games(&self) -> (u8, u8) {
+        match self {
+            SetState::Playing {
+                player1_games,
+                player2_games,
+                ..
             }
-        } else {
-            None
+            | SetState::Completed {
+                player1_games,
+                player2_games,
+                ..
+            } => (*player1_games, *player2_games),
         }
     }
 
-    pub fn winner(&self) -> Option<Player> {
+    /// Check the set against the rules it is played under: games that do not
+    /// already decide it, a tiebreak exactly when one is due, and a final
+    /// score (with tiebreak points, if recorded) that can actually occur.
+    pub fn validate(&self, config: &MatchConfig, is_final_set: bool) -> Result<(), InvariantError> {
+        let set = SetContext {
+            config,
+            is_final_set,
+            kind: SetKind::Regular,
+        };
+        self.validate_with(&StandardRules, &set)
+    }
+
+    /// As [`validate`](Self::validate), under `rules`.
+    pub fn validate_with(
+        &self,
+        rules: &dyn ScoringRules,
+        set: &SetContext,
+    ) -> Result<(), InvariantError> {
+        let target = rules.tiebreak_target(set);
+        let starts_in_tiebreak = rules.set_starts_in_tiebreak(set);
+
         match self {
-            SetState::Completed { winner, .. } => Some(*winner),
-            _ => None,
+            SetState::Playing {
+                player1_games,
+                player2_games,
+                current_game,
+                tiebreak,
+            } => {
+                let (p1, p2) = (*player1_games, *player2_games);
+                let reachable = if starts_in_tiebreak {
+                    (p1, p2) == (0, 0)
+                } else {
+                    Self::games_reachable(rules, set, p1, p2)
+                        && rules.set_winner(p1, p2, set).is_none()
+                };
+                if !reachable {
+                    return Err(InvariantError::SetAlreadyDecided {
+                        player1_games: p1,
+                        player2_games: p2,
+                    });
+                }
+                let tiebreak_due = starts_in_tiebreak || rules.tiebreak_due(p1, p2, set);
+                if tiebreak_due != tiebreak.is_some() {
+                    return Err(InvariantError::TiebreakMismatch {
+                        player1_games: p1,
+                        player2_games: p2,
+                    });
+                }
+
+                match tiebreak {
+                    Some(tb) => {
+                        tb.validate_with(rules, set)?;
+                        match tb {
+                            TiebreakState::Playing { target_points, .. }
+                                if *target_points == target => {}
+                            _ => return Err(InvariantError::InvalidTiebreak(tb.clone())),
+                        }
+                        if *current_game != GameState::new() {
+                            return Err(InvariantError::InvalidGame(current_game.clone()));
+                        }
+                    }
+                    None => {
+                        rules.validate_game(current_game, set)?;
+                        if current_game.winner().is_some() {
+                            return Err(InvariantError::InvalidGame(current_game.clone()));
+                        }
+                    }
+                }
+                Ok(())
+            }
+            SetState::Completed {
+                winner,
+                player1_games,
+                player2_games,
+                tiebreak_score,
+            } => {
+                let (p1, p2) = (*player1_games, *player2_games);
+                // Games before the set's last game (or tiebreak) was won
+                let before = match winner {
+                    Player::Player1 => p1.checked_sub(1).map(|g| (g, p2)),
+                    Player::Player2 => p2.checked_sub(1).map(|g| (p1, g)),
+                };
+                let (by_games, by_tiebreak) = match before {
+                    None => (false, false),
+                    Some((b1, b2)) if starts_in_tiebreak => (false, (b1, b2) == (0, 0)),
+                    Some((b1, b2)) => {
+                        let reached = Self::games_reachable(rules, set, b1, b2)
+                            && rules.set_winner(b1, b2, set).is_none();
+                        let in_tiebreak = rules.tiebreak_due(b1, b2, set);
+                        (
+                            reached
+                                && !in_tiebreak
+                                && rules.set_winner(p1, p2, set) == Some(*winner),
+                            reached && in_tiebreak,
+                        )
+                    }
+                };
+                let tiebreak_ok = match tiebreak_score {
+                    None => true,
+                    Some((tb1, tb2)) => {
+                        let (tb1, tb2) = (*tb1, *tb2);
+                        let previous = match winner {
+                            Player::Player1 => tb1.checked_sub(1).map(|t| (t, tb2)),
+                            Player::Player2 => tb2.checked_sub(1).map(|t| (tb1, t)),
+                        };
+                        by_tiebreak
+                            && rules.tiebreak_winner(tb1, tb2, target, set) == Some(*winner)
+                            && previous.is_some_and(|(t1, t2)| {
+                                rules.tiebreak_winner(t1, t2, target, set).is_none()
+                            })
+                    }
+                };
+                if (by_games || by_tiebreak) && tiebreak_ok {
+                    Ok(())
+                } else {
+                    Err(InvariantError::InvalidSetResult {
+                        player1_games: p1,
+                        player2_games: p2,
+                    })
+                }
+            }
+        }
+    }
+
+    /// Whether games `p1`-`p2` can be reached from 0-0 without the set being
+    /// won or a tiebreak starting on the way.
+    fn games_reachable(rules: &dyn ScoringRules, set: &SetContext, p1: u8, p2: u8) -> bool {
+        let (rows, cols) = (p1 as usize + 1, p2 as usize + 1);
+        // open[a][b]: reachable, undecided and not in a tiebreak, so the next
+        // game can be played from it
+        let mut open = vec![vec![false; cols]; rows];
+        let mut reached = false;
+        for a in 0..rows {
+            for b in 0..cols {
+                reached =
+                    (a, b) == (0, 0) || (a > 0 && open[a - 1][b]) || (b > 0 && open[a][b - 1]);
+                let (ga, gb) = (a as u8, b as u8);
+                open[a][b] = reached
+                    && rules.set_winner(ga, gb, set).is_none()
+                    && !rules.tiebreak_due(ga, gb, set);
+            }
         }
+        reached
     }
 }
 
@@ -148,6 +333,7 @@ impl Default for SetState {
 }
 
 #[cfg(test)]
+#[allow(clippy::collapsible_if)]
 mod tests {
     use super::*;
 
@@ -190,7 +376,8 @@ mod tests {
             SetState::Completed {
                 winner: Player::Player1,
                 player1_games: 6,
-                player2_games: 4
+                player2_games: 4,
+                ..
             }
         ));
     }
@@ -210,7 +397,8 @@ mod tests {
             SetState::Completed {
                 winner: Player::Player1,
                 player1_games: 7,
-                player2_games: 5
+                player2_games: 5,
+                ..
             }
         ));
     }
@@ -249,14 +437,15 @@ mod tests {
             set = set.score_point(Player::Player1, false, 7, false, true);
         }
 
-        assert!(matches!(
+        assert_eq!(
             set,
             SetState::Completed {
                 winner: Player::Player1,
                 player1_games: 7,
-                player2_games: 6
+                player2_games: 6,
+                tiebreak_score: Some((7, 0)),
             }
-        ));
+        );
     }
 
     #[test]
@@ -265,6 +454,7 @@ mod tests {
             winner: Player::Player1,
             player1_games: 6,
             player2_games: 4,
+            tiebreak_score: None,
         };
         let set = set.score_point(Player::Player2, false, 7, false, true);
 
@@ -276,4 +466,183 @@ mod tests {
             }
         ));
     }
+
+    #[test]
+    fn test_validate_playing_set() {
+        let config = MatchConfig::default();
+        let playing = |p1, p2, tiebreak| SetState::Playing {
+            player1_games: p1,
+            player2_games: p2,
+            current_game: GameState::new(),
+            tiebreak,
+        };
+        assert!(playing(5, 5, None).validate(&config, false).is_ok());
+        assert!(
+            playing(6, 6, Some(TiebreakState::new(7)))
+                .validate(&config, false)
+                .is_ok()
+        );
+
+        assert_eq!(
+            playing(9, 2, None).validate(&config, false),
+            Err(InvariantError::SetAlreadyDecided {
+                player1_games: 9,
+                player2_games: 2,
+            })
+        );
+        assert_eq!(
+            playing(6, 6, None).validate(&config, false),
+            Err(InvariantError::TiebreakMismatch {
+                player1_games: 6,
+                player2_games: 6,
+            })
+        );
+        assert!(
+            playing(6, 6, Some(TiebreakState::new(10)))
+                .validate(&config, false)
+                .is_err()
+        );
+        let finished_game = SetState::Playing {
+            player1_games: 1,
+            player2_games: 0,
+            current_game: GameState::Completed(Player::Player1),
+            tiebreak: None,
+        };
+        assert!(finished_game.validate(&config, false).is_err());
+    }
+
+    #[test]
+    fn test_validate_advantage_final_set() {
+        let config = MatchConfig {
+            final_set_tiebreak: false,
+            ..MatchConfig::default()
+        };
+        let set = SetState::Playing {
+            player1_games: 8,
+            player2_games: 8,
+            current_game: GameState::new(),
+            tiebreak: None,
+        };
+        assert!(set.validate(&config, true).is_ok());
+        assert!(set.validate(&config, false).is_err());
+
+        let set = SetState::Completed {
+            winner: Player::Player2,
+            player1_games: 10,
+            player2_games: 12,
+            tiebreak_score: None,
+        };
+        assert!(set.validate(&config, true).is_ok());
+    }
+
+    #[test]
+    fn test_validate_completed_set() {
+        let config = MatchConfig::default();
+        let completed = |winner, p1, p2, tiebreak_score| SetState::Completed {
+            winner,
+            player1_games: p1,
+            player2_games: p2,
+            tiebreak_score,
+        };
+        assert!(
+            completed(Player::Player1, 6, 4, None)
+                .validate(&config, false)
+                .is_ok()
+        );
+        assert!(
+            completed(Player::Player2, 6, 7, Some((10, 12)))
+                .validate(&config, false)
+                .is_ok()
+        );
+
+        // No winning margin
+        assert_eq!(
+            completed(Player::Player1, 6, 5, None).validate(&config, false),
+            Err(InvariantError::InvalidSetResult {
+                player1_games: 6,
+                player2_games: 5,
+            })
+        );
+        // Winner with fewer games
+        assert!(
+            completed(Player::Player2, 6, 4, None)
+                .validate(&config, false)
+                .is_err()
+        );
+        // Tiebreak that would have ended earlier
+        assert!(
+            completed(Player::Player1, 7, 6, Some((9, 4)))
+                .validate(&config, false)
+                .is_err()
+        );
+        // Tiebreak points on a set decided by games
+        assert!(
+            completed(Player::Player1, 6, 3, Some((7, 3)))
+                .validate(&config, false)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_handicap_seeds_each_game() {
+        let config = MatchConfig {
+            handicap: crate::config::Handicap {
+                player1_points: -1,
+                player2_points: 2,
+                ..Default::default()
+            },
+            ..MatchConfig::default()
+        };
+        let set = SetContext {
+            config: &config,
+            is_final_set: false,
+            kind: SetKind::Regular,
+        };
+        let state = SetState::new_with(&StandardRules, &set);
+        let SetState::Playing { current_game, .. } = &state else {
+            panic!("set should be in progress");
+        };
+        assert_eq!(current_game.to_string(), "-15-30");
+
+        // Player 2 needs two points to win the game; the next starts the same
+        let state = state
+            .score_point_with(Player::Player2, &StandardRules, &set)
+            .score_point_with(Player::Player2, &StandardRules, &set);
+        match &state {
+            SetState::Playing {
+                player2_games,
+                current_game,
+                ..
+            } => {
+                assert_eq!(*player2_games, 1);
+                assert_eq!(current_game.to_string(), "-15-30");
+            }
+            _ => panic!("set should be in progress"),
+        }
+    }
+
+    #[test]
+    fn test_owed_points_are_worked_off() {
+        let config = MatchConfig {
+            handicap: crate::config::Handicap {
+                player1_points: -2,
+                player2_points: 3,
+                ..Default::default()
+            },
+            ..MatchConfig::default()
+        };
+        let set = SetContext {
+            config: &config,
+            is_final_set: false,
+            kind: SetKind::Regular,
+        };
+        let mut state = SetState::new_with(&StandardRules, &set);
+        for _ in 0..5 {
+            state = state.score_point_with(Player::Player1, &StandardRules, &set);
+        }
+        let SetState::Playing { current_game, .. } = &state else {
+            panic!("set should be in progress");
+        };
+        assert_eq!(*current_game, GameState::Deuce { count: 1 });
+    }
 }
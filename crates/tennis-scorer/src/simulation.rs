@@ -0,0 +1,481 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::time::{Duration, SystemTime};
+
+use serde::{Deserialize, Serialize};
+
+use crate::match_state::MatchState;
+use crate::rules::{ScoringRules, StandardRules};
+use crate::types::Player;
+
+/// How one player (or doubles team) plays in a simulation.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct PlayerModel {
+    /// Chance of winning a point on their own serve, between 0 and 1.
+    pub serve_win: f64,
+    /// Added to their chance of winning a pressure point (break point, or a
+    /// point that can decide a set or the match), whoever serves it.
+    /// Negative for players who tighten up.
+    #[serde(default)]
+    pub pressure: f64,
+}
+
+/// Gaps between simulated points.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct PointTiming {
+    pub average_secs: f64,
+    /// Each gap varies uniformly by up to this much either way.
+    pub jitter_secs: f64,
+    /// Added after odd-numbered games, when players change ends.
+    pub changeover_secs: f64,
+}
+
+impl Default for PointTiming {
+    fn default() -> Self {
+        Self {
+            average_secs: 40.0,
+            jitter_secs: 15.0,
+            changeover_secs: 90.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SimulationConfig {
+    pub player1: PlayerModel,
+    pub player2: PlayerModel,
+    /// The same seed always plays out the same points.
+    pub seed: u64,
+    /// Timestamp of the first simulated point.
+    pub start_time: SystemTime,
+    pub timing: PointTiming,
+}
+
+impl Default for SimulationConfig {
+    fn default() -> Self {
+        let even = PlayerModel {
+            serve_win: 0.62,
+            pressure: 0.0,
+        };
+        Self {
+            player1: even,
+            player2: even,
+            seed: 0,
+            start_time: SystemTime::UNIX_EPOCH,
+            timing: PointTiming::default(),
+        }
+    }
+}
+
+/// Points a single run may play before it is abandoned, well short of the
+/// games a set can count.
+pub const MAX_SIMULATED_POINTS: usize = 1_000;
+
+/// Points a single game or tiebreak may last before the run is abandoned,
+/// well short of the points a tiebreak can count.
+pub const MAX_SIMULATED_GAME_POINTS: usize = 100;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SimulationError {
+    /// The player's serve-win chance is not between 0 and 1, or their
+    /// pressure adjustment is not a finite number.
+    InvalidModel(Player),
+    /// A point timing is negative or not a finite number, or the gaps run
+    /// past the last time a timestamp can hold.
+    InvalidTiming,
+    /// A run went past `MAX_SIMULATED_POINTS`, or a game or tiebreak past
+    /// `MAX_SIMULATED_GAME_POINTS`, as when two players who never lose a
+    /// service point meet in an uncapped tiebreak.
+    PointLimit,
+}
+
+impl fmt::Display for SimulationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SimulationError::InvalidModel(player) => {
+                write!(f, "the model for {player:?} is not a valid probability")
+            }
+            SimulationError::InvalidTiming => write!(f, "the point timing is not valid"),
+            SimulationError::PointLimit => write!(f, "the match went on too long to simulate"),
+        }
+    }
+}
+
+impl std::error::Error for SimulationError {}
+
+/// One simulated match. `events` can be replayed with
+/// `MatchWithHistory::from_events` from the starting state.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SimulatedMatch {
+    pub events: Vec<(Player, SystemTime)>,
+    pub final_state: MatchState,
+}
+
+/// How often a final score came up.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScoreLineCount {
+    pub score: String,
+    pub count: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SimulationSummary {
+    pub runs: u32,
+    pub player1_wins: u32,
+    pub player1_win_probability: f64,
+    pub average_points: f64,
+    /// Final scores, most common first.
+    pub score_lines: Vec<ScoreLineCount>,
+}
+
+/// Play `start` to completion. Time limits are not simulated. A run is
+/// abandoned once it runs too long (see `SimulationError::PointLimit`).
+pub fn simulate_match(
+    start: &MatchState,
+    config: &SimulationConfig,
+) -> Result<SimulatedMatch, SimulationError> {
+    simulate_match_with(start, config, &StandardRules)
+}
+
+/// Like `simulate_match`, scoring each point under `rules`.
+pub fn simulate_match_with(
+    start: &MatchState,
+    config: &SimulationConfig,
+    rules: &dyn ScoringRules,
+) -> Result<SimulatedMatch, SimulationError> {
+    validate(config)?;
+    play_out(start, config, rules, &mut Rng::new(config.seed))
+}
+
+/// Play `start` to completion `runs` times and tally the results. Runs
+/// draw from one random stream seeded with `config.seed`. Fails if any
+/// run does.
+pub fn simulate_many(
+    start: &MatchState,
+    config: &SimulationConfig,
+    runs: u32,
+) -> Result<SimulationSummary, SimulationError> {
+    simulate_many_with(start, config, runs, &StandardRules)
+}
+
+/// Like `simulate_many`, scoring each point under `rules`.
+pub fn simulate_many_with(
+    start: &MatchState,
+    config: &SimulationConfig,
+    runs: u32,
+    rules: &dyn ScoringRules,
+) -> Result<SimulationSummary, SimulationError> {
+    validate(config)?;
+    let mut rng = Rng::new(config.seed);
+    let mut player1_wins = 0;
+    let mut total_points = 0usize;
+    let mut counts: HashMap<String, u32> = HashMap::new();
+
+    for _ in 0..runs {
+        let simulated = play_out(start, config, rules, &mut rng)?;
+        if simulated.final_state.winner() == Some(Player::Player1) {
+            player1_wins += 1;
+        }
+        total_points += simulated.events.len();
+        let score = simulated.final_state.display_with(rules).to_string();
+        *counts.entry(score).or_default() += 1;
+    }
+
+    let mut score_lines: Vec<ScoreLineCount> = counts
+        .into_iter()
+        .map(|(score, count)| ScoreLineCount { score, count })
+        .collect();
+    score_lines.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.score.cmp(&b.score)));
+
+    let (player1_win_probability, average_points) = if runs > 0 {
+        (
+            player1_wins as f64 / runs as f64,
+            total_points as f64 / runs as f64,
+        )
+    } else {
+        (0.0, 0.0)
+    };
+
+    Ok(SimulationSummary {
+        runs,
+        player1_wins,
+        player1_win_probability,
+        average_points,
+        score_lines,
+    })
+}
+
+fn validate(config: &SimulationConfig) -> Result<(), SimulationError> {
+    for (player, model) in [
+        (Player::Player1, &config.player1),
+        (Player::Player2, &config.player2),
+    ] {
+        if !(0.0..=1.0).contains(&model.serve_win) || !model.pressure.is_finite() {
+            return Err(SimulationError::InvalidModel(player));
+        }
+    }
+    let timing = &config.timing;
+    if [
+        timing.average_secs,
+        timing.jitter_secs,
+        timing.changeover_secs,
+    ]
+    .iter()
+    .any(|secs| !(secs.is_finite() && *secs >= 0.0))
+    {
+        return Err(SimulationError::InvalidTiming);
+    }
+    Ok(())
+}
+
+fn play_out(
+    start: &MatchState,
+    config: &SimulationConfig,
+    rules: &dyn ScoringRules,
+    rng: &mut Rng,
+) -> Result<SimulatedMatch, SimulationError> {
+    let mut state = start.clone();
+    let mut events = Vec::new();
+    let mut timestamp = config.start_time;
+    let mut game_points = 0;
+
+    while state.winner().is_none() {
+        if events.len() == MAX_SIMULATED_POINTS || game_points == MAX_SIMULATED_GAME_POINTS {
+            return Err(SimulationError::PointLimit);
+        }
+        let server = state.serving_player().unwrap_or(Player::Player1);
+        let mut player1_wins = match server {
+            Player::Player1 => config.player1.serve_win,
+            Player::Player2 => 1.0 - config.player2.serve_win,
+        };
+        if is_pressure_point(&state, server, rules) {
+            player1_wins += config.player1.pressure - config.player2.pressure;
+        }
+        let scorer = if rng.next_f64() < player1_wins.clamp(0.0, 1.0) {
+            Player::Player1
+        } else {
+            Player::Player2
+        };
+
+        let games_before = games_played(&state);
+        events.push((scorer, timestamp));
+        state = state.score_point_with(scorer, rules);
+
+        let timing = &config.timing;
+        let mut gap = timing.average_secs + timing.jitter_secs * (2.0 * rng.next_f64() - 1.0);
+        let games = games_played(&state);
+        if games > games_before {
+            game_points = 0;
+            if games % 2 == 1 {
+                gap += timing.changeover_secs;
+            }
+        } else {
+            game_points += 1;
+        }
+        timestamp = Duration::try_from_secs_f64(gap.max(0.0))
+            .ok()
+            .and_then(|gap| timestamp.checked_add(gap))
+            .ok_or(SimulationError::InvalidTiming)?;
+    }
+
+    Ok(SimulatedMatch {
+        events,
+        final_state: state,
+    })
+}
+
+fn games_played(state: &MatchState) -> u16 {
+    let (p1, p2) = state.total_games();
+    p1 + p2
+}
+
+fn sets_won(state: &MatchState) -> (u8, u8) {
+    match state {
+        MatchState::Playing {
+            player1_sets,
+            player2_sets,
+            ..
+        }
+        | MatchState::Completed {
+            player1_sets,
+            player2_sets,
+            ..
+        } => (*player1_sets, *player2_sets),
+    }
+}
+
+/// A break point, or a point either player could close out a set on.
+fn is_pressure_point(state: &MatchState, server: Player, rules: &dyn ScoringRules) -> bool {
+    let returner = server.opponent();
+    let returner_games = |state: &MatchState| match returner {
+        Player::Player1 => state.total_games().0,
+        Player::Player2 => state.total_games().1,
+    };
+    let sets = sets_won(state);
+
+    let after_return = state.score_point_with(returner, rules);
+    returner_games(&after_return) > returner_games(state)
+        || sets_won(&after_return) != sets
+        || sets_won(&state.score_point_with(server, rules)) != sets
+}
+
+/// SplitMix64: small, fast and reproducible from a single seed.
+struct Rng(u64);
+
+impl Rng {
+    fn new(seed: u64) -> Self {
+        Rng(seed)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform in [0, 1).
+    fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::MatchConfig;
+    use crate::history::MatchWithHistory;
+    use crate::rules::TiebreakOnlyRules;
+
+    fn config(seed: u64, player1_serve: f64, player2_serve: f64) -> SimulationConfig {
+        SimulationConfig {
+            player1: PlayerModel {
+                serve_win: player1_serve,
+                pressure: 0.0,
+            },
+            player2: PlayerModel {
+                serve_win: player2_serve,
+                pressure: 0.0,
+            },
+            seed,
+            ..SimulationConfig::default()
+        }
+    }
+
+    #[test]
+    fn test_simulated_match_replays() {
+        let start = MatchState::new(MatchConfig::default());
+        let simulated = simulate_match(&start, &config(7, 0.65, 0.6)).unwrap();
+        assert!(simulated.final_state.winner().is_some());
+
+        let replayed = MatchWithHistory::from_events(start, &simulated.events).unwrap();
+        assert_eq!(replayed.current(), &simulated.final_state);
+
+        let gaps: Vec<Duration> = simulated
+            .events
+            .windows(2)
+            .map(|w| w[1].1.duration_since(w[0].1).unwrap())
+            .collect();
+        assert!(gaps.iter().all(|g| g.as_secs_f64() >= 25.0));
+        assert!(gaps.iter().any(|g| g.as_secs_f64() >= 115.0));
+    }
+
+    #[test]
+    fn test_same_seed_same_match() {
+        let start = MatchState::new(MatchConfig::default());
+        let a = simulate_match(&start, &config(42, 0.6, 0.6)).unwrap();
+        let b = simulate_match(&start, &config(42, 0.6, 0.6)).unwrap();
+        let c = simulate_match(&start, &config(43, 0.6, 0.6)).unwrap();
+        assert_eq!(a, b);
+        assert_ne!(a.events, c.events);
+    }
+
+    #[test]
+    fn test_certain_points() {
+        let start = MatchState::new(MatchConfig::default());
+        let simulated = simulate_match(&start, &config(1, 1.0, 0.0)).unwrap();
+        assert_eq!(simulated.final_state.to_string(), "6-0 6-0");
+        assert_eq!(simulated.events.len(), 48);
+    }
+
+    #[test]
+    fn test_many_runs_favour_stronger_server() {
+        let start = MatchState::new(MatchConfig::default());
+        let summary = simulate_many(&start, &config(3, 0.7, 0.6), 200).unwrap();
+        assert_eq!(summary.runs, 200);
+        assert!(summary.player1_win_probability > 0.75);
+        assert_eq!(
+            summary.score_lines.iter().map(|s| s.count).sum::<u32>(),
+            200
+        );
+        assert!(summary.score_lines[0].count >= summary.score_lines[1].count);
+        assert_eq!(
+            Ok(summary),
+            simulate_many(&start, &config(3, 0.7, 0.6), 200)
+        );
+    }
+
+    #[test]
+    fn test_from_position_and_pressure() {
+        // Player1 is a set and 5-0 up; choking on every pressure point
+        // still leaves them well ahead
+        let mut start = MatchState::new(MatchConfig::default());
+        for _ in 0..44 {
+            start = start.score_point(Player::Player1);
+        }
+        let mut choking = config(9, 0.62, 0.62);
+        choking.player1.pressure = -0.3;
+        let summary = simulate_many(&start, &choking, 100).unwrap();
+        assert!(summary.player1_win_probability > 0.5);
+        assert!(summary.player1_win_probability < 1.0);
+
+        let finished =
+            simulate_many(&MatchState::new(MatchConfig::default()), &choking, 0).unwrap();
+        assert_eq!(finished.player1_win_probability, 0.0);
+        assert!(finished.score_lines.is_empty());
+    }
+
+    #[test]
+    fn test_rejects_invalid_models() {
+        let start = MatchState::new(MatchConfig::default());
+        assert_eq!(
+            simulate_match(&start, &config(1, 1.5, 0.6)),
+            Err(SimulationError::InvalidModel(Player::Player1))
+        );
+        assert_eq!(
+            simulate_many(&start, &config(1, 0.6, f64::NAN), 10),
+            Err(SimulationError::InvalidModel(Player::Player2))
+        );
+        let mut slow = config(1, 0.6, 0.6);
+        slow.timing.average_secs = f64::INFINITY;
+        assert_eq!(
+            simulate_match(&start, &slow),
+            Err(SimulationError::InvalidTiming)
+        );
+    }
+
+    #[test]
+    fn test_endless_play_hits_point_limit() {
+        // Neither player ever loses a service point, so an uncapped
+        // tiebreak or an advantage set never ends
+        let start = MatchState::new(MatchConfig::tiebreaks(7, 1));
+        assert_eq!(
+            simulate_match_with(&start, &config(1, 1.0, 1.0), &TiebreakOnlyRules),
+            Err(SimulationError::PointLimit)
+        );
+        assert_eq!(
+            simulate_many_with(&start, &config(1, 1.0, 1.0), 3, &TiebreakOnlyRules),
+            Err(SimulationError::PointLimit)
+        );
+        let advantage_sets = MatchConfig {
+            final_set_tiebreak: false,
+            sets_to_win: 1,
+            ..MatchConfig::default()
+        };
+        assert_eq!(
+            simulate_match(&MatchState::new(advantage_sets), &config(1, 1.0, 1.0)),
+            Err(SimulationError::PointLimit)
+        );
+    }
+}
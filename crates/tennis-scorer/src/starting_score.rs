@@ -0,0 +1,187 @@
+use std::fmt;
+
+use crate::game::GameState;
+use crate::match_state::MatchState;
+use crate::set::SetState;
+use crate::types::Player;
+
+/// Points in the game being played when scoring is picked up mid-match.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StartingPoints {
+    Game(GameState),
+    Tiebreak { player1: u8, player2: u8 },
+}
+
+/// A position to start scoring from, e.g. "6-4 3-2, 30-15, Player2 serving".
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StartingScore {
+    /// Games of each completed set, as (player1, player2).
+    pub completed_sets: Vec<(u8, u8)>,
+    /// Games in the set currently being played, as (player1, player2).
+    pub games: (u8, u8),
+    pub points: StartingPoints,
+    /// Player (or team, in doubles) serving the next point.
+    pub server: Player,
+    /// Position of the server within their team, as in `serve_order`.
+    /// Ignored in singles.
+    pub server_position: u8,
+}
+
+impl StartingScore {
+    /// The position `state` has reached, e.g. to continue scoring the match
+    /// on another device. Returns `None` once the match is over.
+    pub fn from_state(state: &MatchState) -> Option<StartingScore> {
+        let MatchState::Playing { sets, config, .. } = state else {
+            return None;
+        };
+        let (current, completed) = sets.split_last()?;
+        let completed_sets = completed
+            .iter()
+            .map(|set| match set {
+                SetState::Completed {
+                    player1_games,
+                    player2_games,
+                    ..
+                }
+                | SetState::Playing {
+                    player1_games,
+                    player2_games,
+                    ..
+                } => (*player1_games, *player2_games),
+            })
+            .collect();
+        let SetState::Playing {
+            player1_games,
+            player2_games,
+            current_game,
+            tiebreak,
+        } = current
+        else {
+            return None;
+        };
+        let points = match tiebreak.as_ref().and_then(|tb| tb.points()) {
+            Some((player1, player2)) => StartingPoints::Tiebreak { player1, player2 },
+            None => StartingPoints::Game(current_game.clone()),
+        };
+        let (server, server_position) = if config.serve_order.is_empty() {
+            (state.serving_player()?, 0)
+        } else {
+            config.serve_order[state.current_server() as usize]
+        };
+        Some(StartingScore {
+            completed_sets,
+            games: (*player1_games, *player2_games),
+            points,
+            server,
+            server_position,
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StartingScoreError {
+    /// A completed set score that cannot occur under the match config.
+    InvalidSetScore {
+        set_number: usize,
+        player1_games: u8,
+        player2_games: u8,
+    },
+    /// One side has already won enough sets to take the match.
+    MatchAlreadyDecided,
+    /// The current set's games cannot occur in an unfinished set.
+    InvalidGameCount {
+        player1_games: u8,
+        player2_games: u8,
+    },
+    /// The current game score cannot occur, or a regular game was given
+    /// where a tiebreak is being played.
+    InvalidGameScore,
+    /// Tiebreak points were given where no tiebreak is being played, or the
+    /// tiebreak would already be over.
+    InvalidTiebreakScore {
+        player1_points: u8,
+        player2_points: u8,
+    },
+    /// The server does not appear in the config's serve order.
+    UnknownServer { player: Player, position: u8 },
+}
+
+impl fmt::Display for StartingScoreError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StartingScoreError::InvalidSetScore {
+                set_number,
+                player1_games,
+                player2_games,
+            } => write!(
+                f,
+                "set {set_number} cannot finish {player1_games}-{player2_games}"
+            ),
+            StartingScoreError::MatchAlreadyDecided => {
+                write!(f, "the completed sets already decide the match")
+            }
+            StartingScoreError::InvalidGameCount {
+                player1_games,
+                player2_games,
+            } => write!(
+                f,
+                "{player1_games}-{player2_games} is not an unfinished set score"
+            ),
+            StartingScoreError::InvalidGameScore => write!(f, "impossible game score"),
+            StartingScoreError::InvalidTiebreakScore {
+                player1_points,
+                player2_points,
+            } => write!(
+                f,
+                "{player1_points}-{player2_points} is not an unfinished tiebreak score"
+            ),
+            StartingScoreError::UnknownServer { player, position } => write!(
+                f,
+                "{player:?} position {position} is not in the serve order"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for StartingScoreError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::MatchConfig;
+
+    #[test]
+    fn test_from_state_round_trips_through_from_score() {
+        let mut state = MatchState::new(MatchConfig::default());
+        // 6-0, then 2-1 and 15-30
+        for _ in 0..24 {
+            state = state.score_point(Player::Player1);
+        }
+        for scorer in [Player::Player1; 8].into_iter().chain([Player::Player2; 4]) {
+            state = state.score_point(scorer);
+        }
+        for scorer in [Player::Player2, Player::Player1, Player::Player2] {
+            state = state.score_point(scorer);
+        }
+
+        let score = StartingScore::from_state(&state).unwrap();
+        assert_eq!(score.completed_sets, vec![(6, 0)]);
+        assert_eq!(score.games, (2, 1));
+        assert_eq!(score.server, Player::Player2);
+
+        let rebuilt = MatchState::from_score(MatchConfig::default(), &score).unwrap();
+        assert_eq!(rebuilt.to_string(), state.to_string());
+    }
+
+    #[test]
+    fn test_from_state_completed_match() {
+        let state = MatchState::Completed {
+            winner: Player::Player1,
+            player1_sets: 2,
+            player2_sets: 0,
+            sets: vec![],
+            config: MatchConfig::default(),
+        };
+        assert_eq!(StartingScore::from_state(&state), None);
+    }
+}
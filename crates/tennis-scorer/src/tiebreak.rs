@@ -1,3 +1,6 @@
+use crate::config::MatchConfig;
+use crate::invariant::InvariantError;
+use crate::rules::{ScoringRules, SetContext, SetKind, StandardRules};
 use crate::types::Player;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -19,7 +22,24 @@ impl TiebreakState {
         }
     }
 
+    /// Score a point under standard tiebreak rules (two-point margin).
     pub fn score_point(&self, scorer: Player) -> TiebreakState {
+        let config = MatchConfig::default();
+        let set = SetContext {
+            config: &config,
+            is_final_set: false,
+            kind: SetKind::Regular,
+        };
+        self.score_point_with(scorer, &StandardRules, &set)
+    }
+
+    /// Score a point, with `rules` deciding when the tiebreak is won.
+    pub fn score_point_with(
+        &self,
+        scorer: Player,
+        rules: &dyn ScoringRules,
+        set: &SetContext,
+    ) -> TiebreakState {
         match self {
             TiebreakState::Completed(_) => self.clone(),
 
@@ -33,23 +53,13 @@ impl TiebreakState {
                     Player::Player2 => (*player1_points, player2_points + 1),
                 };
 
-                let leader_points = new_p1.max(new_p2);
-                let trailer_points = new_p1.min(new_p2);
-                let lead = leader_points - trailer_points;
-
-                if leader_points >= *target_points && lead >= 2 {
-                    let winner = if new_p1 > new_p2 {
-                        Player::Player1
-                    } else {
-                        Player::Player2
-                    };
-                    TiebreakState::Completed(winner)
-                } else {
-                    TiebreakState::Playing {
+                match rules.tiebreak_winner(new_p1, new_p2, *target_points, set) {
+                    Some(winner) => TiebreakState::Completed(winner),
+                    None => TiebreakState::Playing {
                         player1_points: new_p1,
                         player2_points: new_p2,
                         target_points: *target_points,
-                    }
+                    },
                 }
             }
         }
@@ -61,6 +71,51 @@ impl TiebreakState {
             _ => None,
         }
     }
+
+    /// Check an unfinished tiebreak has a target and has not already been won.
+    pub fn validate(&self) -> Result<(), InvariantError> {
+        let config = MatchConfig::default();
+        let set = SetContext {
+            config: &config,
+            is_final_set: false,
+            kind: SetKind::Regular,
+        };
+        self.validate_with(&StandardRules, &set)
+    }
+
+    /// As [`validate`](Self::validate), with `rules` deciding when a
+    /// tiebreak is won.
+    pub fn validate_with(
+        &self,
+        rules: &dyn ScoringRules,
+        set: &SetContext,
+    ) -> Result<(), InvariantError> {
+        if let TiebreakState::Playing {
+            player1_points,
+            player2_points,
+            target_points,
+        } = self
+            && (*target_points == 0
+                || rules
+                    .tiebreak_winner(*player1_points, *player2_points, *target_points, set)
+                    .is_some())
+        {
+            return Err(InvariantError::InvalidTiebreak(self.clone()));
+        }
+        Ok(())
+    }
+
+    /// Current points as (player1, player2), or `None` once completed.
+    pub fn points(&self) -> Option<(u8, u8)> {
+        match self {
+            TiebreakState::Playing {
+                player1_points,
+                player2_points,
+                ..
+            } => Some((*player1_points, *player2_points)),
+            TiebreakState::Completed(_) => None,
+        }
+    }
 }
 
 #[cfg(test)]
@@ -119,4 +174,23 @@ mod tests {
         let tb = tb.score_point(Player::Player2);
         assert_eq!(tb, TiebreakState::Completed(Player::Player1));
     }
+
+    #[test]
+    fn test_validate() {
+        let playing = |p1, p2| TiebreakState::Playing {
+            player1_points: p1,
+            player2_points: p2,
+            target_points: 7,
+        };
+        assert!(playing(6, 5).validate().is_ok());
+        assert!(playing(9, 8).validate().is_ok());
+        assert!(TiebreakState::Completed(Player::Player1).validate().is_ok());
+
+        assert_eq!(
+            playing(7, 5).validate(),
+            Err(InvariantError::InvalidTiebreak(playing(7, 5)))
+        );
+        assert!(playing(10, 7).validate().is_err());
+        assert!(TiebreakState::new(0).validate().is_err());
+    }
 }
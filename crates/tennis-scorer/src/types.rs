@@ -1,6 +1,6 @@
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum Player {
     Player1,
     Player2,
@@ -15,8 +15,17 @@ impl Player {
     }
 }
 
+/// The points side of a game score.
+///
+/// Marked `#[non_exhaustive]`: handicap play added the owed points below
+/// love, and other formats may add more, so code outside this crate
+/// matching on a `Point` needs a wildcard arm.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
 pub enum Point {
+    /// Owed points in handicap play, worked off before reaching love.
+    OweThirty,
+    OweFifteen,
     Love,
     Fifteen,
     Thirty,
@@ -26,6 +35,8 @@ pub enum Point {
 impl Point {
     pub fn increment(self) -> Option<Point> {
         match self {
+            Point::OweThirty => Some(Point::OweFifteen),
+            Point::OweFifteen => Some(Point::Love),
             Point::Love => Some(Point::Fifteen),
             Point::Fifteen => Some(Point::Thirty),
             Point::Thirty => Some(Point::Forty),